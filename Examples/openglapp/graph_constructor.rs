@@ -1,21 +1,43 @@
 //! The module creates a pretty graph without intersecting edges.
 
-use super::line::Line;
+use super::obstacle::Obstacle;
 use astar_lib::a_star::NavGraph;
+use astar_lib::line::Line;
 use astar_lib::vector::Vec2;
 use rand::seq::IteratorRandom;
 
 /// The maximum number of iterations we make per attempt for link generation
 const MAX_ITERATIONS: usize = 100000;
 
+/// The cut-off of the cluster distribution in multiples of the spread.
+const CLUSTER_CUT_OFF: f32 = 3.0;
+
+/// The minimum distance of two cluster centers in multiples of the spread.
+const CLUSTER_SEPARATION: f32 = 10.0;
+
+/// The strategies the links between the points can be generated with.
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum LinkStrategy {
+    /// Links random pairs of points within the maximum line length, replacing the links added before.
+    /// A candidate link gets rejected if it crosses another link, runs through an obstacle or too close to a point,
+    /// or if one of its points already has the maximum degree, which avoids unnaturally connected hubs.
+    /// Use usize::MAX for no degree limit.
+    RandomNonCrossing { max_degree: usize },
+    /// Adds the shortest valid links between different components to the links added before, until all points
+    /// form one connected component.
+    ConnectComponents,
+}
+
 /// Creates a graph.
 /// The nodes should have a certain minimum distance to each other.
 /// The edges should not intersect and there should be a minimum distance
-/// of a node to an edge.
+/// of a node to an edge. Edges may also not pass through obstacles.
 pub struct GraphConstructor {
     point_collection: Vec<Vec2>,
     point_pairing: Vec<(usize, usize)>,
-    extension: f32,
+    obstacles: Vec<Obstacle>,
+    min: Vec2,
+    max: Vec2,
     max_line_length: f32,
     exclusion_distance: f32,
     edge_distance: f32,
@@ -25,40 +47,119 @@ impl GraphConstructor {
     /// Generates a new graph constructor
     ///
     /// # Parameters
-    /// * **extension:** The graph will have coordinates ranging from -extension..extension
+    /// * **min:** The lower corner of the rectangle the coordinates of the graph range in.
+    /// * **max:** The upper corner of the rectangle the coordinates of the graph range in.
     /// * **max_line_length:** The maximum length and edge can have.
     /// * **exclusion_radius:** The outer radius of each node to keep distance.
     /// * **edge_distance:** The minimum distance a point to an edge if within the voronoi region of the edge.
     ///
+    /// # Panics
+    /// Panics if the lower corner is not strictly below the upper corner on both axes, as the random points
+    /// could not be placed in an empty rectangle.
+    ///
     /// # Example
     /// ```
     /// use astar_lib::graph_constructor::GraphConstructor;
-    /// let mut constructor = GraphConstructor::new(1.0, 0.3, 0.02, 0.01);
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
     /// ```
     pub fn new(
-        extension: f32,
+        min: [f32; 2],
+        max: [f32; 2],
         max_line_length: f32,
         exclusion_radius: f32,
         edge_distance: f32,
     ) -> GraphConstructor {
+        assert!(
+            min[0] < max[0] && min[1] < max[1],
+            "The lower corner has to be below the upper corner on both axes."
+        );
         let exclusion_distance = 2.0 * exclusion_radius;
         GraphConstructor {
             point_collection: vec![],
             point_pairing: vec![],
-            extension,
+            obstacles: vec![],
+            min: Vec2::from(min),
+            max: Vec2::from(max),
             max_line_length,
             exclusion_distance,
             edge_distance,
         }
     }
 
-    /// Fills the point array with random points.
+    /// Adds an axis aligned rectangular obstacle, that links may not pass through. The obstacles
+    /// are kept when a graph gets generated.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::graph_constructor::GraphConstructor;
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+    /// constructor.add_obstacle([-0.2, -0.2], [0.2, 0.2]);
+    /// ```
+    pub fn add_obstacle(&mut self, min: [f32; 2], max: [f32; 2]) {
+        self.obstacles
+            .push(Obstacle::new(Vec2::from(min), Vec2::from(max)));
+    }
+
+    /// Checks if the straight line between two positions is free. This is not the case if it passes
+    /// through an obstacle or if it passes within the edge distance of one of the points, which are
+    /// treated as blockers.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::graph_constructor::GraphConstructor;
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+    /// constructor.add_obstacle([-0.2, -0.2], [0.2, 0.2]);
+    /// assert!(!constructor.line_of_sight([-0.5, 0.0], [0.5, 0.0]));
+    /// ```
+    pub fn line_of_sight(&self, start: [f32; 2], end: [f32; 2]) -> bool {
+        let start = Vec2::from(start);
+        let end = Vec2::from(end);
+        if self
+            .obstacles
+            .iter()
+            .any(|obstacle| obstacle.blocks(start, end))
+        {
+            return false;
+        }
+
+        let line = Line::new(start, end);
+        !self
+            .point_collection
+            .iter()
+            .any(|point| line.is_in_critical_range(*point, self.edge_distance))
+    }
+
+    /// Gets the index of the first obstacle blocking the straight line from start to end, which is the
+    /// one the line enters first. The indices follow the sequence of [`GraphConstructor::add_obstacle`].
+    /// This helps understanding why a link has not been generated. Returns None if no obstacle blocks the line.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::graph_constructor::GraphConstructor;
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+    /// constructor.add_obstacle([-0.2, -0.2], [0.2, 0.2]);
+    /// assert_eq!(constructor.first_blocking_obstacle([-0.5, 0.0], [0.5, 0.0]), Some(0));
+    /// assert_eq!(constructor.first_blocking_obstacle([-0.5, 0.5], [0.5, 0.5]), None);
+    /// ```
+    pub fn first_blocking_obstacle(&self, start: [f32; 2], end: [f32; 2]) -> Option<usize> {
+        let start = Vec2::from(start);
+        let end = Vec2::from(end);
+        self.obstacles
+            .iter()
+            .enumerate()
+            .filter(|(_, obstacle)| obstacle.blocks(start, end))
+            .map(|(index, obstacle)| (index, obstacle.entry_parameter(start, end).unwrap_or(0.0)))
+            .min_by(|first, second| first.1.total_cmp(&second.1))
+            .map(|(index, _)| index)
+    }
+
+    /// Fills the point array with random points. Points inside of the obstacles added so far get rejected.
     /// After too many attempts it stops adding points.
     ///
     /// # Example
     /// ```
     /// use astar_lib::graph_constructor::GraphConstructor;
-    /// let mut constructor = GraphConstructor::new(1.0, 0.3, 0.02, 0.01);
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
     /// constructor.add_random_points(1000);
     /// ```
     pub fn add_random_points(&mut self, num_of_points: usize) {
@@ -67,42 +168,164 @@ impl GraphConstructor {
         while (self.point_collection.len() < num_of_points) && (counter < MAX_ITERATIONS) {
             counter += 1;
             let candidate = Vec2::new(
-                rand::random_range(-self.extension..self.extension),
-                rand::random_range(-self.extension..self.extension),
+                rand::random_range(self.min.x..self.max.x),
+                rand::random_range(self.min.y..self.max.y),
             );
 
-            if self
+            if self.is_free_position(candidate) {
+                self.point_collection.push(candidate);
+            }
+        }
+    }
+
+    /// Checks if a candidate point keeps the exclusion distance to all points placed so far and is
+    /// outside of all obstacles. Points on the boundary of an obstacle count as inside.
+    fn is_free_position(&self, candidate: Vec2) -> bool {
+        !self
+            .obstacles
+            .iter()
+            .any(|obstacle| obstacle.contains(candidate))
+            && self
                 .point_collection
                 .iter()
                 .all(|partner| candidate.dist_to(partner) > self.exclusion_distance)
+    }
+
+    /// Samples an offset with a normal distribution (Box-Muller) that gets cut off
+    /// at [`CLUSTER_CUT_OFF`] times the spread.
+    fn sample_cluster_offset(spread: f32) -> Vec2 {
+        loop {
+            let radius = spread * (-2.0 * rand::random_range(f32::EPSILON..1.0).ln()).sqrt();
+            if radius > CLUSTER_CUT_OFF * spread {
+                continue;
+            }
+            let angle = rand::random_range(0.0..2.0 * std::f32::consts::PI);
+            return Vec2::new(radius * angle.cos(), radius * angle.sin());
+        }
+    }
+
+    /// Fills the point array with clustered points, which gives town and wilderness style maps
+    /// rather than uniform noise. First the cluster centers are placed with a minimum distance of
+    /// ten times the spread, then the points get sampled normally distributed around them.
+    /// The exclusion distance of points is still respected, and after too many attempts it stops adding points.
+    ///
+    /// # Parameters
+    /// * **clusters:** The number of clusters to generate.
+    /// * **per_cluster:** The number of points we try to place around each cluster center.
+    /// * **spread:** The standard deviation of the points around the cluster center.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::graph_constructor::GraphConstructor;
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+    /// constructor.add_clustered_points(5, 50, 0.05);
+    /// ```
+    pub fn add_clustered_points(&mut self, clusters: usize, per_cluster: usize, spread: f32) {
+        self.point_collection = Vec::with_capacity(clusters * per_cluster);
+        // The centers keep the cut off distance to the border, unless the rectangle is too small for that.
+        let margin = Vec2::new(CLUSTER_CUT_OFF * spread, CLUSTER_CUT_OFF * spread);
+        let center = self.min.lerp(&self.max, 0.5);
+        let center_min = (self.min + margin).min(&center);
+        let center_max = (self.max - margin).max(&center);
+        let mut centers: Vec<Vec2> = Vec::with_capacity(clusters);
+        let mut counter = 0;
+        while (centers.len() < clusters) && (counter < MAX_ITERATIONS) {
+            counter += 1;
+            let candidate = Vec2::new(
+                rand::random_range(center_min.x..=center_max.x),
+                rand::random_range(center_min.y..=center_max.y),
+            );
+            if centers
+                .iter()
+                .all(|center| candidate.dist_to(center) > CLUSTER_SEPARATION * spread)
             {
-                self.point_collection.push(candidate);
+                centers.push(candidate);
+            }
+        }
+
+        for center in centers {
+            let mut placed = 0;
+            let mut counter = 0;
+            while (placed < per_cluster) && (counter < MAX_ITERATIONS) {
+                counter += 1;
+                let candidate = center + Self::sample_cluster_offset(spread);
+                if self.is_free_position(candidate) {
+                    self.point_collection.push(candidate);
+                    placed += 1;
+                }
             }
         }
     }
 
-    /// Tries to generate a number of random links.
-    /// After too many attempts it stops adding links.
+    /// Generates links with the indicated strategy. The count limits the number of links the strategy adds,
+    /// where None leaves it to the strategy when to stop. All strategies stop after too many attempts.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::graph_constructor::{GraphConstructor, LinkStrategy};
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+    /// constructor.add_random_points(1000);
+    /// constructor.add_links(LinkStrategy::RandomNonCrossing { max_degree: 6 }, Some(5000));
+    /// constructor.add_links(LinkStrategy::ConnectComponents, None);
+    /// ```
+    pub fn add_links(&mut self, strategy: LinkStrategy, count: Option<usize>) {
+        let count = count.unwrap_or(usize::MAX);
+        match strategy {
+            LinkStrategy::RandomNonCrossing { max_degree } => {
+                self.add_random_non_crossing(count, max_degree)
+            }
+            LinkStrategy::ConnectComponents => self.connect_components(count),
+        }
+    }
+
+    /// Tries to generate a number of random links with the strategy [LinkStrategy::RandomNonCrossing],
+    /// without a limit of the degree. After too many attempts it stops adding links.
     ///
     /// # Example
     /// ```
     /// use astar_lib::graph_constructor::GraphConstructor;
-    /// let mut constructor = GraphConstructor::new(1.0, 0.3, 0.02, 0.01);
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
     /// constructor.add_random_points(1000);
     /// constructor.add_random_links(5000);
     /// ```
     pub fn add_random_links(&mut self, num_of_links: usize) {
-        self.point_pairing = Vec::with_capacity(num_of_links);
+        self.add_random_links_with_max_degree(num_of_links, usize::MAX);
+    }
+
+    /// Tries to generate a number of random links like [GraphConstructor::add_random_links], where no point
+    /// gets more links than the maximum degree.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::graph_constructor::GraphConstructor;
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+    /// constructor.add_random_points(1000);
+    /// constructor.add_random_links_with_max_degree(5000, 6);
+    /// ```
+    pub fn add_random_links_with_max_degree(&mut self, num_of_links: usize, max_degree: usize) {
+        self.add_links(
+            LinkStrategy::RandomNonCrossing { max_degree },
+            Some(num_of_links),
+        );
+    }
+
+    /// Adds random links as described in [LinkStrategy::RandomNonCrossing].
+    fn add_random_non_crossing(&mut self, num_of_links: usize, max_degree: usize) {
+        self.point_pairing = Vec::new();
         let mut counter = 0;
         let num_of_points = self.point_collection.len();
         if num_of_points == 0 {
             return;
         }
 
-        let mut link_collection: Vec<Line> = Vec::with_capacity(num_of_links);
+        let mut link_collection: Vec<Line> = Vec::new();
+        let mut degrees = vec![0usize; num_of_points];
         while (self.point_pairing.len() < num_of_links) && (counter < MAX_ITERATIONS) {
             counter += 1;
             let first_ind = rand::random_range(0..num_of_points);
+            if degrees[first_ind] >= max_degree {
+                continue;
+            }
             let first_pos = self.point_collection[first_ind];
 
             // Now we filter for other points that are in max len range.
@@ -112,6 +335,7 @@ impl GraphConstructor {
                 .enumerate()
                 .filter(|(index, position)| {
                     (*index != first_ind)
+                        && (degrees[*index] < max_degree)
                         && (**position - first_pos).magnitude() < self.max_line_length
                 })
                 .map(|(index, _)| index)
@@ -123,50 +347,208 @@ impl GraphConstructor {
             }
             let second_ind = partner_index.unwrap();
 
-            // Check if line already contained in one form.
-            let test_pairing = (first_ind, second_ind);
-            let test_paring_inverse = (second_ind, first_ind);
-            if self
-                .point_pairing
-                .iter()
-                .any(|other| (other == &test_pairing) || (other == &test_paring_inverse))
-            {
-                continue;
+            if let Some(line) = self.check_link(first_ind, second_ind, &link_collection) {
+                link_collection.push(line);
+                self.point_pairing.push((first_ind, second_ind));
+                degrees[first_ind] += 1;
+                degrees[second_ind] += 1;
             }
+        }
+    }
+
+    /// Checks if a link between the two points may be added, given the lines of the links added so far.
+    /// Returns the line of the link if it is valid.
+    fn check_link(
+        &self,
+        first_ind: usize,
+        second_ind: usize,
+        link_collection: &[Line],
+    ) -> Option<Line> {
+        // Check if line already contained in one form.
+        let test_pairing = (first_ind, second_ind);
+        let test_paring_inverse = (second_ind, first_ind);
+        if self
+            .point_pairing
+            .iter()
+            .any(|other| (other == &test_pairing) || (other == &test_paring_inverse))
+        {
+            return None;
+        }
 
-            let line = Line::new(
+        let line = Line::new(
+            self.point_collection[first_ind],
+            self.point_collection[second_ind],
+        );
+        // Check if line intersects with a different one.
+        if link_collection
+            .iter()
+            .any(|other_line| other_line.intersects_with(&line))
+        {
+            return None;
+        }
+
+        // Check if line passes through an obstacle.
+        if self.obstacles.iter().any(|obstacle| {
+            obstacle.blocks(
                 self.point_collection[first_ind],
                 self.point_collection[second_ind],
-            );
-            // Check if line intersects with a different one.
-            if link_collection
-                .iter()
-                .any(|other_line| other_line.intersects_with(&line))
-            {
+            )
+        }) {
+            return None;
+        }
+
+        // Last we check for degenerate triangles.
+        if self
+            .point_collection
+            .iter()
+            .any(|point| line.is_in_critical_range(*point, self.edge_distance))
+        {
+            return None;
+        }
+
+        Some(line)
+    }
+
+    /// Gets the component label of every point with respect to the links added so far.
+    fn component_labels(&self) -> Vec<usize> {
+        let num_of_points = self.point_collection.len();
+        let mut neighbors: Vec<Vec<usize>> = vec![Vec::new(); num_of_points];
+        for (first, second) in self.point_pairing.iter() {
+            neighbors[*first].push(*second);
+            neighbors[*second].push(*first);
+        }
+
+        let mut labels = vec![usize::MAX; num_of_points];
+        for seed in 0..num_of_points {
+            if labels[seed] != usize::MAX {
                 continue;
             }
+            labels[seed] = seed;
+            let mut todo = vec![seed];
+            while let Some(current) = todo.pop() {
+                for partner in neighbors[current].iter() {
+                    if labels[*partner] == usize::MAX {
+                        labels[*partner] = seed;
+                        todo.push(*partner);
+                    }
+                }
+            }
+        }
+        labels
+    }
 
-            // Last we check for degenerate triangles.
-            if self
-                .point_collection
-                .iter()
-                .any(|point| line.is_in_critical_range(*point, self.edge_distance))
-            {
-                continue;
+    /// Adds links until all points form one connected component with the strategy [LinkStrategy::ConnectComponents].
+    /// This is meant to be called after the
+    /// random links have been added, as those frequently leave the graph fragmented into islands.
+    /// The shortest valid link between two different components gets added repeatedly. The maximum line length
+    /// is respected where possible, and only relaxed as a last resort. If no valid link between two
+    /// components exists at all, the graph stays fragmented.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::graph_constructor::GraphConstructor;
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+    /// constructor.add_random_points(100);
+    /// constructor.add_random_links(50);
+    /// constructor.ensure_connectivity();
+    /// ```
+    pub fn ensure_connectivity(&mut self) {
+        self.add_links(LinkStrategy::ConnectComponents, None);
+    }
+
+    /// Adds at most the indicated number of links between components as described in [LinkStrategy::ConnectComponents].
+    fn connect_components(&mut self, num_of_links: usize) {
+        let num_of_points = self.point_collection.len();
+        let mut labels = self.component_labels();
+        let mut link_collection: Vec<Line> = self
+            .point_pairing
+            .iter()
+            .map(|(first, second)| {
+                Line::new(
+                    self.point_collection[*first],
+                    self.point_collection[*second],
+                )
+            })
+            .collect();
+
+        let mut candidates: Vec<(f32, usize, usize)> = Vec::new();
+        for first in 0..num_of_points {
+            for second in (first + 1)..num_of_points {
+                let length = self.point_collection[first].dist_to(&self.point_collection[second]);
+                candidates.push((length, first, second));
+            }
+        }
+        candidates.sort_by(|a, b| a.0.total_cmp(&b.0));
+
+        for _ in 0..num_of_links {
+            let num_of_components = (0..num_of_points)
+                .filter(|index| labels[*index] == *index)
+                .count();
+            if num_of_components <= 1 {
+                return;
             }
 
+            let found = [true, false].iter().find_map(|respect_length| {
+                candidates.iter().find_map(|(length, first, second)| {
+                    if labels[*first] == labels[*second]
+                        || (*respect_length && *length >= self.max_line_length)
+                    {
+                        return None;
+                    }
+                    self.check_link(*first, *second, &link_collection)
+                        .map(|line| (*first, *second, line))
+                })
+            });
+            let Some((first, second, line)) = found else {
+                return;
+            };
+
             link_collection.push(line);
-            self.point_pairing.push(test_pairing);
+            self.point_pairing.push((first, second));
+            let (kept_label, replaced_label) = if labels[first] < labels[second] {
+                (labels[first], labels[second])
+            } else {
+                (labels[second], labels[first])
+            };
+            for label in labels.iter_mut() {
+                if *label == replaced_label {
+                    *label = kept_label;
+                }
+            }
         }
     }
 
+    /// Gets the minimum, the mean and the maximum length of the links added so far, which helps to tune
+    /// the maximum line length. Returns zeros if there are no links.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::graph_constructor::GraphConstructor;
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+    /// constructor.add_random_points(1000);
+    /// constructor.add_random_links(5000);
+    /// let (min, mean, max) = constructor.edge_length_stats();
+    /// ```
+    pub fn edge_length_stats(&self) -> (f32, f32, f32) {
+        if self.point_pairing.is_empty() {
+            return (0.0, 0.0, 0.0);
+        }
+        let lengths = self.point_pairing.iter().map(|(first, second)| {
+            self.point_collection[*first].dist_to(&self.point_collection[*second])
+        });
+        let (min, max, sum) = lengths.fold((f32::MAX, f32::MIN, 0.0), |(min, max, sum), length| {
+            (min.min(length), max.max(length), sum + length)
+        });
+        (min, sum / self.point_pairing.len() as f32, max)
+    }
+
     /// Generates a graph
     ///
     ///
     /// # Example
     /// ```
     /// use astar_lib::graph_constructor::GraphConstructor;
-    /// let mut constructor = GraphConstructor::new(1.0, 0.3, 0.02, 0.01);
+    /// let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
     /// constructor.add_random_points(1000);
     /// constructor.add_random_links(5000);
     /// let _graph = constructor.generate_graph();
@@ -192,7 +574,7 @@ mod tests {
 
     #[test]
     fn vec_construction_test() {
-        let mut constructor = GraphConstructor::new(1.0, 0.3, 0.02, 0.01);
+        let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
         constructor.add_random_points(1000);
         constructor.add_random_links(5000);
         constructor.generate_graph();
@@ -200,8 +582,208 @@ mod tests {
 
     #[test]
     fn vec_test_empty_constructs() {
-        let mut constructor = GraphConstructor::new(1.0, 0.3, 0.02, 0.01);
+        let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
         constructor.add_random_links(5000);
         constructor.generate_graph();
     }
+
+    #[test]
+    #[should_panic(expected = "lower corner")]
+    fn inverted_corners_test() {
+        GraphConstructor::new([1.0, -1.0], [-1.0, 1.0], 0.3, 0.02, 0.01);
+    }
+
+    #[test]
+    #[should_panic(expected = "lower corner")]
+    fn zero_width_test() {
+        GraphConstructor::new([-1.0, 0.5], [1.0, 0.5], 0.3, 0.02, 0.01);
+    }
+
+    #[test]
+    fn bounding_box_test() {
+        let mut constructor = GraphConstructor::new([2.0, -1.0], [3.0, 4.0], 0.3, 0.02, 0.01);
+        constructor.add_random_points(200);
+        let points = &constructor.point_collection;
+        assert_eq!(points.len(), 200);
+        assert!(
+            points
+                .iter()
+                .all(|point| (2.0..=3.0).contains(&point.x) && (-1.0..=4.0).contains(&point.y))
+        );
+    }
+
+    #[test]
+    fn first_blocking_obstacle_test() {
+        let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+        constructor.add_obstacle([0.4, -0.1], [0.6, 0.1]);
+        constructor.add_obstacle([-0.6, -0.1], [-0.4, 0.1]);
+        assert_eq!(
+            constructor.first_blocking_obstacle([-0.9, 0.0], [0.9, 0.0]),
+            Some(1)
+        );
+        assert_eq!(
+            constructor.first_blocking_obstacle([0.9, 0.0], [-0.9, 0.0]),
+            Some(0)
+        );
+        assert_eq!(
+            constructor.first_blocking_obstacle([0.0, 0.0], [0.9, 0.0]),
+            Some(0)
+        );
+        assert_eq!(
+            constructor.first_blocking_obstacle([-0.9, 0.5], [0.9, 0.5]),
+            None
+        );
+        assert!(constructor.line_of_sight([-0.9, 0.5], [0.9, 0.5]));
+    }
+
+    #[test]
+    fn obstacle_sampling_test() {
+        let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.001, 0.01);
+        constructor.add_obstacle([-0.5, -0.5], [0.5, 0.5]);
+        constructor.add_random_points(500);
+        let obstacle = Obstacle::new(Vec2::new(-0.5, -0.5), Vec2::new(0.5, 0.5));
+        assert_eq!(constructor.point_collection.len(), 500);
+        assert!(
+            !constructor
+                .point_collection
+                .iter()
+                .any(|point| obstacle.contains(*point))
+        );
+    }
+
+    #[test]
+    fn clustered_points_test() {
+        let spread = 0.02;
+        let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.001, 0.01);
+        constructor.add_clustered_points(4, 30, spread);
+        let points = &constructor.point_collection;
+        assert!(points.len() >= 110, "Too few points: {}", points.len());
+        assert!(
+            points
+                .iter()
+                .all(|point| point.x.abs() <= 1.0 && point.y.abs() <= 1.0)
+        );
+
+        // Single linkage grouping: clusters are separated by a gap larger than the threshold.
+        let threshold = CLUSTER_CUT_OFF * spread;
+        let mut group = vec![usize::MAX; points.len()];
+        let mut num_of_groups = 0;
+        for seed in 0..points.len() {
+            if group[seed] != usize::MAX {
+                continue;
+            }
+            group[seed] = num_of_groups;
+            let mut todo = vec![seed];
+            while let Some(current) = todo.pop() {
+                for other in 0..points.len() {
+                    if group[other] == usize::MAX
+                        && points[current].dist_to(&points[other]) < threshold
+                    {
+                        group[other] = num_of_groups;
+                        todo.push(other);
+                    }
+                }
+            }
+            num_of_groups += 1;
+        }
+        assert_eq!(num_of_groups, 4, "Clusters should be separable.");
+    }
+
+    #[test]
+    fn line_of_sight_test() {
+        let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+        assert!(constructor.line_of_sight([-0.5, 0.0], [0.5, 0.0]));
+
+        constructor.add_obstacle([-0.1, -0.1], [0.1, 0.1]);
+        assert!(!constructor.line_of_sight([-0.5, 0.0], [0.5, 0.0]));
+        assert!(constructor.line_of_sight([-0.5, 0.5], [0.5, 0.5]));
+
+        constructor.point_collection.push(Vec2::new(0.0, 0.505));
+        assert!(!constructor.line_of_sight([-0.5, 0.5], [0.5, 0.5]));
+    }
+
+    #[test]
+    fn ensure_connectivity_test() {
+        let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.2, 0.02, 0.001);
+        constructor.add_random_points(100);
+        constructor.add_random_links(20);
+        assert!(
+            constructor
+                .component_labels()
+                .iter()
+                .enumerate()
+                .filter(|(index, label)| *index == **label)
+                .count()
+                > 1
+        );
+
+        constructor.ensure_connectivity();
+        let labels = constructor.component_labels();
+        assert!(labels.iter().all(|label| *label == labels[0]));
+
+        let graph = constructor.generate_graph();
+        assert!(
+            graph
+                .dijkstra_from(0)
+                .iter()
+                .all(|distance| distance.is_some())
+        );
+    }
+
+    #[test]
+    fn max_degree_test() {
+        let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+        constructor.add_random_points(300);
+        constructor.add_random_links_with_max_degree(2000, 3);
+        assert!(!constructor.point_pairing.is_empty());
+
+        let graph = constructor.generate_graph();
+        let histogram = graph.degree_histogram();
+        assert!(histogram.len() <= 4);
+    }
+
+    #[test]
+    fn link_strategy_test() {
+        let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.2, 0.02, 0.001);
+        constructor.add_random_points(100);
+        constructor.add_links(LinkStrategy::RandomNonCrossing { max_degree: 2 }, Some(20));
+        assert_eq!(constructor.point_pairing.len(), 20);
+        let count_components = |constructor: &GraphConstructor| {
+            let labels = constructor.component_labels();
+            (0..labels.len())
+                .filter(|index| labels[*index] == *index)
+                .count()
+        };
+        let components = count_components(&constructor);
+        assert!(components > 2);
+
+        constructor.add_links(LinkStrategy::ConnectComponents, Some(1));
+        assert_eq!(constructor.point_pairing.len(), 21);
+        assert_eq!(count_components(&constructor), components - 1);
+        constructor.add_links(LinkStrategy::ConnectComponents, None);
+        assert_eq!(count_components(&constructor), 1);
+
+        // Without a count, random links get added until the attempts run out.
+        constructor.add_links(LinkStrategy::RandomNonCrossing { max_degree: 3 }, None);
+        assert!(constructor.point_pairing.len() > 21);
+        let graph = constructor.generate_graph();
+        assert!(graph.degree_histogram().len() <= 4);
+    }
+
+    #[test]
+    fn edge_length_stats_test() {
+        let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+        assert_eq!(constructor.edge_length_stats(), (0.0, 0.0, 0.0));
+
+        constructor.point_collection = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(0.1, 0.0),
+            Vec2::new(0.1, 0.3),
+        ];
+        constructor.point_pairing = vec![(0, 1), (1, 2)];
+        let (min, mean, max) = constructor.edge_length_stats();
+        assert!((min - 0.1).abs() < 0.00001);
+        assert!((mean - 0.2).abs() < 0.00001);
+        assert!((max - 0.3).abs() < 0.00001);
+    }
 }
@@ -1,10 +1,16 @@
 //! This is the main module responsible for graphical operations in OpenGL and interaction.
 
-use crate::graph_constructor::GraphConstructor;
-use astar_lib::a_star::{NavGraph, NodeState};
+use crate::graph_constructor::{GraphConstructor, LinkStrategy};
+use crate::hud::{StateCounts, number_lines};
+use crate::persistence;
+use astar_lib::a_star::{NavGraph, NearestQueryCache, NodeState};
 use glume::gl;
 use glume::gl::types::*;
 use glume::window::{Event, MouseButton};
+use std::path::Path;
+
+/// Maps the state of a node to the color it gets painted with.
+pub type ColorScheme = Box<dyn Fn(&NodeState) -> [f32; 3]>;
 
 /// All the callbacks from the glume framework and initialization stuff is handled here.
 /// If one wants to use pure OpenGL with a different framework than glume this would be the entry point.
@@ -22,9 +28,18 @@ pub struct InteractionCore {
     graph: NavGraph,
     circle_radius: f32,
     node_selected: Option<usize>,
+    drag_origin: Option<usize>,
+    modifier_pressed: bool,
+    show_hud: bool,
+    color_scheme: ColorScheme,
+    nearest_cache: NearestQueryCache,
 }
 
 const POINTS_IN_CIRCLE: usize = 20;
+/// The maximum number of links a node of the random graph may have.
+const MAX_DEGREE: usize = 6;
+/// The height of the digits in the head-up display in clip space.
+const HUD_DIGIT_HEIGHT: f32 = 0.05;
 
 impl InteractionCore {
     /// Generates the interaction core from several parameters.
@@ -36,7 +51,18 @@ impl InteractionCore {
     /// * **edge_distance:** The minimum distance a point to an edge if within the voronoi region of the edge.
     /// * **num_of_points**: The number of nodes we have in the graph.
     /// * **num_of_links**: The number of edge we have in the graph.
+    /// * **color_scheme**: The color mapping for the node states, [InteractionCore::default_color] is the standard scheme.
     ///
+    /// # Example
+    /// ```no_run
+    /// use astar_lib::a_star::NodeState;
+    /// use astar_lib::graphics::InteractionCore;
+    /// let palette = |state: &NodeState| match state {
+    ///     NodeState::Solution => [1.0, 1.0, 1.0],
+    ///     _ => [0.3, 0.3, 0.3],
+    /// };
+    /// let core = InteractionCore::new(0.015, 0.04, 0.25, 0.05, 300, 800, Box::new(palette));
+    /// ```
     pub fn new(
         circle_radius: f32,
         circle_exclusion_radius: f32,
@@ -44,15 +70,26 @@ impl InteractionCore {
         edge_distance: f32,
         num_of_points: usize,
         num_of_links: usize,
+        color_scheme: ColorScheme,
     ) -> InteractionCore {
         let shader_program = Self::create_shader_program();
         let (translation, color) = Self::get_translation_colors_parameters(shader_program);
         let line_vbo_vba = Self::create_line_vbo_and_vba();
         let circle_vba = Self::create_circle_vba(circle_radius);
-        let mut graph_constructor =
-            GraphConstructor::new(1.0, max_line_length, circle_exclusion_radius, edge_distance);
+        let mut graph_constructor = GraphConstructor::new(
+            [-1.0, -1.0],
+            [1.0, 1.0],
+            max_line_length,
+            circle_exclusion_radius,
+            edge_distance,
+        );
         graph_constructor.add_random_points(num_of_points);
-        graph_constructor.add_random_links(num_of_links);
+        graph_constructor.add_links(
+            LinkStrategy::RandomNonCrossing {
+                max_degree: MAX_DEGREE,
+            },
+            Some(num_of_links),
+        );
         let graph = graph_constructor.generate_graph();
 
         InteractionCore {
@@ -69,6 +106,11 @@ impl InteractionCore {
             graph,
             circle_radius,
             node_selected: None,
+            drag_origin: None,
+            modifier_pressed: false,
+            show_hud: false,
+            color_scheme,
+            nearest_cache: NearestQueryCache::new(),
         }
     }
 
@@ -222,7 +264,8 @@ impl InteractionCore {
         }
     }
 
-    fn get_color(state: &NodeState) -> [f32; 3] {
+    /// The standard color scheme used for the node states.
+    pub fn default_color(state: &NodeState) -> [f32; 3] {
         match state {
             NodeState::Clear => [0.1_f32, 0.5_f32, 0.1_f32],
             NodeState::Visited => [1.0_f32, 1.0_f32, 0.0_f32],
@@ -244,13 +287,16 @@ impl InteractionCore {
             } else {
                 NodeState::Clear
             };
-            self.draw_line(start, end, &Self::get_color(&color_state))
+            self.draw_line(start, end, &(self.color_scheme)(&color_state))
         }
         unsafe {
             gl::BindVertexArray(self.circle_vba);
         }
         for (position, state) in self.graph.get_all_nodes_with_state() {
-            self.draw_circle(position, &Self::get_color(state));
+            self.draw_circle(position, &(self.color_scheme)(state));
+        }
+        if self.show_hud {
+            self.draw_hud();
         }
         unsafe {
             gl::BindVertexArray(0);
@@ -259,6 +305,32 @@ impl InteractionCore {
         }
     }
 
+    /// Draws the counts of visited, closed and solution nodes of the last search in the upper left corner.
+    /// Each count is painted in the color of its node state.
+    fn draw_hud(&self) {
+        unsafe {
+            gl::BindVertexArray(self.line_vbo_vba.1);
+        }
+        let counts = StateCounts::from_graph(&self.graph);
+        let rows = [
+            (counts.visited, NodeState::Visited),
+            (counts.closed, NodeState::Closed),
+            (counts.solution, NodeState::Solution),
+        ];
+        for (row, (count, state)) in rows.iter().enumerate() {
+            let origin = [-0.95, 0.95 - (row + 1) as f32 * HUD_DIGIT_HEIGHT * 1.5];
+            let color = (self.color_scheme)(state);
+            for (start, end) in number_lines(*count, origin, HUD_DIGIT_HEIGHT) {
+                self.draw_line(start, end, &color);
+            }
+        }
+    }
+
+    /// Toggles the display of the search statistics.
+    pub fn toggle_hud(&mut self) {
+        self.show_hud = !self.show_hud;
+    }
+
     /// Sets the window extension from the outside. This is needed to get the cursor position
     /// in clip space.
     pub fn set_window_extension(&mut self, width: u32, height: u32) {
@@ -273,11 +345,14 @@ impl InteractionCore {
             1.0 - 2.0_f32 * y / self.screen_extension.1,
         ];
 
-        // Here we analyze if we have a pick node.
+        // Here we analyze if we have a pick node. The cursor usually stays close to the last position,
+        // so the cached query rarely has to scan all nodes.
         if let Some(start) = self.node_selected
-            && let Some(destination) = self
-                .graph
-                .find_nearest_node_with_radius(self.cursor_pos, self.circle_radius)
+            && let Some(destination) = self.graph.find_nearest_node_cached(
+                self.cursor_pos,
+                self.circle_radius,
+                &mut self.nearest_cache,
+            )
         {
             self.graph.search_graph(start, destination);
         }
@@ -286,21 +361,98 @@ impl InteractionCore {
     /// Gets called from the outside on right mouse button to regenerate a new graph.
     pub fn generate_graph(&mut self) {
         self.node_selected = None;
+        self.drag_origin = None;
         self.graph_constructor.add_random_points(self.num_of_points);
-        self.graph_constructor.add_random_links(self.num_of_links);
+        self.graph_constructor.add_links(
+            LinkStrategy::RandomNonCrossing {
+                max_degree: MAX_DEGREE,
+            },
+            Some(self.num_of_links),
+        );
         self.graph = self.graph_constructor.generate_graph();
     }
 
-    /// Gets called from the outside on left mouse button to select a new start point.
+    /// Gets called from the outside to save the current graph to a file with a time stamp in the working directory.
+    pub fn save_graph(&self) {
+        match persistence::save_graph(&self.graph, Path::new(".")) {
+            Ok(path) => println!("Saved the graph to {}", path.display()),
+            Err(error) => eprintln!("Could not save the graph: {error}"),
+        }
+    }
+
+    /// Gets called from the outside to replace the graph with the most recently saved one in the working directory.
+    pub fn load_graph(&mut self) {
+        let Some(path) = persistence::latest_graph_file(Path::new(".")) else {
+            eprintln!("There is no saved graph to load.");
+            return;
+        };
+        match persistence::load_graph(&path) {
+            Ok(graph) => {
+                self.node_selected = None;
+                self.drag_origin = None;
+                self.graph = graph;
+                println!("Loaded the graph from {}", path.display());
+            }
+            Err(error) => eprintln!("Could not load the graph: {error}"),
+        }
+    }
+
+    /// Gets called from the outside when the modifier key (shift) gets pressed or released.
+    pub fn set_modifier(&mut self, pressed: bool) {
+        self.modifier_pressed = pressed;
+    }
+
+    /// Gets called from the outside on left mouse button to select a new start point. The node
+    /// also becomes the origin of a drag, that creates a new edge on release over a different node.
+    /// If the modifier key is held, the edge under the cursor gets removed instead.
     pub fn pick_node(&mut self) {
+        if self.modifier_pressed {
+            if let Some((node1, node2)) = self
+                .graph
+                .find_nearest_link_with_radius(self.cursor_pos, self.circle_radius)
+            {
+                self.graph.disconnect_nodes(node1, node2).unwrap();
+                self.refresh_search();
+            }
+            return;
+        }
+
         if let Some(hit_node) = self
             .graph
             .find_nearest_node_with_radius(self.cursor_pos, self.circle_radius)
         {
             self.node_selected = Some(hit_node);
+            self.drag_origin = Some(hit_node);
             self.graph.search_graph(hit_node, hit_node);
         }
     }
+
+    /// Gets called from the outside on release of the left mouse button. If a drag started
+    /// on a node and ends on a different one, both get connected.
+    pub fn release_node(&mut self) {
+        let Some(origin) = self.drag_origin.take() else {
+            return;
+        };
+        if let Some(hit_node) = self
+            .graph
+            .find_nearest_node_with_radius(self.cursor_pos, self.circle_radius)
+            && hit_node != origin
+            && self.graph.connect_nodes(origin, hit_node).is_ok()
+        {
+            self.refresh_search();
+        }
+    }
+
+    /// Repeats the search from the selected node to the node under the cursor after the graph has been edited.
+    fn refresh_search(&mut self) {
+        if let Some(start) = self.node_selected {
+            let destination = self
+                .graph
+                .find_nearest_node_with_radius(self.cursor_pos, self.circle_radius)
+                .unwrap_or(start);
+            self.graph.search_graph(start, destination);
+        }
+    }
 }
 
 /// This is the main entrance to the test program that starts the OpenGL application
@@ -313,6 +465,17 @@ impl InteractionCore {
 /// * **edge_distance:** The minimum distance a point to an edge if within the voronoi region of the edge.
 /// * **num_of_points**: The number of nodes we have in the graph.
 /// * **num_of_links**: The number of edge we have in the graph.
+///
+/// # Controls
+/// * **Left click** on a node: Selects the start node, the path to the node under the cursor gets shown.
+/// * **Left drag** from one node to another: Connects both nodes.
+/// * **Shift + left click** on an edge: Disconnects the nodes of the edge.
+/// * **Right click**: Generates a new random graph.
+/// * **H**: Toggles the display of the visited, closed and solution node counts of the last search.
+/// * **S**: Saves the graph to a file with a time stamp in the working directory.
+/// * **L**: Loads the most recently saved graph from the working directory.
+/// * **Escape**: Closes the application.
+///
 /// # Example
 /// ```no_run
 ///  use astar_lib::graphics;
@@ -352,6 +515,7 @@ pub fn run_prog(
         edge_distance,
         num_of_points,
         num_of_links,
+        Box::new(InteractionCore::default_color),
     );
 
     window.run(move |wc, event| {
@@ -378,8 +542,26 @@ pub fn run_prog(
 
             Event::KeyPressed(key) => {
                 use glume::window::VirtualKeyCode as Vk;
-                if key == Vk::Escape {
-                    wc.close()
+                match key {
+                    Vk::Escape => wc.close(),
+                    Vk::LShift | Vk::RShift => core.set_modifier(true),
+                    Vk::H => {
+                        core.toggle_hud();
+                        wc.request_redraw();
+                    }
+                    Vk::S => core.save_graph(),
+                    Vk::L => {
+                        core.load_graph();
+                        wc.request_redraw();
+                    }
+                    _ => {}
+                }
+            }
+
+            Event::KeyReleased(key) => {
+                use glume::window::VirtualKeyCode as Vk;
+                if key == Vk::LShift || key == Vk::RShift {
+                    core.set_modifier(false);
                 }
             }
 
@@ -394,6 +576,13 @@ pub fn run_prog(
                 }
             }
 
+            Event::MouseButtonReleased(button) => {
+                if let MouseButton::Left = button {
+                    core.release_node();
+                    wc.request_redraw();
+                }
+            }
+
             _ => {}
         }
         Ok(())
@@ -0,0 +1,100 @@
+//! This is a helper module for the head-up display, that shows the statistics of the last search.
+//! The numbers are drawn as seven-segment digits out of lines, so no font rendering is needed.
+
+use astar_lib::a_star::{NavGraph, NodeState};
+
+/// The segments of a seven-segment digit as start and end positions in a unit cell of width 1 and height 2.
+const SEGMENTS: [([f32; 2], [f32; 2]); 7] = [
+    ([0.0, 2.0], [1.0, 2.0]), // top
+    ([1.0, 2.0], [1.0, 1.0]), // top right
+    ([1.0, 1.0], [1.0, 0.0]), // bottom right
+    ([0.0, 0.0], [1.0, 0.0]), // bottom
+    ([0.0, 1.0], [0.0, 0.0]), // bottom left
+    ([0.0, 2.0], [0.0, 1.0]), // top left
+    ([0.0, 1.0], [1.0, 1.0]), // middle
+];
+
+/// The active segments for every digit, bit n corresponds to the n-th entry in the segment list.
+const DIGIT_MASKS: [u8; 10] = [
+    0b0111111, 0b0000110, 0b1011011, 0b1001111, 0b1100110, 0b1101101, 0b1111101, 0b0000111,
+    0b1111111, 0b1101111,
+];
+
+/// The counts of the node states after a graph search.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct StateCounts {
+    pub visited: usize,
+    pub closed: usize,
+    pub solution: usize,
+}
+
+impl StateCounts {
+    /// Counts the node states of the last search in the graph.
+    pub fn from_graph(graph: &NavGraph) -> StateCounts {
+        let mut counts = StateCounts::default();
+        for (_, state) in graph.get_all_nodes_with_state() {
+            match state {
+                NodeState::Visited => counts.visited += 1,
+                NodeState::Closed => counts.closed += 1,
+                NodeState::Solution => counts.solution += 1,
+                NodeState::Clear => {}
+            }
+        }
+        counts
+    }
+}
+
+/// Generates the lines to draw a number in seven-segment style.
+///
+/// # Parameters
+/// * **value**: The number to draw.
+/// * **origin**: The lower left corner of the first digit.
+/// * **height**: The height of a digit, the width is the half of it.
+pub fn number_lines(value: usize, origin: [f32; 2], height: f32) -> Vec<([f32; 2], [f32; 2])> {
+    let scale = height * 0.5;
+    let advance = scale * 1.5;
+    let mut lines = Vec::new();
+
+    for (position, digit) in value.to_string().bytes().enumerate() {
+        let mask = DIGIT_MASKS[(digit - b'0') as usize];
+        let offset = [origin[0] + position as f32 * advance, origin[1]];
+        for (index, (start, end)) in SEGMENTS.iter().enumerate() {
+            if mask & (1 << index) != 0 {
+                lines.push((
+                    [offset[0] + start[0] * scale, offset[1] + start[1] * scale],
+                    [offset[0] + end[0] * scale, offset[1] + end[1] * scale],
+                ));
+            }
+        }
+    }
+    lines
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hud_test() {
+        assert_eq!(number_lines(8, [0.0, 0.0], 1.0).len(), 7);
+        assert_eq!(number_lines(10, [0.0, 0.0], 1.0).len(), 8);
+        let one = number_lines(1, [1.0, 1.0], 0.2);
+        assert!(
+            one.iter()
+                .all(|(start, end)| start[0] > 1.09 && end[0] > 1.09)
+        );
+
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([0.5, 0.5]);
+        let p2 = graph.add_node([1.0, 0.0]);
+        graph.add_node([5.0, 5.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p0, p2).unwrap();
+        graph.search_graph(p0, p1).unwrap();
+
+        let counts = StateCounts::from_graph(&graph);
+        assert_eq!(counts.solution, 2);
+        assert_eq!(counts.visited + counts.closed, 1);
+    }
+}
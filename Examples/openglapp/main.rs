@@ -3,7 +3,9 @@
 
 pub mod graph_constructor;
 pub mod graphics;
-pub mod line;
+pub mod hud;
+pub mod obstacle;
+pub mod persistence;
 
 extern crate astar_lib;
 
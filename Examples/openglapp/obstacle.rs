@@ -0,0 +1,134 @@
+//! This is a helper module for obstacles that block links in graph construction.
+
+use astar_lib::vector::Vec2;
+
+/// Contains an axis aligned rectangular obstacle. Links may not pass through it.
+#[derive(Debug, Clone)]
+pub struct Obstacle {
+    min: Vec2,
+    max: Vec2,
+}
+
+impl Obstacle {
+    /// Creates a new obstacle from its lower left and upper right corner.
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// use astar_lib::obstacle::Obstacle;
+    /// let obstacle = Obstacle::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+    /// ```
+    pub fn new(min: Vec2, max: Vec2) -> Obstacle {
+        Obstacle { min, max }
+    }
+
+    /// Checks if the indicated point is inside the obstacle. Points on the boundary count as inside.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// use astar_lib::obstacle::Obstacle;
+    /// let obstacle = Obstacle::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+    /// assert!(obstacle.contains(Vec2::new(0.5, 0.5)));
+    /// ```
+    pub fn contains(&self, point: Vec2) -> bool {
+        (self.min.x..=self.max.x).contains(&point.x) && (self.min.y..=self.max.y).contains(&point.y)
+    }
+
+    /// Checks if the segment between the two points is blocked by the obstacle. This is the case if
+    /// one of the end points is inside or the segment passes through the obstacle, including diagonals
+    /// from corner to corner. Segments that only touch a corner are not blocked.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// use astar_lib::obstacle::Obstacle;
+    /// let obstacle = Obstacle::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+    /// assert!(obstacle.blocks(Vec2::new(-1.0, 0.5), Vec2::new(2.0, 0.5)));
+    /// ```
+    pub fn blocks(&self, start: Vec2, end: Vec2) -> bool {
+        self.contains(start)
+            || self.contains(end)
+            || self
+                .slab_interval(start, end)
+                .is_some_and(|(entry, exit)| entry < exit)
+    }
+
+    /// Gets the relative position along the segment from start to end, where the segment enters the obstacle.
+    /// The result ranges from 0 at the start to 1 at the end, and is 0 if the start is inside.
+    /// Returns None if the segment does not touch the obstacle.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// use astar_lib::obstacle::Obstacle;
+    /// let obstacle = Obstacle::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+    /// assert_eq!(obstacle.entry_parameter(Vec2::new(-1.0, 0.5), Vec2::new(3.0, 0.5)), Some(0.25));
+    /// ```
+    pub fn entry_parameter(&self, start: Vec2, end: Vec2) -> Option<f32> {
+        self.slab_interval(start, end).map(|(entry, _)| entry)
+    }
+
+    /// Clips the segment from start to end against the slabs of the obstacle. Returns the relative positions
+    /// where the segment enters and leaves the obstacle, or None if it does not touch the obstacle.
+    fn slab_interval(&self, start: Vec2, end: Vec2) -> Option<(f32, f32)> {
+        let delta = end - start;
+        let mut entry: f32 = 0.0;
+        let mut exit: f32 = 1.0;
+        for (origin, direction, lower, upper) in [
+            (start.x, delta.x, self.min.x, self.max.x),
+            (start.y, delta.y, self.min.y, self.max.y),
+        ] {
+            if direction == 0.0 {
+                if !(lower..=upper).contains(&origin) {
+                    return None;
+                }
+                continue;
+            }
+            let first = (lower - origin) / direction;
+            let second = (upper - origin) / direction;
+            entry = entry.max(first.min(second));
+            exit = exit.min(first.max(second));
+        }
+        (entry <= exit).then_some((entry, exit))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn obstacle_test() {
+        let obstacle = Obstacle::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+        assert!(
+            obstacle.contains(Vec2::new(1.0, 0.5)),
+            "Boundary is inside."
+        );
+        assert!(!obstacle.contains(Vec2::new(1.5, 0.5)));
+        assert!(obstacle.blocks(Vec2::new(-1.0, 0.5), Vec2::new(2.0, 0.5)));
+        assert!(obstacle.blocks(Vec2::new(0.5, 0.5), Vec2::new(2.0, 0.5)));
+        assert!(!obstacle.blocks(Vec2::new(-1.0, 1.5), Vec2::new(2.0, 1.5)));
+        assert!(
+            obstacle.blocks(Vec2::new(-1.0, -1.0), Vec2::new(2.0, 2.0)),
+            "Diagonal from corner to corner."
+        );
+        assert!(
+            !obstacle.blocks(Vec2::new(-1.0, 1.0), Vec2::new(1.0, -1.0)),
+            "Only touches a corner."
+        );
+    }
+
+    #[test]
+    fn entry_parameter_test() {
+        let obstacle = Obstacle::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+        let entry = |start: [f32; 2], end: [f32; 2]| {
+            obstacle.entry_parameter(Vec2::from(start), Vec2::from(end))
+        };
+        assert_eq!(entry([2.0, 0.5], [-2.0, 0.5]), Some(0.25));
+        assert_eq!(entry([0.5, -1.0], [0.5, 1.0]), Some(0.5));
+        assert_eq!(entry([0.5, 0.5], [3.0, 0.5]), Some(0.0));
+        assert_eq!(entry([-1.0, 1.5], [2.0, 1.5]), None);
+        assert_eq!(entry([-2.0, 0.5], [-1.0, 0.5]), None);
+        assert_eq!(entry([-1.0, 0.0], [1.0, 2.0]), Some(0.5));
+    }
+}
@@ -0,0 +1,156 @@
+//! This is a helper module to keep interesting graphs of the demo. A graph gets stored as a small text file,
+//! with a line *n x y* for every node in the order of the handles, followed by a line *l a b* for every link
+//! between the node handles a and b. Only the positions and the links are stored, so the costs follow from the positions.
+
+use astar_lib::a_star::NavGraph;
+use std::fs;
+use std::io::{Error, ErrorKind, Result, Write};
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The prefix of the names of the files the graphs get saved to.
+const FILE_PREFIX: &str = "graph_";
+/// The extension of the files the graphs get saved to.
+const FILE_EXTENSION: &str = "txt";
+
+/// Turns the graph into the text format.
+pub fn graph_to_text(graph: &NavGraph) -> String {
+    let mut text = String::new();
+    for (position, _) in graph.get_all_nodes_with_state() {
+        text.push_str(&format!("n {} {}\n", position[0], position[1]));
+    }
+    for (node1, node2) in graph.links_in_order() {
+        text.push_str(&format!("l {node1} {node2}\n"));
+    }
+    text
+}
+
+/// Reconstructs a graph from the text format.
+///
+/// # Error
+/// Returns an error of kind *InvalidData* if a line can not be parsed or a link refers to a node that does not exist.
+pub fn graph_from_text(text: &str) -> Result<NavGraph> {
+    let invalid = |line: &str| Error::new(ErrorKind::InvalidData, format!("invalid line: {line}"));
+    let mut graph = NavGraph::new();
+    for line in text.lines().filter(|line| !line.trim().is_empty()) {
+        let parts: Vec<&str> = line.split_whitespace().collect();
+        match parts.as_slice() {
+            ["n", x, y] => {
+                let x: f32 = x.parse().map_err(|_| invalid(line))?;
+                let y: f32 = y.parse().map_err(|_| invalid(line))?;
+                graph.add_node([x, y]);
+            }
+            ["l", node1, node2] => {
+                let node1: usize = node1.parse().map_err(|_| invalid(line))?;
+                let node2: usize = node2.parse().map_err(|_| invalid(line))?;
+                graph
+                    .connect_nodes(node1, node2)
+                    .map_err(|_| invalid(line))?;
+            }
+            _ => return Err(invalid(line)),
+        }
+    }
+    Ok(graph)
+}
+
+/// Saves the graph to a file in the indicated directory, whose name contains the current time in seconds
+/// and nanoseconds, and returns the path of the file. An existing file is never overwritten, so saving
+/// twice within the resolution of the clock takes the next free name.
+///
+/// # Error
+/// Returns an error if the file can not be written.
+pub fn save_graph(graph: &NavGraph, directory: &Path) -> Result<PathBuf> {
+    let time = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default();
+    let stem = format!("{FILE_PREFIX}{}_{:09}", time.as_secs(), time.subsec_nanos());
+    let mut counter = 0;
+    loop {
+        let name = match counter {
+            0 => format!("{stem}.{FILE_EXTENSION}"),
+            _ => format!("{stem}_{counter}.{FILE_EXTENSION}"),
+        };
+        let path = directory.join(name);
+        match fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&path)
+        {
+            Ok(mut file) => {
+                file.write_all(graph_to_text(graph).as_bytes())?;
+                return Ok(path);
+            }
+            Err(error) if error.kind() == ErrorKind::AlreadyExists => counter += 1,
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+/// Gets the most recently saved graph file in the indicated directory, if there is any.
+pub fn latest_graph_file(directory: &Path) -> Option<PathBuf> {
+    fs::read_dir(directory)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| {
+            path.extension()
+                .is_some_and(|extension| extension == FILE_EXTENSION)
+                && path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .is_some_and(|name| name.starts_with(FILE_PREFIX))
+        })
+        .max_by_key(|path| {
+            // Files saved within the resolution of the modification time are told apart by their names.
+            let modified = fs::metadata(path)
+                .and_then(|metadata| metadata.modified())
+                .unwrap_or(UNIX_EPOCH);
+            (modified, path.clone())
+        })
+}
+
+/// Loads a graph from a file written with [save_graph].
+///
+/// # Error
+/// Returns an error if the file can not be read or is not in the text format.
+pub fn load_graph(path: &Path) -> Result<NavGraph> {
+    graph_from_text(&fs::read_to_string(path)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::graph_constructor::GraphConstructor;
+
+    #[test]
+    fn save_load_test() {
+        let mut constructor = GraphConstructor::new([-1.0, -1.0], [1.0, 1.0], 0.3, 0.02, 0.01);
+        constructor.add_random_points(200);
+        constructor.add_random_links_with_max_degree(500, 6);
+        let graph = constructor.generate_graph();
+
+        let directory = std::env::temp_dir().join(format!("astar_save_{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let path = save_graph(&graph, &directory).unwrap();
+        assert!(fs::metadata(&path).unwrap().len() > 0);
+        assert_eq!(latest_graph_file(&directory), Some(path.clone()));
+
+        let loaded = load_graph(&path).unwrap();
+        assert_eq!(graph_to_text(&loaded), graph_to_text(&graph));
+        assert_eq!(loaded.stats(), graph.stats());
+
+        // Saving quickly in a row never overwrites a file.
+        let paths: Vec<PathBuf> = (0..5)
+            .map(|_| save_graph(&graph, &directory).unwrap())
+            .collect();
+        let mut unique = paths.clone();
+        unique.sort();
+        unique.dedup();
+        assert_eq!(unique.len(), paths.len());
+        assert!(!paths.contains(&path));
+        assert_eq!(latest_graph_file(&directory).as_ref(), paths.last());
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(graph_from_text("n 0 0\nl 0 1\n").is_err());
+        assert!(graph_from_text("n 0.5\n").is_err());
+    }
+}
@@ -2,14 +2,32 @@
 //! The typical use case of this algorithm is navigation in games.
 //!
 
+use super::kd_tree::KdTree;
+use super::line::Line;
 use super::vector::Vec2;
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::sync::atomic::{AtomicU64, Ordering as AtomicOrdering};
+
+/// The tags of nodes and edges that have not been tagged explicitly, which is every category.
+pub const ALL_TAGS: u32 = u32::MAX;
+
+/// The source of the layout ids and the generations of the graphs, which are unique over all graphs.
+static NEXT_ID: AtomicU64 = AtomicU64::new(0);
+
+/// Gets a new id, which is larger than all ids handed out before. A layout id identifies the node positions
+/// of a graph, a generation identifies its complete content.
+fn fresh_id() -> u64 {
+    NEXT_ID.fetch_add(1, AtomicOrdering::Relaxed)
+}
 
 /// A declaration for the current state a node in the nav graph can be in.
 /// This is relevant for the method *get_all_nodes_with_state*, which is
 /// relevant for analyzing the behavior of the algorithm.
 ///
 /// Chances are, you will not need this unless you want to make a debug visualization
-/// of your nav graph. It is only used in method [`NavGraph::get_all_nodes_with_state`].
+/// of your nav graph. It is only used in method [`NavGraph::get_all_nodes_with_state`]
+/// and [`SearchScratch::node_state`].
 #[derive(Debug, Clone, PartialEq)]
 pub enum NodeState {
     /// The node is in its original state, unvisited.
@@ -35,16 +53,30 @@ pub enum ConnectionError {
     LinkAlreadyExists,
     /// May happen during a disconnection attempt, because the link does not exist.
     LinkDoesntExist,
+    /// May happen when setting a cost multiplier of a node that is negative or NaN.
+    InvalidMultiplier,
+}
+
+/// The diagonal links that [`NavGraph::from_walkability`] establishes between the cells of a grid.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DiagonalMoves {
+    /// Only horizontal and vertical links are established.
+    Forbidden,
+    /// Diagonal links are established where both orthogonal cells next to them are free, so that they never
+    /// cut the corner of a wall or squeeze between two walls. This matches [`NavGraph::search_grid_jps`].
+    NoCornerCutting,
+    /// Diagonal links are established between all free diagonal neighbors, even if they pass between
+    /// two walls.
+    AllowCornerCutting,
 }
 
 #[derive(Debug, Clone)]
 struct NavNode {
     position: Vec2,
     connections: Vec<(usize, f32)>,
-    ancestor_node: usize,
-    g_value: f32,
-    f_value: f32,
-    state: NodeState,
+    cost_multiplier: f32,
+    tags: u32,
+    height: f32,
 }
 
 impl NavNode {
@@ -52,26 +84,498 @@ impl NavNode {
         Self {
             position,
             connections: Vec::new(),
-            ancestor_node: 0,
-            g_value: 0.0,
-            f_value: 0.0,
-            state: NodeState::Clear,
+            cost_multiplier: 1.0,
+            tags: ALL_TAGS,
+            height: 0.0,
+        }
+    }
+}
+
+/// Contains all the mutable state of a graph search, which is the state, the g and f values,
+/// and the ancestor of every node as well as the open list. It also caches the heuristic distances
+/// of all nodes to the last destination, so that consecutive searches towards the same destination, as
+/// for an agent moving towards a fixed goal, do not recompute them. The cache gets invalidated
+/// when the destination or the node positions of the graph change. A scratch may be allocated once per agent
+/// or thread and then be reused for many searches with [`NavGraph::search_graph_with_scratch`].
+/// As that search only needs an immutable graph, several searches on one shared graph may run in parallel,
+/// each one with its own scratch.
+#[derive(Debug, Clone, Default)]
+pub struct SearchScratch {
+    states: Vec<NodeState>,
+    g_values: Vec<f32>,
+    f_values: Vec<f32>,
+    ancestors: Vec<usize>,
+    open_list: Vec<usize>,
+    heuristic_values: Vec<f32>,
+    heuristic_key: Option<(usize, u64)>,
+    skip_solution_marks: bool,
+    entry_orders: Vec<usize>,
+    entry_count: usize,
+    open_heap: BinaryHeap<OpenEntry>,
+    use_heap: bool,
+}
+
+impl SearchScratch {
+    /// Generates a new, empty search scratch. It grows to the size of the graph on first use.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use astar_lib::a_star::SearchScratch;
+    /// let mut scratch = SearchScratch::new();
+    /// ```
+    pub fn new() -> SearchScratch {
+        SearchScratch::default()
+    }
+
+    /// Gets the state the indicated node had in the last search performed with this scratch.
+    /// Returns None if the node did not exist at that time.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use astar_lib::a_star::{NavGraph, NodeState, SearchScratch};
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let mut scratch = SearchScratch::new();
+    /// graph.search_graph_with_scratch(p0, p0, &mut scratch);
+    /// assert_eq!(scratch.node_state(p0), Some(&NodeState::Solution));
+    /// ```
+    pub fn node_state(&self, node: usize) -> Option<&NodeState> {
+        self.states.get(node)
+    }
+
+    /// Reconstructs the solution path of the last search from the nodes marked as solution.
+    /// Returns an empty path if the last search did not find one.
+    fn solution_path(&self) -> Vec<usize> {
+        let solution_nodes: Vec<usize> = (0..self.states.len())
+            .filter(|node| self.states[*node] == NodeState::Solution)
+            .collect();
+        let Some(destination) = solution_nodes.iter().copied().find(|node| {
+            !solution_nodes
+                .iter()
+                .any(|other| (other != node) && (self.ancestors[*other] == *node))
+        }) else {
+            return Vec::new();
+        };
+
+        let mut path = vec![destination];
+        let mut scan = destination;
+        while scan != self.ancestors[scan] {
+            scan = self.ancestors[scan];
+            path.push(scan);
+        }
+        path.reverse();
+        path
+    }
+
+    /// Fills the heuristic cache with the distances of the positions to the destination point,
+    /// unless it already holds them for the key of destination and layout.
+    fn cache_heuristic<'a>(
+        &mut self,
+        key: (usize, u64),
+        positions: impl Iterator<Item = &'a Vec2>,
+        dest_point: Vec2,
+    ) {
+        if self.heuristic_key != Some(key) {
+            self.heuristic_values.clear();
+            self.heuristic_values
+                .extend(positions.map(|position| position.dist_to(&dest_point)));
+            self.heuristic_key = Some(key);
+        }
+    }
+
+    /// Prepares the scratch for a new search on a graph with the indicated number of nodes.
+    /// The open nodes are kept in a binary heap instead of the open list if indicated.
+    fn reset(&mut self, node_count: usize, use_heap: bool) {
+        self.states.clear();
+        self.states.resize(node_count, NodeState::Clear);
+        self.g_values.resize(node_count, 0.0);
+        self.f_values.resize(node_count, 0.0);
+        self.ancestors.resize(node_count, 0);
+        self.entry_orders.resize(node_count, 0);
+        self.entry_count = 0;
+        self.open_list.clear();
+        self.open_heap.clear();
+        self.use_heap = use_heap;
+    }
+
+    /// Registers that the f value of a visited node has been set, which puts it on the open list if it
+    /// has been newly visited. The entry order breaks ties between equal f values, so the open list and the heap
+    /// always expand the nodes in the same order.
+    fn enter_open(&mut self, node: usize, newly_visited: bool) {
+        self.entry_orders[node] = self.entry_count;
+        self.entry_count += 1;
+        if self.use_heap {
+            // Outdated entries of the node stay in the heap, they get skipped when popped.
+            self.open_heap.push(OpenEntry {
+                f_value: self.f_values[node],
+                order: self.entry_orders[node],
+                node,
+            });
+        } else if newly_visited {
+            self.open_list.push(node);
+        }
+    }
+
+    /// Removes the open node with the lowest f value from the open nodes, where the node entered first wins
+    /// among equal values. The open list gets scanned linearly, while the heap needs logarithmic time.
+    fn pop_best(&mut self) -> Option<usize> {
+        if self.use_heap {
+            while let Some(entry) = self.open_heap.pop() {
+                if self.states[entry.node] == NodeState::Visited
+                    && self.entry_orders[entry.node] == entry.order
+                {
+                    return Some(entry.node);
+                }
+            }
+            return None;
+        }
+        let (best_index, best_candidate) = self
+            .open_list
+            .iter()
+            .copied()
+            .enumerate()
+            .min_by_key(|(_, node)| (OrderedF32(self.f_values[*node]), self.entry_orders[*node]))?;
+        self.open_list.swap_remove(best_index);
+        Some(best_candidate)
+    }
+
+    /// Reconstructs the path by following the ancestors up to a start node, which is its own ancestor,
+    /// and marks its nodes as solution unless the marks are skipped.
+    fn get_path(&mut self, destination_index: usize) -> Vec<usize> {
+        let mut path: Vec<usize> = Vec::new();
+        let mut scan = destination_index;
+
+        while scan != self.ancestors[scan] {
+            path.push(scan);
+            scan = self.ancestors[scan];
+        }
+        path.push(scan);
+        path.reverse();
+        if !self.skip_solution_marks {
+            for node in path.iter() {
+                self.states[*node] = NodeState::Solution;
+            }
+        }
+        path
+    }
+}
+
+/// A cost with a total order, which is used for all comparisons of costs in the searches.
+/// The order is the one of [`f32::total_cmp`], so NaN values sort consistently behind the infinite
+/// costs instead of making the comparisons panic or fail silently.
+#[derive(Debug, Clone, Copy)]
+struct OrderedF32(f32);
+
+impl PartialEq for OrderedF32 {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OrderedF32 {}
+
+impl PartialOrd for OrderedF32 {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OrderedF32 {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+/// An entry of the priority queue used by the Dijkstra based analysis functions.
+/// The ordering is reversed, so that the binary heap pops the lowest cost first.
+#[derive(Debug, Clone, Copy)]
+struct HeapEntry {
+    cost: f32,
+    node: usize,
+}
+
+impl PartialEq for HeapEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for HeapEntry {}
+
+impl PartialOrd for HeapEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for HeapEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        OrderedF32(other.cost)
+            .cmp(&OrderedF32(self.cost))
+            .then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+/// An entry of the binary heap that holds the open nodes of a search on a large graph, which orders the nodes
+/// by their f value first and by their entry order second. The ordering is reversed like the one of [`HeapEntry`].
+#[derive(Debug, Clone, Copy)]
+struct OpenEntry {
+    f_value: f32,
+    order: usize,
+    node: usize,
+}
+
+impl PartialEq for OpenEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for OpenEntry {}
+
+impl PartialOrd for OpenEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for OpenEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        OrderedF32(other.f_value)
+            .cmp(&OrderedF32(self.f_value))
+            .then_with(|| other.order.cmp(&self.order))
+    }
+}
+
+/// An entry of the priority queue of the cooperative search, which orders the states of node and time
+/// by their estimated arrival time first and by their cost second. The ordering is reversed like the one
+/// of [`HeapEntry`].
+#[derive(Debug, Clone, Copy)]
+struct TimedEntry {
+    arrival: usize,
+    cost: f32,
+    node: usize,
+    time: usize,
+}
+
+impl PartialEq for TimedEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for TimedEntry {}
+
+impl PartialOrd for TimedEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for TimedEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other
+            .arrival
+            .cmp(&self.arrival)
+            .then_with(|| OrderedF32(other.cost).cmp(&OrderedF32(self.cost)))
+            .then_with(|| other.node.cmp(&self.node))
+            .then_with(|| other.time.cmp(&self.time))
+    }
+}
+
+/// The optional attributes of an edge, which only get stored for edges that differ from the defaults.
+#[derive(Debug, Clone, Copy)]
+struct EdgeAttributes {
+    tags: u32,
+    clearance: f32,
+    enabled: bool,
+}
+
+impl Default for EdgeAttributes {
+    fn default() -> Self {
+        EdgeAttributes {
+            tags: ALL_TAGS,
+            clearance: f32::INFINITY,
+            enabled: true,
+        }
+    }
+}
+
+/// A summary of the metrics of a graph, as obtained with [`NavGraph::stats`].
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphStats {
+    /// The number of nodes.
+    pub nodes: usize,
+    /// The number of edges.
+    pub edges: usize,
+    /// The number of connected components, where every isolated node is a component of its own.
+    pub components: usize,
+    /// The mean number of edges per node, or 0 for an empty graph.
+    pub avg_degree: f32,
+    /// The lowest edge cost, or 0 if there are no edges.
+    pub min_edge: f32,
+    /// The highest edge cost, or 0 if there are no edges.
+    pub max_edge: f32,
+    /// The mean edge cost, or 0 if there are no edges.
+    pub mean_edge: f32,
+    /// The lower and upper corner of the box containing all nodes, or None for an empty graph.
+    pub bounding_box: Option<([f32; 2], [f32; 2])>,
+}
+
+/// A copy of the nodes and the links of a graph together with the state of the last search, as obtained with
+/// [`NavGraph::snapshot`]. Game engines may copy it into their components in one go.
+#[derive(Debug, Clone, PartialEq)]
+pub struct GraphSnapshot {
+    /// The positions of the nodes, the n-th element belongs to the handle n.
+    pub positions: Vec<[f32; 2]>,
+    /// The states of the nodes in the last search, in the same order as the positions.
+    pub states: Vec<NodeState>,
+    /// The links as the handles of their nodes and the hint whether they are part of the solution, in the order
+    /// of [`NavGraph::links_in_order`].
+    pub edges: Vec<(usize, usize, bool)>,
+}
+
+/// The options of a search with [`NavGraph::search_graph_with_options`], which combine into one query. The
+/// default options perform the same search as [`NavGraph::search_graph`].
+///
+/// # Example
+/// ```
+/// use astar_lib::a_star::SearchOptions;
+/// let options = SearchOptions {
+///     heuristic_weight: Some(2.0),
+///     max_expansions: Some(100),
+///     ..SearchOptions::default()
+/// };
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SearchOptions<'a> {
+    /// The weight the straight-line heuristic gets multiplied with, as with [`NavGraph::set_heuristic_weight`].
+    /// None uses the weight of the graph.
+    pub heuristic_weight: Option<f32>,
+    /// The maximum number of nodes the search closes before it gives up, None for no limit.
+    pub max_expansions: Option<usize>,
+    /// The danger cost of entering a node, as with [`NavGraph::search_graph_with_danger`]. The values are indexed
+    /// by the node handles; missing entries count as 0.0.
+    pub danger: &'a [f32],
+    /// The mask the tags of a traversed edge have to intersect, as with [`NavGraph::search_graph_with_mask`].
+    /// None traverses all edges.
+    pub allowed_mask: Option<u32>,
+    /// The nodes the path must not pass through, as with [`NavGraph::search_graph_avoiding`].
+    pub avoid: &'a [usize],
+}
+
+/// Checks that a heuristic weight is finite and not negative, as the search estimates would be meaningless otherwise.
+fn assert_valid_heuristic_weight(weight: f32) {
+    assert!(
+        weight.is_finite() && weight >= 0.0,
+        "The heuristic weight has to be finite and may not be negative."
+    );
+}
+
+/// The result of a search with [`NavGraph::search_graph_with_options`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchResult {
+    /// The search has found the contained path.
+    Found(Vec<usize>),
+    /// The search has explored everything reachable without finding a path.
+    NoPath,
+    /// The search has closed the maximum number of nodes without reaching the destination.
+    Capped,
+}
+
+impl SearchResult {
+    /// Gets the path found, or None if the search has not found one.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::SearchResult;
+    /// assert_eq!(SearchResult::Found(vec![0, 1]).into_path(), Some(vec![0, 1]));
+    /// assert_eq!(SearchResult::Capped.into_path(), None);
+    /// ```
+    pub fn into_path(self) -> Option<Vec<usize>> {
+        match self {
+            SearchResult::Found(path) => Some(path),
+            SearchResult::NoPath | SearchResult::Capped => None,
         }
     }
+}
+
+/// Remembers the result of the last nearest node query of [`NavGraph::find_nearest_node_cached`], so that
+/// consecutive queries at close positions, like the ones of a moving cursor, do not need to scan the nodes again.
+#[derive(Debug, Clone, Default)]
+pub struct NearestQueryCache {
+    position: Vec2,
+    nearest: Option<usize>,
+    margin: f32,
+    layout_id: u64,
+    checked: usize,
+}
 
-    fn reset(&mut self) {
-        self.state = NodeState::Clear;
+impl NearestQueryCache {
+    /// Generates a new, empty cache, the first query with it scans all nodes.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NearestQueryCache;
+    /// let cache = NearestQueryCache::new();
+    /// ```
+    pub fn new() -> NearestQueryCache {
+        NearestQueryCache::default()
     }
 }
 
 /// The graph structure that may be used for navigation, with all the manipulation and searching
 /// options. Nodes in this graph are supposed to represent positions in a two-dimensional coordinate system
 /// and the edge annotation is always the distance between those positions.
+///
+/// Two nodes are connected by at most one link. All methods establishing links, including the import
+/// [`NavGraph::set_links`], reject a second link between the same nodes with
+/// [`ConnectionError::LinkAlreadyExists`]. The import *from_petgraph* collapses parallel edges into one link
+/// with the minimum cost instead, see [`NavGraph::dedup_edges`].
+///
+/// The graph itself only consists of the positions and the connections. The mutable state of a search
+/// lives in a separate [`SearchScratch`]. [`NavGraph::search_graph`] keeps the scratch of the last search
+/// for visualization purposes, which is why it needs mutable access. The searches with an external scratch
+/// only borrow the graph immutably, so one graph may be shared between threads for concurrent pathfinding.
 pub struct NavGraph {
     nodes: Vec<NavNode>,
     links: Vec<(usize, usize)>,
+    edge_attributes: HashMap<(usize, usize), EdgeAttributes>,
+    last_search: SearchScratch,
+    check_heuristic: bool,
+    sorted_connections: bool,
+    reservations: HashSet<(usize, usize)>,
+    layout_id: u64,
+    generation: u64,
+    landmark_distances: Vec<Vec<Option<f32>>>,
+    landmark_generation: u64,
+    kd_tree: Option<(KdTree, u64)>,
+    heap_threshold: usize,
+    heuristic_weight: f32,
+    improvement_epsilon: f32,
+    last_query: Option<(usize, usize)>,
+    path_cache: VecDeque<CachedPath>,
+    path_cache_generation: u64,
 }
 
+/// The default number of nodes from which on the searches keep the open nodes in a binary heap.
+/// Below that, a linear scan of the open list has less overhead.
+const HEAP_THRESHOLD: usize = 32;
+
+/// The relative cost difference below which [`NavGraph::all_shortest_paths`] considers two paths equally short.
+const SHORTEST_PATH_TOLERANCE: f32 = 1e-5;
+
+/// The default relative amount by which a new cost of a visited node has to be lower to replace its ancestor.
+const IMPROVEMENT_EPSILON: f32 = 1e-6;
+
+/// The number of recent query results [`NavGraph::search_graph_cached`] keeps.
+const PATH_CACHE_SIZE: usize = 16;
+
+/// A result of [`NavGraph::search_graph_cached`] with the start and the destination of its query.
+type CachedPath = ((usize, usize), Option<Vec<usize>>);
+
 impl Default for NavGraph {
     fn default() -> Self {
         Self::new()
@@ -91,6 +595,42 @@ impl NavGraph {
         NavGraph {
             nodes: Vec::new(),
             links: Vec::new(),
+            edge_attributes: HashMap::new(),
+            last_search: SearchScratch::new(),
+            check_heuristic: false,
+            sorted_connections: false,
+            reservations: HashSet::new(),
+            layout_id: fresh_id(),
+            generation: fresh_id(),
+            landmark_distances: Vec::new(),
+            landmark_generation: 0,
+            kd_tree: None,
+            heap_threshold: HEAP_THRESHOLD,
+            heuristic_weight: 1.0,
+            improvement_epsilon: IMPROVEMENT_EPSILON,
+            last_query: None,
+            path_cache: VecDeque::new(),
+            path_cache_generation: 0,
+        }
+    }
+
+    /// Generates a new nav graph with room for the indicated numbers of nodes and links, so that loading a graph
+    /// of known size does not reallocate the node and the link list.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::with_capacity(2, 1);
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// ```
+    pub fn with_capacity(nodes: usize, edges: usize) -> NavGraph {
+        NavGraph {
+            nodes: Vec::with_capacity(nodes),
+            links: Vec::with_capacity(edges),
+            ..NavGraph::new()
         }
     }
 
@@ -98,6 +638,9 @@ impl NavGraph {
     /// The result is meaningful after a graph search has been performed. The use case
     /// of this method is mainly to perform visualizations of the algorithm, as performed in the
     /// openglapp example.
+    ///
+    /// The nodes are guaranteed to be iterated in the sequence they have been added with *add_node*,
+    /// so the n-th element belongs to the handle n.
     /// # Example
     ///
     /// ```
@@ -113,19 +656,123 @@ impl NavGraph {
     pub fn get_all_nodes_with_state(&self) -> impl Iterator<Item = ([f32; 2], &NodeState)> {
         self.nodes
             .iter()
-            .map(|node| ((node.position).into(), &node.state))
+            .enumerate()
+            .map(|(index, node)| ((node.position).into(), self.node_state(index)))
+    }
+
+    /// Gets an iterator over the handles of all nodes that have been closed in the last graph search.
+    /// This includes the nodes of the solution, as they have been closed before being marked.
+    /// The use case is analyzing how much of the graph the search had to explore.
+    /// # Example
+    ///
+    /// ```
+    ///  use astar_lib::a_star::NavGraph;
+    ///  let mut graph = NavGraph::new();
+    ///  let p0 = graph.add_node([0.0, 0.0]);
+    ///  let p1 = graph.add_node([0.5, 0.5]);
+    ///  graph.connect_nodes(p0, p1).unwrap();
+    ///  graph.search_graph(p0, p1);
+    ///
+    /// assert_eq!(graph.closed_nodes().count(), 2);
+    /// ```
+    pub fn closed_nodes(&self) -> impl Iterator<Item = usize> + '_ {
+        (0..self.nodes.len()).filter(|index| {
+            matches!(
+                self.node_state(*index),
+                NodeState::Closed | NodeState::Solution
+            )
+        })
+    }
+
+    /// Gets the cost of reaching the indicated node from the start in the search tree of the last graph search,
+    /// which may be used to render iso-distance contours over the explored region. The cost is final for closed
+    /// and solution nodes, while it is the best cost found so far for nodes that were only visited. Extra costs of
+    /// a search variant, like the danger in [`NavGraph::search_graph_with_danger`], are included.
+    /// Returns None if the node has not been reached in the last search or does not exist.
+    /// # Example
+    ///
+    /// ```
+    ///  use astar_lib::a_star::NavGraph;
+    ///  let mut graph = NavGraph::new();
+    ///  let p0 = graph.add_node([0.0, 0.0]);
+    ///  let p1 = graph.add_node([3.0, 4.0]);
+    ///  let p2 = graph.add_node([9.0, 9.0]);
+    ///  graph.connect_nodes(p0, p1).unwrap();
+    ///  graph.search_graph(p0, p1);
+    ///
+    /// assert_eq!(graph.cost_to(p1), Some(5.0));
+    /// assert_eq!(graph.cost_to(p2), None);
+    /// ```
+    pub fn cost_to(&self, node: usize) -> Option<f32> {
+        if *self.node_state(node) == NodeState::Clear {
+            return None;
+        }
+        self.last_search.g_values.get(node).copied()
+    }
+
+    /// Clears the markings of the last graph search, so that all nodes report [`NodeState::Clear`], together with
+    /// the [`NavGraph::last_query`]. The markings persist after a search for visualization purposes, this may be used
+    /// to display an unmarked graph.
+    /// # Example
+    ///
+    /// ```
+    ///  use astar_lib::a_star::{NavGraph, NodeState};
+    ///  let mut graph = NavGraph::new();
+    ///  let p0 = graph.add_node([0.0, 0.0]);
+    ///  let p1 = graph.add_node([0.5, 0.5]);
+    ///  graph.connect_nodes(p0, p1).unwrap();
+    ///  graph.search_graph(p0, p1);
+    ///  graph.clear_search_state();
+    ///
+    /// assert!(graph.get_all_nodes_with_state().all(|(_, state)| *state == NodeState::Clear));
+    /// ```
+    pub fn clear_search_state(&mut self) {
+        self.last_search = SearchScratch::new();
+        self.last_query = None;
+    }
+
+    /// Gets the start and the destination node of the last search that marks the node states of the graph, like
+    /// [`NavGraph::search_graph`], so that the search may be labeled or repeated without keeping track of it
+    /// separately. Searches from several start nodes, like [`NavGraph::search_graph_multi_start`], do not have
+    /// a single start and leave None. Returns None before the first search and after
+    /// [`NavGraph::clear_search_state`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    ///  use astar_lib::a_star::NavGraph;
+    ///  let mut graph = NavGraph::new();
+    ///  let p0 = graph.add_node([0.0, 0.0]);
+    ///  let p1 = graph.add_node([0.5, 0.5]);
+    ///  graph.connect_nodes(p0, p1).unwrap();
+    ///  assert_eq!(graph.last_query(), None);
+    ///  graph.search_graph(p1, p0);
+    ///
+    /// assert_eq!(graph.last_query(), Some((p1, p0)));
+    /// ```
+    pub fn last_query(&self) -> Option<(usize, usize)> {
+        self.last_query
+    }
+
+    /// Gets the state of a node in the last graph search. Nodes added after that search are clear.
+    fn node_state(&self, node: usize) -> &NodeState {
+        self.last_search
+            .node_state(node)
+            .unwrap_or(&NodeState::Clear)
     }
 
     /// Checks if a link handed over is a solution link.
     fn is_solution_link(&self, start_node: &usize, end_node: &usize) -> bool {
-        (self.nodes[*start_node].state == NodeState::Solution)
-            && (self.nodes[*end_node].state == NodeState::Solution)
+        (*self.node_state(*start_node) == NodeState::Solution)
+            && (*self.node_state(*end_node) == NodeState::Solution)
     }
 
     /// Gets an iterator of all the links consisting of start position, end position, and a hint whether this link is part of the solution.
     /// The result is meaningful after a graph search has been performed. The use case
     /// of this method is mainly to perform visualizations of the algorithm, as performed in the
     /// openglapp example.
+    ///
+    /// The links are iterated in the same order as [`NavGraph::links_in_order`].
     /// # Example
     ///
     /// ```
@@ -151,40 +798,49 @@ impl NavGraph {
         })
     }
 
-    /// Finds the nearest node to the indicated position within a certain
-    /// maximum radius. If there is none, it returns none. The returned
-    /// value is the index generated by add_node.
+    /// Gets a snapshot of the positions and the states of all nodes and of all links with their solution hints.
+    /// It contains the same data as [`NavGraph::get_all_nodes_with_state`] and
+    /// [`NavGraph::get_all_links_with_solution_hint`], with the links given by handles, but collected in one pass.
+    /// This lets a game engine mirror a large graph into its components every frame.
     ///
     /// # Example
+    ///
     /// ```
-    /// use astar_lib::a_star::NavGraph;
-    /// let mut graph = NavGraph::new();
-    /// let p0 = graph.add_node([0.0, 0.0]);
-    /// let index = graph.find_nearest_node_with_radius([0.00001, 0.0], 0.01).unwrap();
+    ///  use astar_lib::a_star::{NavGraph, NodeState};
+    ///  let mut graph = NavGraph::new();
+    ///  let p0 = graph.add_node([0.0, 0.0]);
+    ///  let p1 = graph.add_node([0.5, 0.5]);
+    ///  graph.connect_nodes(p0, p1).unwrap();
+    ///  graph.search_graph(p0, p1);
+    ///
+    /// let snapshot = graph.snapshot();
+    /// assert_eq!(snapshot.positions, [[0.0, 0.0], [0.5, 0.5]]);
+    /// assert_eq!(snapshot.states, [NodeState::Solution, NodeState::Solution]);
+    /// assert_eq!(snapshot.edges, [(p0, p1, true)]);
     /// ```
-    pub fn find_nearest_node_with_radius(&self, position: [f32; 2], radius: f32) -> Option<usize> {
-        let mut min_dist = f32::MAX;
-        let mut best_index = 0usize;
-        let probing = Vec2::from(position);
-
-        for (index, node) in self.nodes.iter().enumerate() {
-            let dist = node.position.dist_to(&probing);
-            if dist < min_dist {
-                min_dist = dist;
-                best_index = index;
-            }
-        }
-
-        if min_dist <= radius {
-            Some(best_index)
-        } else {
-            None
+    pub fn snapshot(&self) -> GraphSnapshot {
+        GraphSnapshot {
+            positions: self.nodes.iter().map(|node| node.position.into()).collect(),
+            states: (0..self.nodes.len())
+                .map(|index| self.node_state(index).clone())
+                .collect(),
+            edges: self
+                .links
+                .iter()
+                .map(|(start_node, end_node)| {
+                    (
+                        *start_node,
+                        *end_node,
+                        self.is_solution_link(start_node, end_node),
+                    )
+                })
+                .collect(),
         }
     }
 
-    /// Adds a position to the nav graph and returns a handle index that may be used for
-    /// connecting the nodes. The returning handles are given in registration sequence and
-    /// starting from 0.
+    /// Gets the indices of the links of the solution of the last graph search in path order. The indices refer to
+    /// the sequence of [`NavGraph::links_in_order`], so edges may be rendered or animated one after another
+    /// without matching positions. The result is empty if the last search found no path.
     ///
     /// # Example
     /// ```
@@ -192,284 +848,10135 @@ impl NavGraph {
     /// let mut graph = NavGraph::new();
     /// let p0 = graph.add_node([0.0, 0.0]);
     /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.search_graph(p0, p2);
     ///
-    /// assert!((p0 == 0) && (p1 == 1), "Illegal sequence of nodes.")
+    /// assert_eq!(graph.solution_edge_indices(), [1, 0]);
     /// ```
-    pub fn add_node(&mut self, position: [f32; 2]) -> usize {
-        let ret_val = self.nodes.len();
-        self.nodes.push(NavNode::new(Vec2::from(position)));
-        ret_val
+    pub fn solution_edge_indices(&self) -> Vec<usize> {
+        self.last_search
+            .solution_path()
+            .windows(2)
+            .filter_map(|pair| self.get_link_index(pair[0], pair[1]))
+            .collect()
     }
 
-    /// Gets the index of the link of the indicated node pairing. Returns None if it does not exist.
-    fn get_link_index(&self, node1: usize, node2: usize) -> Option<usize> {
-        if let Some(result) = self
-            .links
+    /// Gets the handles of the nodes on the solution of the last search in path order, from the start to the
+    /// destination. This distinguishes them from the nodes that have only been explored.
+    /// The result is empty if the last search found no path.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p2, p1).unwrap();
+    /// graph.connect_nodes(p1, p0).unwrap();
+    /// graph.search_graph(p2, p0);
+    ///
+    /// assert_eq!(graph.solution_nodes(), [p2, p1, p0]);
+    /// ```
+    pub fn solution_nodes(&self) -> Vec<usize> {
+        self.last_search.solution_path()
+    }
+
+    /// Gets the node following the indicated one on the solution of the last search, which is the next step
+    /// towards the destination. This allows following the solution without storing the path.
+    /// Returns None for the destination itself, for nodes not on the solution and if the last search
+    /// found no path.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.search_graph(p0, p2);
+    ///
+    /// assert_eq!(graph.next_hop(p0), Some(p1));
+    /// assert_eq!(graph.next_hop(p2), None);
+    /// ```
+    pub fn next_hop(&self, from: usize) -> Option<usize> {
+        let scratch = &self.last_search;
+        if scratch.node_state(from) != Some(&NodeState::Solution) {
+            return None;
+        }
+        (0..scratch.states.len()).find(|node| {
+            (*node != from)
+                && (scratch.states[*node] == NodeState::Solution)
+                && (scratch.ancestors[*node] == from)
+        })
+    }
+
+    /// Gets an iterator of all the links as pairs of node handles. The links are guaranteed to be
+    /// iterated in the sequence they have been established with *connect_nodes*. Disconnecting
+    /// nodes removes the link but keeps the relative order of the remaining ones. This stable order
+    /// may be relied upon for rendering, e.g. when comparing frames.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([0.5, 0.5]);
+    /// let p2 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let links: Vec<(usize, usize)> = graph.links_in_order().collect();
+    /// assert_eq!(links, [(p1, p2), (p0, p1)]);
+    /// ```
+    pub fn links_in_order(&self) -> impl Iterator<Item = (usize, usize)> + '_ {
+        self.links.iter().copied()
+    }
+
+    /// Gets an iterator of all links together with their stored costs, in the same order as
+    /// [`NavGraph::links_in_order`]. The stored cost is usually the distance of the two nodes, but differs
+    /// for moved nodes before [`NavGraph::recompute_edge_costs`] and for shortcut links. The node cost multipliers
+    /// are not included. This may be used for cost aware rendering and analysis.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([3.0, 4.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let edges: Vec<(usize, usize, f32)> = graph.edges().collect();
+    /// assert_eq!(edges, [(p0, p1, 5.0)]);
+    /// ```
+    pub fn edges(&self) -> impl Iterator<Item = (usize, usize, f32)> + '_ {
+        self.links.iter().map(|(node1, node2)| {
+            let cost = self.get_edge(*node1, *node2).unwrap();
+            (*node1, *node2, cost)
+        })
+    }
+
+    /// Gets the degree of the indicated node, which is the number of its connections.
+    /// As all links are undirected, every connection may be walked in both directions, so the degree is
+    /// the number of links leaving the node as well as the number of links entering it.
+    /// Returns None if the node does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert_eq!(graph.node_degree(p0), Some(1));
+    /// assert_eq!(graph.node_degree(5), None);
+    /// ```
+    pub fn node_degree(&self, node: usize) -> Option<usize> {
+        self.nodes
+            .get(node)
+            .map(|nav_node| nav_node.connections.len())
+    }
+
+    /// Gets the number of links entering the indicated node, which counts the nodes that list it among their
+    /// connections. As all links are undirected, this is the same as [`NavGraph::node_degree`].
+    /// Returns None if the node does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert_eq!(graph.node_in_degree(p1), Some(1));
+    /// ```
+    pub fn node_in_degree(&self, node: usize) -> Option<usize> {
+        self.nodes.get(node)?;
+        Some(
+            self.nodes
+                .iter()
+                .filter(|other| {
+                    other
+                        .connections
+                        .iter()
+                        .any(|(partner, _)| *partner == node)
+                })
+                .count(),
+        )
+    }
+
+    /// Gets the number of links leaving the indicated node, which is the number of its connections.
+    /// As all links are undirected, this is the same as [`NavGraph::node_degree`].
+    /// Returns None if the node does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert_eq!(graph.node_out_degree(p0), Some(1));
+    /// ```
+    pub fn node_out_degree(&self, node: usize) -> Option<usize> {
+        self.nodes
+            .get(node)
+            .map(|nav_node| nav_node.connections.len())
+    }
+
+    /// Gets the degree distribution of the graph. The entry at index d contains the number of nodes
+    /// with degree d. This may be used to tune the graph generation, and the entry at index 0 reveals
+    /// isolated nodes that can never be reached.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert_eq!(graph.degree_histogram(), [1, 2]);
+    /// ```
+    pub fn degree_histogram(&self) -> Vec<usize> {
+        let mut histogram = Vec::new();
+        for node in self.nodes.iter() {
+            let degree = node.connections.len();
+            if histogram.len() <= degree {
+                histogram.resize(degree + 1, 0);
+            }
+            histogram[degree] += 1;
+        }
+        histogram
+    }
+
+    /// Gets a summary of the metrics of the graph in one call, which is meant for tooling and dashboards.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 2.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let stats = graph.stats();
+    /// assert_eq!(stats.components, 2);
+    /// assert_eq!(stats.bounding_box, Some(([0.0, 0.0], [1.0, 2.0])));
+    /// ```
+    pub fn stats(&self) -> GraphStats {
+        let mut bounding_box: Option<(Vec2, Vec2)> = None;
+        for node in self.nodes.iter() {
+            bounding_box = Some(match bounding_box {
+                None => (node.position, node.position),
+                Some((lower, upper)) => (lower.min(&node.position), upper.max(&node.position)),
+            });
+        }
+
+        let mut min_edge = f32::INFINITY;
+        let mut max_edge = f32::NEG_INFINITY;
+        let mut sum = 0.0;
+        for (node1, node2) in self.links.iter() {
+            let cost = self.get_edge(*node1, *node2).unwrap();
+            min_edge = min_edge.min(cost);
+            max_edge = max_edge.max(cost);
+            sum += cost;
+        }
+        let edges = self.links.len();
+        let (min_edge, max_edge, mean_edge) = if edges == 0 {
+            (0.0, 0.0, 0.0)
+        } else {
+            (min_edge, max_edge, sum / edges as f32)
+        };
+
+        let mut reached = vec![false; self.nodes.len()];
+        let mut components = 0;
+        for seed in 0..self.nodes.len() {
+            if reached[seed] {
+                continue;
+            }
+            components += 1;
+            reached[seed] = true;
+            let mut todo = vec![seed];
+            while let Some(node) = todo.pop() {
+                for (partner, _) in self.nodes[node].connections.iter() {
+                    if !reached[*partner] && self.edge_enabled_unchecked(node, *partner) {
+                        reached[*partner] = true;
+                        todo.push(*partner);
+                    }
+                }
+            }
+        }
+
+        let nodes = self.nodes.len();
+        GraphStats {
+            nodes,
+            edges,
+            components,
+            avg_degree: if nodes == 0 {
+                0.0
+            } else {
+                2.0 * edges as f32 / nodes as f32
+            },
+            min_edge,
+            max_edge,
+            mean_edge,
+            bounding_box: bounding_box.map(|(lower, upper)| (lower.into(), upper.into())),
+        }
+    }
+
+    /// Computes the smallest circle that contains all node positions with the algorithm of Welzl, and returns its
+    /// center and radius. For roughly circular maps this is a tighter fit than the bounding box of [`NavGraph::stats`],
+    /// for example for a circular world boundary or a camera. The nodes are processed in a scrambled order, so
+    /// the expected cost is O(V). Nodes with a NaN coordinate are ignored. Returns None for an empty graph.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// graph.add_node([0.0, 0.0]);
+    /// graph.add_node([2.0, 0.0]);
+    /// graph.add_node([1.0, 0.5]);
+    /// assert_eq!(graph.bounding_circle(), Some(([1.0, 0.0], 1.0)));
+    /// ```
+    pub fn bounding_circle(&self) -> Option<([f32; 2], f32)> {
+        let mut points: Vec<(u64, Vec2)> = self
+            .nodes
             .iter()
-            .position(|element| *element == (node1, node2))
-        {
-            return Some(result);
-        } else if let Some(result) = self
+            .enumerate()
+            .filter(|(_, node)| !node.position.x.is_nan() && !node.position.y.is_nan())
+            .map(|(index, node)| {
+                (
+                    (index as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15),
+                    node.position,
+                )
+            })
+            .collect();
+        points.sort_unstable_by_key(|(scramble, _)| *scramble);
+        let points: Vec<Vec2> = points.into_iter().map(|(_, point)| point).collect();
+
+        let contains = |(center, radius): (Vec2, f32), point: &Vec2| {
+            center.dist_to(point) <= radius + 0.00001 * radius.max(1.0)
+        };
+        let mut circle = (*points.first()?, 0.0);
+        for (first_index, first) in points.iter().enumerate() {
+            if contains(circle, first) {
+                continue;
+            }
+            // The first point is on the boundary of the circle of all points so far.
+            circle = (*first, 0.0);
+            for (second_index, second) in points[..first_index].iter().enumerate() {
+                if contains(circle, second) {
+                    continue;
+                }
+                circle = circle_from_two(first, second);
+                for third in points[..second_index].iter() {
+                    if !contains(circle, third) {
+                        circle = circle_from_three(first, second, third);
+                    }
+                }
+            }
+        }
+        Some((circle.0.into(), circle.1))
+    }
+
+    /// Estimates the number of bytes the graph occupies on the heap and in its own struct, which may be used
+    /// for profiling large maps. This accounts for the capacities of the vectors rather than their lengths,
+    /// including the connections of every node, as well as for the stored edge attributes, the cached paths
+    /// and the scratch of the last search. The overhead of the allocator is not included.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let empty = graph.memory_footprint();
+    /// graph.add_node([0.0, 0.0]);
+    /// assert!(graph.memory_footprint() > empty);
+    /// ```
+    pub fn memory_footprint(&self) -> usize {
+        let nodes = self.nodes.capacity() * size_of::<NavNode>()
+            + self
+                .nodes
+                .iter()
+                .map(|node| node.connections.capacity() * size_of::<(usize, f32)>())
+                .sum::<usize>();
+        let links = self.links.capacity() * size_of::<(usize, usize)>();
+        let edge_attributes =
+            self.edge_attributes.capacity() * size_of::<((usize, usize), EdgeAttributes)>();
+        let reservations = self.reservations.capacity() * size_of::<(usize, usize)>();
+        let path_cache = self.path_cache.capacity() * size_of::<CachedPath>()
+            + self
+                .path_cache
+                .iter()
+                .map(|(_, path)| {
+                    path.as_ref()
+                        .map_or(0, |path| path.capacity() * size_of::<usize>())
+                })
+                .sum::<usize>();
+        let landmarks = self.landmark_distances.capacity() * size_of::<Vec<Option<f32>>>()
+            + self
+                .landmark_distances
+                .iter()
+                .map(|distances| distances.capacity() * size_of::<Option<f32>>())
+                .sum::<usize>();
+        let kd_tree = self
+            .kd_tree
+            .as_ref()
+            .map_or(0, |(tree, _)| tree.memory_footprint());
+        let scratch = &self.last_search;
+        let scratch = scratch.states.capacity() * size_of::<NodeState>()
+            + (scratch.g_values.capacity()
+                + scratch.f_values.capacity()
+                + scratch.heuristic_values.capacity())
+                * size_of::<f32>()
+            + (scratch.ancestors.capacity()
+                + scratch.open_list.capacity()
+                + scratch.entry_orders.capacity())
+                * size_of::<usize>()
+            + scratch.open_heap.capacity() * size_of::<OpenEntry>();
+        size_of::<NavGraph>()
+            + nodes
+            + links
+            + edge_attributes
+            + reservations
+            + path_cache
+            + landmarks
+            + kd_tree
+            + scratch
+    }
+
+    /// Counts the nodes that can be reached from the start node, including the start node itself.
+    /// This tells how much of the map an agent can reach and is cheaper than [`NavGraph::dijkstra_from`],
+    /// as it does not need any costs. Returns 0 if the start node does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert_eq!(graph.reachable_count(p0), 2);
+    /// assert_eq!(graph.reachable_count(p2), 1);
+    /// ```
+    pub fn reachable_count(&self, start_index: usize) -> usize {
+        self.reachable_nodes(start_index).len()
+    }
+
+    /// Collects the neighborhood of a node within a number of hops along the links, regardless of their lengths.
+    /// This may be used to highlight what is reachable in k steps. The nodes are returned in the order of their
+    /// hop count, starting with the node itself. The links are the ones that can be walked within k steps,
+    /// which are the links with at least one end node less than k hops away, in the order of
+    /// [`NavGraph::links_in_order`]. Both are empty if the node does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// let (nodes, links) = graph.k_hop_neighborhood(p0, 1);
+    /// assert_eq!(nodes, [p0, p1]);
+    /// assert_eq!(links, [(p0, p1)]);
+    /// ```
+    pub fn k_hop_neighborhood(&self, node: usize, k: usize) -> (Vec<usize>, Vec<(usize, usize)>) {
+        if node >= self.nodes.len() {
+            return (Vec::new(), Vec::new());
+        }
+        let mut hops: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        hops[node] = Some(0);
+        let mut nodes = vec![node];
+        let mut next = 0;
+        while let Some(current) = nodes.get(next).copied() {
+            next += 1;
+            let current_hops = hops[current].unwrap();
+            if current_hops == k {
+                continue;
+            }
+            for (partner, _) in self.nodes[current].connections.iter() {
+                if hops[*partner].is_none() {
+                    hops[*partner] = Some(current_hops + 1);
+                    nodes.push(*partner);
+                }
+            }
+        }
+        let within = |node: usize| hops[node].is_some_and(|count| count < k);
+        let links = self
             .links
             .iter()
-            .position(|element| *element == (node2, node1))
-        {
-            return Some(result);
+            .copied()
+            .filter(|(node1, node2)| within(*node1) || within(*node2))
+            .collect();
+        (nodes, links)
+    }
+
+    /// Picks a random node that can be reached from the indicated node, excluding that node itself.
+    /// Every reachable node is equally likely, which may be used to choose destinations for wandering agents
+    /// that a search is guaranteed to find. Returns None if no other node is reachable or the node does not exist.
+    ///
+    /// Only available with the feature *rand*.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert_eq!(graph.random_reachable(p0, &mut rand::rng()), Some(p1));
+    /// assert_eq!(graph.random_reachable(p2, &mut rand::rng()), None);
+    /// ```
+    #[cfg(feature = "rand")]
+    pub fn random_reachable(&self, from: usize, rng: &mut impl rand::Rng) -> Option<usize> {
+        let candidates: Vec<usize> = self
+            .reachable_nodes(from)
+            .into_iter()
+            .filter(|node| *node != from)
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        Some(candidates[rng.random_range(0..candidates.len())])
+    }
+
+    /// Collects the nodes that can be reached from the start node, including the start node itself,
+    /// in an unspecified order. The result is empty if the start node does not exist.
+    fn reachable_nodes(&self, start_index: usize) -> Vec<usize> {
+        if start_index >= self.nodes.len() {
+            return Vec::new();
+        }
+        let mut reached = vec![false; self.nodes.len()];
+        reached[start_index] = true;
+        let mut todo = vec![start_index];
+        let mut result = Vec::new();
+        while let Some(node) = todo.pop() {
+            result.push(node);
+            for (partner, _) in self.nodes[node].connections.iter() {
+                if !reached[*partner] && self.edge_enabled_unchecked(node, *partner) {
+                    reached[*partner] = true;
+                    todo.push(*partner);
+                }
+            }
+        }
+        result
+    }
+
+    /// Checks if two nodes are two-edge-connected, which means that there are at least two routes between them
+    /// that do not share a link. A single blocked link then never separates them, so an agent always has a fallback
+    /// route. The bridges of the component, which are the links whose removal disconnects it, are found with a depth-first
+    /// search, and the nodes are two-edge-connected if they are still connected without the bridges. A node is
+    /// two-edge-connected with itself. Returns false if one of the nodes does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// let p3 = graph.add_node([2.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p2, p0).unwrap();
+    /// graph.connect_nodes(p2, p3).unwrap();
+    ///
+    /// assert!(graph.two_edge_connected(p0, p2));
+    /// assert!(!graph.two_edge_connected(p0, p3));
+    /// ```
+    pub fn two_edge_connected(&self, node1: usize, node2: usize) -> bool {
+        if node1.max(node2) >= self.nodes.len() {
+            return false;
+        }
+
+        // The iterative depth-first search stores the discovery order and the lowest discovery order
+        // reachable with one back link from the subtree of every node.
+        let mut discovery: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut low = vec![0; self.nodes.len()];
+        let mut bridges = HashSet::new();
+        let mut stack: Vec<(usize, usize, usize)> = vec![(node1, node1, 0)];
+        discovery[node1] = Some(0);
+        let mut counter = 1;
+        while let Some((node, parent, next)) = stack.last_mut() {
+            let node = *node;
+            let parent = *parent;
+            if let Some((partner, _)) = self.nodes[node].connections.get(*next) {
+                *next += 1;
+                let partner = *partner;
+                if !self.edge_enabled_unchecked(node, partner) {
+                    continue;
+                }
+                match discovery[partner] {
+                    Some(order) => {
+                        if partner != parent {
+                            low[node] = low[node].min(order);
+                        }
+                    }
+                    None => {
+                        discovery[partner] = Some(counter);
+                        low[partner] = counter;
+                        counter += 1;
+                        stack.push((partner, node, 0));
+                    }
+                }
+            } else {
+                stack.pop();
+                if node != parent {
+                    low[parent] = low[parent].min(low[node]);
+                    if discovery[parent].is_some_and(|order| low[node] > order) {
+                        bridges.insert(edge_key(node, parent));
+                    }
+                }
+            }
+        }
+
+        let mut reached = vec![false; self.nodes.len()];
+        reached[node1] = true;
+        let mut todo = vec![node1];
+        while let Some(node) = todo.pop() {
+            for (partner, _) in self.nodes[node].connections.iter() {
+                if !reached[*partner]
+                    && self.edge_enabled_unchecked(node, *partner)
+                    && !bridges.contains(&edge_key(node, *partner))
+                {
+                    reached[*partner] = true;
+                    todo.push(*partner);
+                }
+            }
+        }
+        reached[node2]
+    }
+
+    /// Gets the handles of all isolated nodes, which are the nodes without any connection.
+    /// Such nodes can never be reached by a search, so this helps cleaning generated graphs before use.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert_eq!(graph.isolated_nodes(), [p2]);
+    /// ```
+    pub fn isolated_nodes(&self) -> Vec<usize> {
+        self.nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, node)| node.connections.is_empty())
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// Removes all isolated nodes from the graph. As the handles are indices, this changes the handles
+    /// of the remaining nodes. The returned mapping contains the new handle for every old handle,
+    /// or None if the node has been removed. The state of the last search gets cleared.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p2).unwrap();
+    /// assert_eq!(graph.prune_isolated(), [Some(0), None, Some(1)]);
+    /// ```
+    pub fn prune_isolated(&mut self) -> Vec<Option<usize>> {
+        let keep: Vec<bool> = self
+            .nodes
+            .iter()
+            .map(|node| !node.connections.is_empty())
+            .collect();
+        self.retain_nodes(&keep)
+    }
+
+    /// Coarsens the graph to the indicated landmark nodes, for example for a lower level of detail of a nav mesh.
+    /// All other nodes are removed, and shortcut links between the landmarks replace the paths through them,
+    /// so that the shortest path costs between all pairs of landmarks change by at most the cost tolerance.
+    /// Shortcuts are only added where the links kept between landmarks do not already meet the tolerance,
+    /// starting with the closest pairs. A larger tolerance therefore leads to fewer links.
+    ///
+    /// The cost of a shortcut is the cost of the path it replaces, which is generally longer than the distance
+    /// of its end nodes, so [`NavGraph::recompute_edge_costs`] destroys the guarantee. The cost multipliers of the
+    /// landmarks are taken into account and have to be positive. Invalid handles in the landmarks are ignored.
+    /// As the handles change, the returned mapping contains the new handle for every old handle,
+    /// or None if the node has been removed. The state of the last search gets cleared.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 1.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// let mapping = graph.prune_to_landmarks(&[p0, p2], 0.01);
+    /// assert_eq!(mapping, [Some(0), None, Some(1)]);
+    /// assert!((graph.get_edge(0, 1).unwrap() - 2.0 * 2.0_f32.sqrt()).abs() < 0.0001);
+    /// ```
+    pub fn prune_to_landmarks(
+        &mut self,
+        keep: &[usize],
+        cost_tolerance: f32,
+    ) -> Vec<Option<usize>> {
+        let mut flags = vec![false; self.nodes.len()];
+        for node in keep.iter() {
+            if let Some(flag) = flags.get_mut(*node) {
+                *flag = true;
+            }
+        }
+        let landmarks: Vec<usize> = (0..self.nodes.len()).filter(|node| flags[*node]).collect();
+        let mut pairs: Vec<(usize, usize, f32)> = Vec::new();
+        for (position, first) in landmarks.iter().enumerate() {
+            let distances = self.dijkstra_from(*first);
+            for (offset, second) in landmarks[position + 1..].iter().enumerate() {
+                if let Some(cost) = distances[*second] {
+                    // After pruning, the landmarks get the handles of their position in the list.
+                    pairs.push((position, position + 1 + offset, cost));
+                }
+            }
+        }
+        pairs.sort_by_key(|(_, _, cost)| OrderedF32(*cost));
+
+        let mapping = self.retain_nodes(&flags);
+        // Shortcuts never undercut the original costs, so adding one can only lower the error of the other pairs.
+        for (first, second, cost) in pairs {
+            let current = self.dijkstra_from(first)[second];
+            if current.is_some_and(|current| current - cost <= cost_tolerance) {
+                continue;
+            }
+            let multiplier =
+                0.5 * (self.nodes[first].cost_multiplier + self.nodes[second].cost_multiplier);
+            let stored_cost = cost / multiplier;
+            if self.get_link_index(first, second).is_some() {
+                self.set_stored_cost(first, second, stored_cost);
+            } else {
+                self.push_link(first, second, stored_cost);
+            }
+        }
+        self.clear_search_state();
+        mapping
+    }
+
+    /// Renumbers the nodes in the Morton order of their positions, which is the order along a Z shaped curve
+    /// through the plane. Nodes that are close in space then mostly get close handles, so the nodes touched by
+    /// an expansion of the search sit close in memory, which improves the cache behavior on large graphs.
+    /// The links keep their order and attributes. The returned mapping contains the new handle for every old handle.
+    /// The state of the last search gets cleared.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([1.0, 1.0]);
+    /// let p1 = graph.add_node([0.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let mapping = graph.reorder_spatially();
+    /// assert_eq!(mapping, [2, 0, 1]);
+    /// assert_eq!(graph.links_in_order().next(), Some((2, 0)));
+    /// ```
+    pub fn reorder_spatially(&mut self) -> Vec<usize> {
+        let Some((lower, upper)) = self.stats().bounding_box else {
+            return Vec::new();
+        };
+        let lower = Vec2::from(lower);
+        let extent = Vec2::from(upper) - lower;
+        let quantize = |value: f32, extent: f32| {
+            if extent > 0.0 {
+                (value / extent * u32::MAX as f32) as u32
+            } else {
+                0
+            }
+        };
+        let mut order: Vec<(u64, usize)> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                let offset = node.position - lower;
+                let code = morton_code(quantize(offset.x, extent.x), quantize(offset.y, extent.y));
+                (code, index)
+            })
+            .collect();
+        order.sort();
+
+        let mut mapping = vec![0; self.nodes.len()];
+        for (new_index, (_, old_index)) in order.iter().enumerate() {
+            mapping[*old_index] = new_index;
+        }
+        let mut old_nodes: Vec<Option<NavNode>> = std::mem::take(&mut self.nodes)
+            .into_iter()
+            .map(Some)
+            .collect();
+        self.nodes = order
+            .iter()
+            .map(|(_, old_index)| old_nodes[*old_index].take().unwrap())
+            .collect();
+        for node in self.nodes.iter_mut() {
+            for (partner, _) in node.connections.iter_mut() {
+                *partner = mapping[*partner];
+            }
+            if self.sorted_connections {
+                node.connections.sort_by_key(|(partner, _)| *partner);
+            }
+        }
+        for (node1, node2) in self.links.iter_mut() {
+            *node1 = mapping[*node1];
+            *node2 = mapping[*node2];
+        }
+        self.edge_attributes = self
+            .edge_attributes
+            .iter()
+            .map(|((node1, node2), attributes)| {
+                (edge_key(mapping[*node1], mapping[*node2]), *attributes)
+            })
+            .collect();
+        self.reservations = self
+            .reservations
+            .iter()
+            .map(|(node, time)| (mapping[*node], *time))
+            .collect();
+        self.clear_search_state();
+        self.layout_id = fresh_id();
+        self.generation = fresh_id();
+        mapping
+    }
+
+    /// Extracts the part of the graph near a position, so that an agent may reason over a local window cheaply.
+    /// The new graph contains all nodes within the radius around the center and the links between them,
+    /// with their costs, cost multipliers and tags. The second return value maps the handles of the new graph to the
+    /// handles of this graph.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([5.0, 0.0]);
+    /// let p2 = graph.add_node([0.5, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p2, p0).unwrap();
+    ///
+    /// let (subgraph, original) = graph.subgraph_within_radius([0.0, 0.0], 1.0);
+    /// assert_eq!(original, [p0, p2]);
+    /// assert_eq!(subgraph.links_in_order().count(), 1);
+    /// ```
+    pub fn subgraph_within_radius(&self, center: [f32; 2], radius: f32) -> (NavGraph, Vec<usize>) {
+        let center = Vec2::from(center);
+        let keep: Vec<bool> = self
+            .nodes
+            .iter()
+            .map(|node| node.position.dist_to(&center) <= radius)
+            .collect();
+
+        let mut subgraph = NavGraph {
+            nodes: self.nodes.clone(),
+            links: self.links.clone(),
+            edge_attributes: self.edge_attributes.clone(),
+            last_search: SearchScratch::new(),
+            check_heuristic: self.check_heuristic,
+            sorted_connections: self.sorted_connections,
+            reservations: self.reservations.clone(),
+            layout_id: self.layout_id,
+            generation: self.generation,
+            landmark_distances: Vec::new(),
+            landmark_generation: 0,
+            kd_tree: None,
+            heap_threshold: self.heap_threshold,
+            heuristic_weight: self.heuristic_weight,
+            improvement_epsilon: self.improvement_epsilon,
+            last_query: None,
+            path_cache: VecDeque::new(),
+            path_cache_generation: 0,
+        };
+        subgraph.retain_nodes(&keep);
+        let original = (0..self.nodes.len()).filter(|index| keep[*index]).collect();
+        (subgraph, original)
+    }
+
+    /// Removes the most recently added node together with its links.
+    pub(crate) fn remove_last_node(&mut self) {
+        let mut keep = vec![true; self.nodes.len()];
+        if let Some(last) = keep.last_mut() {
+            *last = false;
+        }
+        self.retain_nodes(&keep);
+    }
+
+    /// Keeps only the nodes flagged in the keep list together with the links between them, and renumbers
+    /// the handles. Returns the mapping from old to new handles.
+    fn retain_nodes(&mut self, keep: &[bool]) -> Vec<Option<usize>> {
+        let mut mapping: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut next_index = 0;
+        for (index, flag) in keep.iter().enumerate() {
+            if *flag {
+                mapping[index] = Some(next_index);
+                next_index += 1;
+            }
+        }
+
+        let old_nodes = std::mem::take(&mut self.nodes);
+        for (index, mut node) in old_nodes.into_iter().enumerate() {
+            if mapping[index].is_none() {
+                continue;
+            }
+            node.connections = node
+                .connections
+                .iter()
+                .filter_map(|(partner, cost)| mapping[*partner].map(|partner| (partner, *cost)))
+                .collect();
+            self.nodes.push(node);
+        }
+        self.links = self
+            .links
+            .iter()
+            .filter_map(|(node1, node2)| Some((mapping[*node1]?, mapping[*node2]?)))
+            .collect();
+        self.edge_attributes = self
+            .edge_attributes
+            .iter()
+            .filter_map(|((node1, node2), attributes)| {
+                Some((edge_key(mapping[*node1]?, mapping[*node2]?), *attributes))
+            })
+            .collect();
+        self.reservations = self
+            .reservations
+            .iter()
+            .filter_map(|(node, time)| Some((mapping[*node]?, *time)))
+            .collect();
+        self.clear_search_state();
+        self.layout_id = fresh_id();
+        self.generation = fresh_id();
+        mapping
+    }
+
+    /// Builds a k-d tree over the node positions, which accelerates [`NavGraph::find_nearest_node_with_radius`],
+    /// [`NavGraph::find_k_nearest_nodes`] and [`NavGraph::nodes_within_radius`] from O(V) to about O(log V) per query.
+    /// Unlike a uniform grid, the tree adapts to clustered positions. Adding, removing or moving nodes invalidates
+    /// the tree, and the queries fall back to checking all nodes until it is built again. Changes of the links keep it valid.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.build_kdtree();
+    /// assert_eq!(graph.find_nearest_node_with_radius([0.9, 0.0], 0.5), Some(p1));
+    /// ```
+    pub fn build_kdtree(&mut self) {
+        let tree = KdTree::new(self.nodes.iter().map(|node| node.position));
+        self.kd_tree = Some((tree, self.layout_id));
+    }
+
+    /// Gets the k-d tree if it has been built for the current node positions.
+    fn current_kd_tree(&self) -> Option<&KdTree> {
+        self.kd_tree
+            .as_ref()
+            .filter(|(_, layout_id)| *layout_id == self.layout_id)
+            .map(|(tree, _)| tree)
+    }
+
+    /// Finds the nearest node to the indicated position within a certain
+    /// maximum radius. If there is none, it returns none. The returned
+    /// value is the index generated by add_node. Among equally distant nodes, the one added first is taken.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let index = graph.find_nearest_node_with_radius([0.00001, 0.0], 0.01).unwrap();
+    /// ```
+    pub fn find_nearest_node_with_radius(&self, position: [f32; 2], radius: f32) -> Option<usize> {
+        if let Some(tree) = self.current_kd_tree() {
+            return tree.nearest(Vec2::from(position), radius);
+        }
+        let mut min_dist = f32::MAX;
+        let mut best_index = 0usize;
+        let probing = Vec2::from(position);
+
+        for (index, node) in self.nodes.iter().enumerate() {
+            let dist = node.position.dist_to(&probing);
+            if dist < min_dist {
+                min_dist = dist;
+                best_index = index;
+            }
+        }
+
+        if min_dist <= radius {
+            Some(best_index)
+        } else {
+            None
+        }
+    }
+
+    /// Finds the nearest node to the indicated position within a certain maximum radius like
+    /// [`NavGraph::find_nearest_node_with_radius`], but exploits the temporal coherence of consecutive queries.
+    /// Along with the nearest node, the cache stores half the gap between its distance and the one of the second nearest node.
+    /// By the triangle inequality, the nearest node can not change as long as the position moves less than that margin
+    /// from the position of the scan, so then only the distance to the cached node is checked. Otherwise, or if nodes
+    /// have been added, removed or moved since, all nodes get scanned again, or the k-d tree of [`NavGraph::build_kdtree`] is used.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{NavGraph, NearestQueryCache};
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let mut cache = NearestQueryCache::new();
+    /// assert_eq!(graph.find_nearest_node_cached([0.1, 0.0], 0.5, &mut cache), Some(p0));
+    /// assert_eq!(graph.find_nearest_node_cached([0.15, 0.0], 0.5, &mut cache), Some(p0));
+    /// assert_eq!(graph.find_nearest_node_cached([0.9, 0.0], 0.5, &mut cache), Some(p1));
+    /// ```
+    pub fn find_nearest_node_cached(
+        &self,
+        position: [f32; 2],
+        radius: f32,
+        cache: &mut NearestQueryCache,
+    ) -> Option<usize> {
+        let probing = Vec2::from(position);
+        if cache.layout_id != self.layout_id || probing.dist_to(&cache.position) >= cache.margin {
+            let (nearest, second) = if let Some(tree) = self.current_kd_tree() {
+                let found = tree.k_nearest(probing, 2);
+                (found.first().copied(), found.get(1).copied())
+            } else {
+                let mut best: [Option<(f32, usize)>; 2] = [None, None];
+                for (index, node) in self.nodes.iter().enumerate() {
+                    let dist = node.position.dist_to(&probing);
+                    if dist.is_nan() {
+                        continue;
+                    }
+                    if best[0].is_none_or(|first| (dist, index) < first) {
+                        best = [Some((dist, index)), best[0]];
+                    } else if best[1].is_none_or(|second| (dist, index) < second) {
+                        best[1] = Some((dist, index));
+                    }
+                }
+                cache.checked += self.nodes.len();
+                (
+                    best[0].map(|(_, index)| index),
+                    best[1].map(|(_, index)| index),
+                )
+            };
+            let dist = |node: Option<usize>| {
+                node.map_or(f32::INFINITY, |node| {
+                    self.nodes[node].position.dist_to(&probing)
+                })
+            };
+            *cache = NearestQueryCache {
+                position: probing,
+                nearest,
+                margin: (dist(second) - dist(nearest)) * 0.5,
+                layout_id: self.layout_id,
+                checked: cache.checked,
+            };
+        }
+
+        let nearest = cache.nearest?;
+        cache.checked += 1;
+        (self.nodes[nearest].position.dist_to(&probing) <= radius).then_some(nearest)
+    }
+
+    /// Finds the indicated number of nodes nearest to the position, sorted by ascending distance, where equally
+    /// distant nodes are sorted by their handles. Fewer nodes are returned if the graph has fewer nodes.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([3.0, 0.0]);
+    /// assert_eq!(graph.find_k_nearest_nodes([1.5, 0.0], 2), [p1, p0]);
+    /// ```
+    pub fn find_k_nearest_nodes(&self, position: [f32; 2], count: usize) -> Vec<usize> {
+        let probing = Vec2::from(position);
+        if let Some(tree) = self.current_kd_tree() {
+            return tree.k_nearest(probing, count);
+        }
+        let mut candidates: Vec<(OrderedF32, usize)> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| (OrderedF32(node.position.dist_to(&probing)), index))
+            .filter(|(dist, _)| !dist.0.is_nan())
+            .collect();
+        candidates.sort_unstable();
+        candidates.truncate(count);
+        candidates.into_iter().map(|(_, index)| index).collect()
+    }
+
+    /// Finds all nodes within the indicated radius of the position, sorted by their handles.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([3.0, 0.0]);
+    /// assert_eq!(graph.nodes_within_radius([0.5, 0.0], 1.0), [p0, p1]);
+    /// ```
+    pub fn nodes_within_radius(&self, position: [f32; 2], radius: f32) -> Vec<usize> {
+        let probing = Vec2::from(position);
+        if let Some(tree) = self.current_kd_tree() {
+            return tree.within(probing, radius);
+        }
+        (0..self.nodes.len())
+            .filter(|index| self.nodes[*index].position.dist_to(&probing) <= radius)
+            .collect()
+    }
+
+    /// Finds the link nearest to the indicated position within a certain
+    /// maximum radius, measured as distance to the line segment. If there is none, it returns none.
+    /// The returned values are the two node handles of the link in the order they were connected.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert_eq!(graph.find_nearest_link_with_radius([0.5, 0.005], 0.01), Some((p0, p1)));
+    /// assert_eq!(graph.find_nearest_link_with_radius([0.5, 0.1], 0.01), None);
+    /// ```
+    pub fn find_nearest_link_with_radius(
+        &self,
+        position: [f32; 2],
+        radius: f32,
+    ) -> Option<(usize, usize)> {
+        let probing = Vec2::from(position);
+        let mut best: Option<(f32, (usize, usize))> = None;
+
+        for (node1, node2) in self.links.iter() {
+            let line = Line::new(self.nodes[*node1].position, self.nodes[*node2].position);
+            let dist = line.distance_to_point(probing);
+            if dist <= radius && best.is_none_or(|(best_dist, _)| dist < best_dist) {
+                best = Some((dist, (*node1, *node2)));
+            }
+        }
+
+        best.map(|(_, link)| link)
+    }
+
+    /// Gets all links whose segments intersect the query segment between the two positions, for example
+    /// the links cut by a wall the player draws, which may then be disconnected. Links that merely touch the segment
+    /// with an end point are not reported. The links are the pairs of node handles in the order they were connected.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([2.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 2.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// assert_eq!(graph.edges_crossing([1.0, -1.0], [1.0, 1.0]), [(p0, p1)]);
+    /// ```
+    pub fn edges_crossing(&self, start: [f32; 2], end: [f32; 2]) -> Vec<(usize, usize)> {
+        let query = Line::new(Vec2::from(start), Vec2::from(end));
+        self.links
+            .iter()
+            .filter(|(node1, node2)| {
+                Line::new(self.nodes[*node1].position, self.nodes[*node2].position)
+                    .intersects_with(&query)
+            })
+            .copied()
+            .collect()
+    }
+
+    /// Builds a wall between the two positions by disabling every enabled link it crosses, as found by
+    /// [`NavGraph::edges_crossing`], and returns the number of links that got disabled. The searches then route
+    /// around the wall. The wall can be torn down again by enabling the links crossing it with [`NavGraph::set_edge_enabled`].
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// assert_eq!(graph.apply_wall([1.0, -1.0], [1.0, 1.0]), 1);
+    /// assert_eq!(graph.edge_enabled(p0, p1), Some(false));
+    /// assert_eq!(graph.search_graph(p0, p1), None);
+    /// ```
+    pub fn apply_wall(&mut self, start: [f32; 2], end: [f32; 2]) -> usize {
+        let mut cut = 0;
+        for (node1, node2) in self.edges_crossing(start, end) {
+            if self.edge_enabled_unchecked(node1, node2) {
+                self.set_edge_enabled(node1, node2, false).unwrap();
+                cut += 1;
+            }
+        }
+        cut
+    }
+
+    /// Gets the adjacency of the graph in the compressed sparse row format, which is the common interchange
+    /// format for external graph kernels. The result are the row offsets, the column indices and the edge costs.
+    /// The neighbors of node n are the column indices from position offsets\[n\] up to offsets\[n + 1\],
+    /// and every undirected link appears in the rows of both its nodes.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let (offsets, columns, costs) = graph.to_csr();
+    /// assert_eq!(offsets, [0, 1, 2, 2]);
+    /// assert_eq!(columns, [p1, p0]);
+    /// assert_eq!(costs, [1.0, 1.0]);
+    /// ```
+    pub fn to_csr(&self) -> (Vec<usize>, Vec<usize>, Vec<f32>) {
+        let mut offsets = Vec::with_capacity(self.nodes.len() + 1);
+        let mut columns = Vec::with_capacity(2 * self.links.len());
+        let mut costs = Vec::with_capacity(2 * self.links.len());
+        offsets.push(0);
+        for node in self.nodes.iter() {
+            for (partner, cost) in node.connections.iter() {
+                columns.push(*partner);
+                costs.push(*cost);
+            }
+            offsets.push(columns.len());
+        }
+        (offsets, columns, costs)
+    }
+
+    /// Converts the nav graph into an immutable form that is optimized for queries, for levels that do not
+    /// change after construction. The frozen graph stores the adjacency in the compressed sparse row format
+    /// of [`NavGraph::to_csr`] with the node cost multipliers already applied, and it gets a spatial grid
+    /// for finding the nearest nodes. Its searches return the same paths as [`NavGraph::search_graph`],
+    /// including the heuristic weight, but only use the plain distances, the tags, clearances and heights are not kept. Disabled edges are left out.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{NavGraph, SearchScratch};
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let frozen = graph.freeze();
+    /// let mut scratch = SearchScratch::new();
+    /// assert_eq!(frozen.search_graph_with_scratch(p0, p1, &mut scratch).unwrap(), [p0, p1]);
+    /// assert_eq!(frozen.find_nearest_node_with_radius([0.9, 0.1], 0.5), Some(p1));
+    /// ```
+    pub fn freeze(self) -> FrozenNavGraph {
+        let mut offsets = vec![0];
+        let mut neighbors = Vec::with_capacity(2 * self.links.len());
+        let mut costs = Vec::with_capacity(2 * self.links.len());
+        for (index, node) in self.nodes.iter().enumerate() {
+            for (partner, distance) in node.connections.iter() {
+                if self.edge_enabled_unchecked(index, *partner) {
+                    neighbors.push(*partner);
+                    costs.push(self.traversal_cost(index, *partner, *distance));
+                }
+            }
+            offsets.push(neighbors.len());
+        }
+        let positions: Vec<Vec2> = self.nodes.iter().map(|node| node.position).collect();
+
+        // The cells of the grid hold about one node on average. The square roots are taken separately, so that
+        // the area of a huge extent does not overflow. The cells are kept in a hash map, as keys of floored
+        // coordinates also work for negative positions, and empty cells of sparse extents take no memory.
+        let cell_size = match self.stats().bounding_box {
+            Some((lower, upper)) => {
+                let width = (upper[0] - lower[0]).max(f32::EPSILON);
+                let height = (upper[1] - lower[1]).max(f32::EPSILON);
+                (width.sqrt() * height.sqrt() / (positions.len() as f32).sqrt())
+                    .clamp(f32::EPSILON, f32::MAX)
+            }
+            None => 1.0,
+        };
+        let mut cells: HashMap<(i64, i64), Vec<usize>> = HashMap::new();
+        for (index, position) in positions.iter().enumerate() {
+            cells
+                .entry(position.quantized_key(cell_size))
+                .or_default()
+                .push(index);
+        }
+
+        FrozenNavGraph {
+            positions,
+            offsets,
+            neighbors,
+            costs,
+            cell_size,
+            cells,
+            layout_id: self.layout_id,
+            heuristic_weight: self.heuristic_weight,
+            improvement_epsilon: self.improvement_epsilon,
+        }
+    }
+
+    /// Converts the nav graph into an undirected petgraph graph, so that the algorithms of the
+    /// petgraph ecosystem may be run on it. The node weights are the positions and the edge weights
+    /// are the costs. Node indices correspond to the handles, and edges are added in link order.
+    ///
+    /// Only available with the feature *petgraph*.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// let converted = graph.to_petgraph();
+    /// assert_eq!(converted.edge_count(), 1);
+    /// ```
+    #[cfg(feature = "petgraph")]
+    pub fn to_petgraph(&self) -> petgraph::graph::UnGraph<[f32; 2], f32> {
+        let mut result =
+            petgraph::graph::UnGraph::with_capacity(self.nodes.len(), self.links.len());
+        for node in self.nodes.iter() {
+            result.add_node(node.position.into());
+        }
+        for (node1, node2) in self.links.iter() {
+            let cost = self.get_edge(*node1, *node2).unwrap();
+            result.add_edge(
+                petgraph::graph::NodeIndex::new(*node1),
+                petgraph::graph::NodeIndex::new(*node2),
+                cost,
+            );
+        }
+        result
+    }
+
+    /// Generates a nav graph from an undirected petgraph graph. The node weights are taken as positions
+    /// and the edge weights as edge costs. The handles of the nodes are the petgraph node indices.
+    ///
+    /// Only available with the feature *petgraph*.
+    ///
+    /// Parallel edges get collapsed into one link with the minimum cost, as with [`NavGraph::dedup_edges`].
+    ///
+    /// # Error
+    /// Returns an error if the petgraph graph contains self loops.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut converted = petgraph::graph::UnGraph::new_undirected();
+    /// let p0 = converted.add_node([0.0, 0.0]);
+    /// let p1 = converted.add_node([1.0, 0.0]);
+    /// converted.add_edge(p0, p1, 2.0);
+    /// let graph = NavGraph::from_petgraph(&converted).unwrap();
+    /// ```
+    #[cfg(feature = "petgraph")]
+    pub fn from_petgraph(
+        graph: &petgraph::graph::UnGraph<[f32; 2], f32>,
+    ) -> Result<NavGraph, ConnectionError> {
+        use petgraph::visit::EdgeRef;
+
+        let mut result = NavGraph::new();
+        for position in graph.node_weights() {
+            result.add_node(*position);
+        }
+        for edge in graph.edge_references() {
+            let node1 = edge.source().index();
+            let node2 = edge.target().index();
+            if node1 == node2 {
+                return Err(ConnectionError::NodeDoubled);
+            }
+            result.push_link(node1, node2, *edge.weight());
+        }
+        result.dedup_edges();
+        Ok(result)
+    }
+
+    /// Generates a grid graph from a walkability bitmap, as used in tile games. The bitmap has the indicated
+    /// width and height and contains the cells row by row. Cells missing in the bitmap count as walls.
+    /// Every walkable cell becomes a node at the position of its column and row times the spacing, and the
+    /// handles are given in the sequence of the walkable cells. Walkable neighbors get connected
+    /// horizontally and vertically, and diagonally as indicated by the diagonal moves. The costs of the
+    /// links are their lengths, so √2 times the spacing for the diagonal ones, which
+    /// [`NavGraph::search_grid_octile`] relies on.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{DiagonalMoves, NavGraph};
+    /// let walkable = [true, true, false, true];
+    /// let mut graph = NavGraph::from_walkability(2, 2, &walkable, 1.0, DiagonalMoves::NoCornerCutting);
+    /// // The diagonal from the first to the last cell would cut the corner of the wall.
+    /// assert_eq!(graph.search_graph(0, 2).unwrap(), [0, 1, 2]);
+    /// ```
+    pub fn from_walkability(
+        width: usize,
+        height: usize,
+        walkable: &[bool],
+        spacing: f32,
+        diagonal: DiagonalMoves,
+    ) -> NavGraph {
+        let free = |x: usize, y: usize| {
+            (x < width) && (y < height) && walkable.get(y * width + x) == Some(&true)
+        };
+        let mut result = NavGraph::new();
+        let mut handles: Vec<Option<usize>> = vec![None; width * height];
+        for y in 0..height {
+            for x in 0..width {
+                if free(x, y) {
+                    handles[y * width + x] =
+                        Some(result.add_node([x as f32 * spacing, y as f32 * spacing]));
+                }
+            }
+        }
+
+        let handle = |x: usize, y: usize| handles[y * width + x].unwrap();
+        for y in 0..height {
+            for x in 0..width {
+                if !free(x, y) {
+                    continue;
+                }
+                if free(x + 1, y) {
+                    result
+                        .connect_nodes(handle(x, y), handle(x + 1, y))
+                        .unwrap();
+                }
+                if free(x, y + 1) {
+                    result
+                        .connect_nodes(handle(x, y), handle(x, y + 1))
+                        .unwrap();
+                }
+                // The orthogonal cells next to a diagonal are the one below and the one beside.
+                let diagonal_allowed = |beside_free: bool| match diagonal {
+                    DiagonalMoves::Forbidden => false,
+                    DiagonalMoves::NoCornerCutting => beside_free && free(x, y + 1),
+                    DiagonalMoves::AllowCornerCutting => true,
+                };
+                if free(x + 1, y + 1) && diagonal_allowed(free(x + 1, y)) {
+                    result
+                        .connect_nodes(handle(x, y), handle(x + 1, y + 1))
+                        .unwrap();
+                }
+                if (x > 0) && free(x - 1, y + 1) && diagonal_allowed(free(x - 1, y)) {
+                    result
+                        .connect_nodes(handle(x, y), handle(x - 1, y + 1))
+                        .unwrap();
+                }
+            }
+        }
+        result
+    }
+
+    /// Generates a graph from polylines, like the center lines of the roads of a road network. Every point of
+    /// a polyline becomes a node that is connected to the nodes of its neighboring points. End points of polylines
+    /// that are not farther apart than the join distance become one shared node at the first of these positions,
+    /// so that roads meeting at an intersection get connected. Inner points are never merged, but a point repeating
+    /// its predecessor is skipped, and a segment contained twice only gets connected once.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let roads = vec![vec![[0.0, 0.0], [1.0, 0.0]], vec![[1.0, 0.001], [1.0, 1.0]]];
+    /// let mut graph = NavGraph::from_polylines(&roads, 0.01);
+    /// assert_eq!(graph.search_graph(0, 2).unwrap(), [0, 1, 2]);
+    /// ```
+    pub fn from_polylines(lines: &[Vec<[f32; 2]>], join_eps: f32) -> NavGraph {
+        let mut result = NavGraph::new();
+        let mut end_nodes: Vec<usize> = Vec::new();
+        for line in lines.iter() {
+            let mut previous: Option<usize> = None;
+            for (index, position) in line.iter().enumerate() {
+                let point = Vec2::from(*position);
+                let is_end = index == 0 || index == line.len() - 1;
+                if let Some(previous) = previous
+                    && result.nodes[previous].position == point
+                {
+                    if is_end && !end_nodes.contains(&previous) {
+                        end_nodes.push(previous);
+                    }
+                    continue;
+                }
+                let node = if is_end {
+                    let existing = end_nodes
+                        .iter()
+                        .map(|node| (*node, result.nodes[*node].position.dist_to(&point)))
+                        .filter(|(_, distance)| *distance <= join_eps)
+                        .min_by_key(|(_, distance)| OrderedF32(*distance));
+                    match existing {
+                        Some((node, _)) => node,
+                        None => {
+                            let node = result.add_node(*position);
+                            end_nodes.push(node);
+                            node
+                        }
+                    }
+                } else {
+                    result.add_node(*position)
+                };
+                if let Some(previous) = previous
+                    && result.check_new_link(previous, node).is_ok()
+                {
+                    result.connect_nodes(previous, node).unwrap();
+                }
+                previous = Some(node);
+            }
+        }
+        result
+    }
+
+    /// Adds a position to the nav graph and returns a handle index that may be used for
+    /// connecting the nodes. The returning handles are given in registration sequence and
+    /// starting from 0.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    ///
+    /// assert!((p0 == 0) && (p1 == 1), "Illegal sequence of nodes.")
+    /// ```
+    pub fn add_node(&mut self, position: [f32; 2]) -> usize {
+        let ret_val = self.nodes.len();
+        self.nodes.push(NavNode::new(Vec2::from(position)));
+        self.layout_id = fresh_id();
+        self.generation = fresh_id();
+        ret_val
+    }
+
+    /// Adds a position to the nav graph like [`NavGraph::add_node`], unless a node already exists within
+    /// the indicated distance. In that case the handle of the nearest existing node is returned and the graph
+    /// remains unchanged. This prevents duplicated nodes and zero length edges when importing imperfect data.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node_dedup([0.0, 0.0], 0.001);
+    /// let p1 = graph.add_node_dedup([0.0001, 0.0], 0.001);
+    ///
+    /// assert_eq!(p0, p1);
+    /// ```
+    pub fn add_node_dedup(&mut self, position: [f32; 2], eps: f32) -> usize {
+        match self.find_nearest_node_with_radius(position, eps) {
+            Some(existing) => existing,
+            None => self.add_node(position),
+        }
+    }
+
+    /// Gets the node of the indicated handle, or an error if the handle is invalid. The public methods
+    /// that take handles from the outside check them with this, while the searches index the nodes directly.
+    fn node(&self, handle: usize) -> Result<&NavNode, ConnectionError> {
+        self.nodes
+            .get(handle)
+            .ok_or(ConnectionError::NodeDoesntExist(handle))
+    }
+
+    /// Gets the node of the indicated handle mutably, or an error if the handle is invalid.
+    fn node_mut(&mut self, handle: usize) -> Result<&mut NavNode, ConnectionError> {
+        self.nodes
+            .get_mut(handle)
+            .ok_or(ConnectionError::NodeDoesntExist(handle))
+    }
+
+    /// Gets the index of the link of the indicated node pairing. Returns None if it does not exist.
+    fn get_link_index(&self, node1: usize, node2: usize) -> Option<usize> {
+        if let Some(result) = self
+            .links
+            .iter()
+            .position(|element| *element == (node1, node2))
+        {
+            return Some(result);
+        } else if let Some(result) = self
+            .links
+            .iter()
+            .position(|element| *element == (node2, node1))
+        {
+            return Some(result);
+        }
+        None
+    }
+
+    /// Connects two graph nodes with indicated indices.
+    ///
+    /// # Error
+    /// For the case that nodes do not exist, that indices handed over are the same, or that such a link has already been
+    /// established that an error is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// ```
+    pub fn connect_nodes(&mut self, node1: usize, node2: usize) -> Result<(), ConnectionError> {
+        self.check_new_link(node1, node2)?;
+        let dist = self.nodes[node1]
+            .position
+            .dist_to(&self.nodes[node2].position);
+        self.push_link(node1, node2, dist);
+
+        Ok(())
+    }
+
+    /// Replaces all links of the graph at once, for example after the terrain has been deformed.
+    /// The costs are the distances between the nodes, as with [`NavGraph::connect_nodes`]. Links that existed
+    /// before keep their tags and clearances. All links get validated first, so that the graph is left
+    /// unchanged in case of an error.
+    ///
+    /// # Error
+    /// Returns an error if a node does not exist, if a link connects a node with itself, or if a link is
+    /// contained twice.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{ConnectionError, NavGraph};
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// graph.set_links(&[(p1, p2), (p2, p0)]).unwrap();
+    /// assert_eq!(graph.links_in_order().collect::<Vec<_>>(), [(p1, p2), (p2, p0)]);
+    /// assert_eq!(graph.set_links(&[(p0, 5)]), Err(ConnectionError::NodeDoesntExist(5)));
+    /// ```
+    pub fn set_links(&mut self, links: &[(usize, usize)]) -> Result<(), ConnectionError> {
+        let mut keys = HashSet::with_capacity(links.len());
+        for (node1, node2) in links.iter() {
+            self.node(*node1)?;
+            self.node(*node2)?;
+            if node1 == node2 {
+                return Err(ConnectionError::NodeDoubled);
+            }
+            if !keys.insert(edge_key(*node1, *node2)) {
+                return Err(ConnectionError::LinkAlreadyExists);
+            }
+        }
+
+        for node in self.nodes.iter_mut() {
+            node.connections.clear();
+        }
+        self.links.clear();
+        self.edge_attributes.retain(|key, _| keys.contains(key));
+        for (node1, node2) in links.iter() {
+            let dist = self.nodes[*node1]
+                .position
+                .dist_to(&self.nodes[*node2].position);
+            self.push_link(*node1, *node2, dist);
+        }
+        self.clear_search_state();
+        self.generation = fresh_id();
+        Ok(())
+    }
+
+    /// Gets the pairs of nodes that are connected by more than one link, each pair once in the order of
+    /// its first link. The methods establishing links reject duplicates, so this is a consistency check
+    /// after assembling a graph from imported data.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert!(graph.find_duplicate_edges().is_empty());
+    /// ```
+    pub fn find_duplicate_edges(&self) -> Vec<(usize, usize)> {
+        let mut counts: HashMap<(usize, usize), usize> = HashMap::with_capacity(self.links.len());
+        for (node1, node2) in self.links.iter() {
+            *counts.entry(edge_key(*node1, *node2)).or_insert(0) += 1;
+        }
+        self.links
+            .iter()
+            .filter(|(node1, node2)| counts.remove(&edge_key(*node1, *node2)).unwrap_or(0) > 1)
+            .copied()
+            .collect()
+    }
+
+    /// Collapses all links between the same pair of nodes into one, which keeps the place of the first link
+    /// and the minimum cost of them. This is the cleanup pass for imported data, the graph is left unchanged
+    /// if it contains no duplicates.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.dedup_edges();
+    /// assert_eq!(graph.get_edge(p0, p1), Some(1.0));
+    /// ```
+    pub fn dedup_edges(&mut self) {
+        if self.find_duplicate_edges().is_empty() {
+            return;
+        }
+        for node in self.nodes.iter_mut() {
+            let mut cheapest: HashMap<usize, f32> = HashMap::with_capacity(node.connections.len());
+            for (partner, cost) in node.connections.iter() {
+                let minimum = cheapest.entry(*partner).or_insert(*cost);
+                *minimum = minimum.min(*cost);
+            }
+            // Only the first connection to every partner survives and takes the minimum cost.
+            node.connections
+                .retain_mut(|(partner, cost)| match cheapest.remove(partner) {
+                    Some(minimum) => {
+                        *cost = minimum;
+                        true
+                    }
+                    None => false,
+                });
+        }
+        let mut keys = HashSet::with_capacity(self.links.len());
+        self.links
+            .retain(|(node1, node2)| keys.insert(edge_key(*node1, *node2)));
+        self.clear_search_state();
+        self.generation = fresh_id();
+    }
+
+    /// Checks if a link between the two nodes may be established.
+    fn check_new_link(&self, node1: usize, node2: usize) -> Result<(), ConnectionError> {
+        if node1 == node2 {
+            return Err(ConnectionError::NodeDoubled);
+        }
+        self.node(node1)?;
+        self.node(node2)?;
+        if self.get_link_index(node1, node2).is_some() {
+            return Err(ConnectionError::LinkAlreadyExists);
+        }
+        Ok(())
+    }
+
+    /// Establishes a link between the two nodes with the indicated cost without any checks.
+    fn push_link(&mut self, node1: usize, node2: usize, cost: f32) {
+        if self.sorted_connections {
+            for (node, partner) in [(node1, node2), (node2, node1)] {
+                let connections = &mut self.nodes[node].connections;
+                let position = connections.partition_point(|(element, _)| *element < partner);
+                connections.insert(position, (partner, cost));
+            }
+        } else {
+            self.nodes[node1].connections.push((node2, cost));
+            self.nodes[node2].connections.push((node1, cost));
+        }
+        self.links.push((node1, node2));
+        self.generation = fresh_id();
+    }
+
+    /// Replaces the stored cost of the existing link between the two nodes in both directions.
+    pub(crate) fn set_stored_cost(&mut self, node1: usize, node2: usize, cost: f32) {
+        for (node, partner) in [(node1, node2), (node2, node1)] {
+            for connection in self.nodes[node].connections.iter_mut() {
+                if connection.0 == partner {
+                    connection.1 = cost;
+                }
+            }
+        }
+        self.generation = fresh_id();
+    }
+
+    /// Removes the connection to the partner from the connections of the node, keeping them sorted if requested.
+    fn remove_connection(&mut self, node: usize, partner: usize) {
+        let connections = &mut self.nodes[node].connections;
+        let index = connections
+            .iter()
+            .position(|(element, _)| *element == partner)
+            .unwrap();
+        if self.sorted_connections {
+            connections.remove(index);
+        } else {
+            connections.swap_remove(index);
+        }
+    }
+
+    /// Enables or disables keeping the connections of every node sorted by the handle of the neighbor.
+    /// The search resolves ties between nodes with the same estimated cost by the order in which they entered
+    /// the open list, which follows the order of the connections of the expanded nodes. Without sorting this
+    /// order depends on the sequence of [`NavGraph::connect_nodes`] calls, so equivalent graphs built in
+    /// a different order may return different paths of the same cost. With sorting enabled, the returned path
+    /// only depends on the positions and the links, which makes tests and replays reproducible.
+    /// Enabling it sorts the existing connections right away.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// graph.set_sorted_connections(true);
+    /// ```
+    pub fn set_sorted_connections(&mut self, enabled: bool) {
+        self.sorted_connections = enabled;
+        self.generation = fresh_id();
+        if enabled {
+            for node in self.nodes.iter_mut() {
+                node.connections.sort_by_key(|(partner, _)| *partner);
+            }
+        }
+    }
+
+    /// Removes an already existing connection between two nodes.
+    /// In the case of a game, this would be a closing door.
+    ///
+    /// # Error
+    /// Returns an error if the link does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.disconnect_nodes(p1, p0).unwrap();
+    pub fn disconnect_nodes(&mut self, node1: usize, node2: usize) -> Result<(), ConnectionError> {
+        if let Some(link) = self.get_link_index(node1, node2) {
+            self.links.remove(link);
+            self.remove_connection(node1, node2);
+            self.remove_connection(node2, node1);
+            self.edge_attributes.remove(&edge_key(node1, node2));
+            self.generation = fresh_id();
+            return Ok(());
+        }
+        Err(ConnectionError::LinkDoesntExist)
+    }
+
+    /// Removes crossing edges, which look wrong for a planar nav mesh and may be contained in imported
+    /// or hand built graphs. Of every pair of edges whose segments intersect the longer one gets removed,
+    /// until no crossings remain. Edges that only share an end point do not cross. All pairs of edges get tested
+    /// once, so this takes O(E²) for E edges. Returns the removed links.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([2.0, 2.0]);
+    /// let p2 = graph.add_node([0.0, 1.0]);
+    /// let p3 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p2, p3).unwrap();
+    /// assert_eq!(graph.remove_crossing_edges(), [(p0, p1)]);
+    /// ```
+    pub fn remove_crossing_edges(&mut self) -> Vec<(usize, usize)> {
+        let lines: Vec<Line> = self
+            .links
+            .iter()
+            .map(|(node1, node2)| {
+                Line::new(self.nodes[*node1].position, self.nodes[*node2].position)
+            })
+            .collect();
+        let length = |link: usize| {
+            let (node1, node2) = self.links[link];
+            self.nodes[node1]
+                .position
+                .dist_to(&self.nodes[node2].position)
+        };
+
+        // The crossings are found once, every link keeps the ascending list of the links it crosses
+        // and the number of those that have not been removed yet.
+        let mut crossings: Vec<Vec<usize>> = vec![Vec::new(); lines.len()];
+        for first in 0..lines.len() {
+            for second in (first + 1)..lines.len() {
+                if lines[first].intersects_with(&lines[second]) {
+                    crossings[first].push(second);
+                    crossings[second].push(first);
+                }
+            }
+        }
+        let mut counts: Vec<usize> = crossings.iter().map(Vec::len).collect();
+        let mut kept = vec![true; lines.len()];
+
+        // Removing links never creates crossings, so the earlier links of the scan have no crossings left,
+        // and the pairs get resolved in the order of the links.
+        let mut removed = Vec::new();
+        for first in 0..lines.len() {
+            while kept[first] && counts[first] > 0 {
+                let second = *crossings[first]
+                    .iter()
+                    .find(|partner| kept[**partner])
+                    .unwrap();
+                let longer = if length(first) >= length(second) {
+                    first
+                } else {
+                    second
+                };
+                kept[longer] = false;
+                for partner in crossings[longer].iter() {
+                    counts[*partner] -= 1;
+                }
+                removed.push(self.links[longer]);
+            }
+        }
+
+        for (node1, node2) in removed.iter() {
+            self.disconnect_nodes(*node1, *node2).unwrap();
+        }
+        removed
+    }
+
+    /// Splits an existing edge by inserting a new node at the indicated position. The edge between
+    /// the two nodes gets removed and the new node gets connected to both of them. The edge costs
+    /// are the distances of the sub-segments, and both of them inherit the tags and the clearance of the edge.
+    /// In the case of a game, this would be an agent entering a road somewhere in the middle.
+    ///
+    /// # Error
+    /// Returns an error if the link does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// let p2 = graph.split_edge(p0, p1, [0.5, 0.0]).unwrap();
+    /// assert_eq!(graph.search_graph(p0, p1).unwrap(), [p0, p2, p1]);
+    /// ```
+    pub fn split_edge(
+        &mut self,
+        node1: usize,
+        node2: usize,
+        position: [f32; 2],
+    ) -> Result<usize, ConnectionError> {
+        let attributes = self.edge_attributes.get(&edge_key(node1, node2)).copied();
+        self.disconnect_nodes(node1, node2)?;
+        let new_node = self.add_node(position);
+        self.connect_nodes(node1, new_node)?;
+        self.connect_nodes(new_node, node2)?;
+        if let Some(attributes) = attributes {
+            self.edge_attributes
+                .insert(edge_key(node1, new_node), attributes);
+            self.edge_attributes
+                .insert(edge_key(new_node, node2), attributes);
+        }
+        Ok(new_node)
+    }
+
+    /// Merges nearly collinear adjacent edges, which is the inverse of [`NavGraph::split_edge`]. A node with
+    /// exactly two edges, whose directions deviate by at most the angle tolerance in radians from a straight line,
+    /// gets bypassed by a direct edge between its two neighbors. The merged edge gets the tags both edges have
+    /// in common and the lower clearance. Nodes whose neighbors are already connected are kept.
+    /// The bypassed nodes stay as isolated nodes, so that all handles remain valid, and are returned.
+    /// They may be removed with [`NavGraph::prune_isolated`].
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// assert_eq!(graph.merge_collinear(0.01), [p1]);
+    /// assert_eq!(graph.search_graph(p0, p2).unwrap(), [p0, p2]);
+    /// ```
+    pub fn merge_collinear(&mut self, angle_tolerance: f32) -> Vec<usize> {
+        let mut merged = Vec::new();
+        for node in 0..self.nodes.len() {
+            let [(first, _), (second, _)] = self.nodes[node].connections[..] else {
+                continue;
+            };
+            if self.get_link_index(first, second).is_some() {
+                continue;
+            }
+            let position = self.nodes[node].position;
+            let (first_length, to_first) =
+                (self.nodes[first].position - position).get_mag_normalized();
+            let (second_length, to_second) =
+                (self.nodes[second].position - position).get_mag_normalized();
+            if first_length == 0.0 || second_length == 0.0 {
+                continue;
+            }
+            let deviation = std::f32::consts::PI - to_first.dot(to_second).clamp(-1.0, 1.0).acos();
+            if deviation > angle_tolerance {
+                continue;
+            }
+
+            let first_attributes = self.edge_attributes.get(&edge_key(first, node)).copied();
+            let second_attributes = self.edge_attributes.get(&edge_key(node, second)).copied();
+            self.disconnect_nodes(first, node).unwrap();
+            self.disconnect_nodes(node, second).unwrap();
+            self.connect_nodes(first, second).unwrap();
+            if first_attributes.is_some() || second_attributes.is_some() {
+                let first_attributes = first_attributes.unwrap_or_default();
+                let second_attributes = second_attributes.unwrap_or_default();
+                self.edge_attributes.insert(
+                    edge_key(first, second),
+                    EdgeAttributes {
+                        tags: first_attributes.tags & second_attributes.tags,
+                        clearance: first_attributes.clearance.min(second_attributes.clearance),
+                        enabled: first_attributes.enabled && second_attributes.enabled,
+                    },
+                );
+            }
+            merged.push(node);
+        }
+        merged
+    }
+
+    /// Gets the stored cost of the direct connection between two nodes, which is the read complement to
+    /// [`NavGraph::connect_nodes`]. The cost does not contain the cost multipliers of the nodes.
+    /// Returns None if one of the nodes does not exist or if they are not connected.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert_eq!(graph.get_edge(p1, p0), Some(1.0));
+    /// assert_eq!(graph.get_edge(p0, p2), None);
+    /// ```
+    pub fn get_edge(&self, node1: usize, node2: usize) -> Option<f32> {
+        self.nodes
+            .get(node1)?
+            .connections
+            .iter()
+            .find(|(partner, _)| *partner == node2)
+            .map(|(_, cost)| *cost)
+    }
+
+    /// Gets the midpoint and the normalized direction of the connection from the first to the second node,
+    /// which is where to draw a cost label or a direction arrow of the edge. The direction is zero if both
+    /// nodes are at the same position. Returns None if one of the nodes does not exist or if they are not connected.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([0.0, 2.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert_eq!(graph.edge_geometry(p1, p0), Some(([0.0, 1.0], [0.0, -1.0])));
+    /// ```
+    pub fn edge_geometry(&self, node1: usize, node2: usize) -> Option<([f32; 2], [f32; 2])> {
+        self.get_edge(node1, node2)?;
+        let start = self.nodes[node1].position;
+        let end = self.nodes[node2].position;
+        let (length, normalized) = (end - start).get_mag_normalized();
+        let direction = if length > 0.0 {
+            normalized
+        } else {
+            Vec2::new(0.0, 0.0)
+        };
+        Some((start.lerp(&end, 0.5).into(), direction.into()))
+    }
+
+    /// Gets two sample points on either side of the connection from the first to the second node, for example to
+    /// determine the regions of a planar nav mesh that the edge separates. The points are offset from the midpoint
+    /// along the normal of the edge by a tenth of its length. The first point is on the left side when looking from
+    /// the first to the second node, the second one is on the right side. Both points are the midpoint if the nodes
+    /// are at the same position. Returns None if one of the nodes does not exist or if they are not connected.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([10.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert_eq!(graph.edge_sides(p0, p1), Some(([5.0, 1.0], [5.0, -1.0])));
+    /// ```
+    pub fn edge_sides(&self, node1: usize, node2: usize) -> Option<([f32; 2], [f32; 2])> {
+        let (midpoint, direction) = self.edge_geometry(node1, node2)?;
+        let offset = 0.1
+            * self.nodes[node1]
+                .position
+                .dist_to(&self.nodes[node2].position);
+        let midpoint = Vec2::from(midpoint);
+        let normal = Vec2::from(direction).get_orthogonal();
+        let shift = Vec2::new(normal.x * offset, normal.y * offset);
+        Some(((midpoint - shift).into(), (midpoint + shift).into()))
+    }
+
+    /// Checks if the indicated path of node handles is walkable. This is the case if all indices
+    /// exist and every consecutive pair of nodes is directly connected. This may be used to
+    /// validate cached or serialized paths after the graph has changed. An empty path is valid.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// assert!(graph.is_valid_path(&[p0, p1, p2]));
+    /// assert!(!graph.is_valid_path(&[p0, p2]));
+    /// ```
+    pub fn is_valid_path(&self, path: &[usize]) -> bool {
+        path.iter().all(|node| *node < self.nodes.len())
+            && path
+                .windows(2)
+                .all(|pair| self.get_edge(pair[0], pair[1]).is_some())
+    }
+
+    /// Computes the cost of the indicated path of node handles by summing up the stored edge costs.
+    /// This may be used to compare the path found by the search with a hand-authored route.
+    /// Returns None if the path is not valid, as checked by [`NavGraph::is_valid_path`].
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// assert_eq!(graph.path_cost(&[p0, p1, p2]), Some(2.0));
+    /// assert_eq!(graph.path_cost(&[p0, p2]), None);
+    /// ```
+    pub fn path_cost(&self, path: &[usize]) -> Option<f32> {
+        if path.iter().any(|node| *node >= self.nodes.len()) {
+            return None;
+        }
+        path.windows(2)
+            .map(|pair| self.get_edge(pair[0], pair[1]))
+            .sum()
+    }
+
+    /// Gets the position reached after traveling the indicated distance along the path of node handles,
+    /// which is useful for moving an agent at constant speed. The distance is measured along the straight
+    /// segments between the node positions and gets clamped to the start and the end of the path.
+    /// Returns None if the path is empty or contains a node that does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// assert_eq!(graph.position_along_path(&[p0, p1, p2], 0.5), Some([0.5, 0.0]));
+    /// assert_eq!(graph.position_along_path(&[p0, p1, p2], 5.0), Some([1.0, 1.0]));
+    /// ```
+    pub fn position_along_path(&self, path: &[usize], distance: f32) -> Option<[f32; 2]> {
+        if path.is_empty() || path.iter().any(|node| *node >= self.nodes.len()) {
+            return None;
+        }
+
+        let mut remaining = distance.max(0.0);
+        for pair in path.windows(2) {
+            let start = self.nodes[pair[0]].position;
+            let end = self.nodes[pair[1]].position;
+            let length = start.dist_to(&end);
+            if remaining < length {
+                return Some(start.lerp(&end, remaining / length).into());
+            }
+            remaining -= length;
+        }
+
+        Some(self.nodes[path[path.len() - 1]].position.into())
+    }
+
+    /// Gets the facing direction of an agent at every waypoint of the path of node handles, for example
+    /// for turning animations. The direction at a waypoint is the normalized vector towards the next node, and
+    /// at the last node it is the incoming direction. The direction is zero where it is undefined, which is the case for
+    /// a path of one node or for consecutive nodes at the same position.
+    /// Returns an empty vector if one of the nodes does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([2.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 3.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// let headings = graph.path_headings(&[p0, p1, p2]);
+    /// assert_eq!(headings, [[1.0, 0.0], [0.0, 1.0], [0.0, 1.0]]);
+    /// ```
+    pub fn path_headings(&self, path: &[usize]) -> Vec<[f32; 2]> {
+        if path.iter().any(|node| *node >= self.nodes.len()) {
+            return Vec::new();
+        }
+
+        let direction = |from: usize, to: usize| {
+            let (length, normalized) =
+                (self.nodes[to].position - self.nodes[from].position).get_mag_normalized();
+            if length > 0.0 {
+                normalized.into()
+            } else {
+                [0.0, 0.0]
+            }
+        };
+        let mut headings: Vec<[f32; 2]> = path
+            .windows(2)
+            .map(|pair| direction(pair[0], pair[1]))
+            .collect();
+        if let Some(last) = headings.last() {
+            headings.push(*last);
+        } else if !path.is_empty() {
+            headings.push([0.0, 0.0]);
+        }
+        headings
+    }
+
+    /// Gets all nodes within the indicated distance of the segments of a path, which is the corridor along the route.
+    /// This tells what has to be loaded or activated along the way, for example for streaming. The nodes of the
+    /// path itself are always contained, and a path of one node gives the nodes around it. The handles are returned
+    /// in ascending order. The result is empty if the path is empty or contains a handle that does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([2.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 0.5]);
+    /// let p3 = graph.add_node([1.0, 3.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// assert_eq!(graph.path_corridor(&[p0, p1], 1.0), [p0, p1, p2]);
+    /// ```
+    pub fn path_corridor(&self, path: &[usize], band: f32) -> Vec<usize> {
+        if path.is_empty() || path.iter().any(|node| self.node(*node).is_err()) {
+            return Vec::new();
+        }
+        let segments: Vec<Line> = if path.len() == 1 {
+            let position = self.nodes[path[0]].position;
+            vec![Line::new(position, position)]
+        } else {
+            path.windows(2)
+                .map(|pair| Line::new(self.nodes[pair[0]].position, self.nodes[pair[1]].position))
+                .collect()
+        };
+        (0..self.nodes.len())
+            .filter(|node| {
+                let position = self.nodes[*node].position;
+                segments
+                    .iter()
+                    .any(|segment| segment.distance_to_point(position) <= band)
+            })
+            .collect()
+    }
+
+    /// Refines a path of nodes into the shortest route through the corridor around it with the
+    /// simple stupid funnel algorithm. Every inner node of the path becomes a portal, which is a segment
+    /// through the node with the indicated half width that is orthogonal to the mean direction of the adjacent edges.
+    /// The result is a taut path of positions that cuts the corners within the portals.
+    /// Returns an empty path if one of the nodes does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.1]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// assert_eq!(graph.funnel_path(&[p0, p1, p2], 0.2), [[0.0, 0.0], [2.0, 0.0]]);
+    /// ```
+    pub fn funnel_path(&self, path: &[usize], portal_half_width: f32) -> Vec<[f32; 2]> {
+        if path.iter().any(|node| *node >= self.nodes.len()) {
+            return Vec::new();
+        }
+        let positions: Vec<Vec2> = path.iter().map(|node| self.nodes[*node].position).collect();
+        if positions.len() < 3 {
+            return positions
+                .into_iter()
+                .map(|position| position.into())
+                .collect();
+        }
+
+        // The portals as pairs of left and right points, with the end points as degenerated portals.
+        let mut portals = vec![(positions[0], positions[0])];
+        for window in positions.windows(3) {
+            let (_, incoming) = (window[1] - window[0]).get_mag_normalized();
+            let (_, outgoing) = (window[2] - window[1]).get_mag_normalized();
+            let mut direction = incoming + outgoing;
+            if direction.magnitude() < f32::EPSILON {
+                direction = incoming;
+            }
+            let (_, direction) = direction.get_mag_normalized();
+            let right = direction.get_orthogonal();
+            let width = Vec2::new(right.x * portal_half_width, right.y * portal_half_width);
+            portals.push((window[1] - width, window[1] + width));
+        }
+        let end = positions[positions.len() - 1];
+        portals.push((end, end));
+
+        let cross = |first: Vec2, second: Vec2| first.x * second.y - first.y * second.x;
+        let mut result = vec![positions[0]];
+        let (mut apex, mut left, mut right) = (positions[0], positions[0], positions[0]);
+        let (mut left_index, mut right_index) = (0, 0);
+        let mut index = 1;
+        while index < portals.len() {
+            let (new_left, new_right) = portals[index];
+
+            if cross(right - apex, new_right - apex) >= 0.0 {
+                if apex == right || cross(left - apex, new_right - apex) < 0.0 {
+                    right = new_right;
+                    right_index = index;
+                } else {
+                    // The right side crosses over the left one, so the left point becomes the new apex.
+                    apex = left;
+                    result.push(apex);
+                    right = apex;
+                    right_index = left_index;
+                    index = left_index + 1;
+                    continue;
+                }
+            }
+
+            if cross(left - apex, new_left - apex) <= 0.0 {
+                if apex == left || cross(right - apex, new_left - apex) > 0.0 {
+                    left = new_left;
+                    left_index = index;
+                } else {
+                    apex = right;
+                    result.push(apex);
+                    left = apex;
+                    left_index = right_index;
+                    index = right_index + 1;
+                    continue;
+                }
+            }
+            index += 1;
+        }
+
+        if result[result.len() - 1] != end {
+            result.push(end);
+        }
+        result.into_iter().map(|position| position.into()).collect()
+    }
+
+    /// Shortens a path of node handles without ever leaving the graph. A waypoint is only skipped if its
+    /// predecessor on the smoothed path is directly linked to a later node, in which case the path jumps as far
+    /// ahead as possible. Unlike a smoothing by visibility, this never invents shortcuts across gaps
+    /// without a link, so the result is always walkable on the graph if the input is.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// let p3 = graph.add_node([3.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p2, p3).unwrap();
+    /// graph.connect_nodes(p0, p2).unwrap();
+    ///
+    /// assert_eq!(graph.smooth_path_on_graph(&[p0, p1, p2, p3]), [p0, p2, p3]);
+    /// ```
+    pub fn smooth_path_on_graph(&self, path: &[usize]) -> Vec<usize> {
+        let Some(first) = path.first() else {
+            return Vec::new();
+        };
+        let mut result = vec![*first];
+        let mut current = 0;
+        while current + 1 < path.len() {
+            current = ((current + 1)..path.len())
+                .rev()
+                .find(|next| {
+                    self.get_link_index(path[current], path[*next]).is_some()
+                        && self.edge_enabled_unchecked(path[current], path[*next])
+                })
+                .unwrap_or(current + 1);
+            result.push(path[current]);
+        }
+        result
+    }
+
+    /// Sets a cost multiplier for the terrain of a node, the default is 1.0. Each edge charges half
+    /// of the multiplier of both end nodes, so the effective cost of traversing an edge
+    /// in the search is its distance times the average multiplier of its end nodes.
+    ///
+    /// The heuristic of the search is the straight-line distance. It stays admissible as long as
+    /// all multipliers are at least 1.0. Multipliers below that may lead to suboptimal paths.
+    ///
+    /// # Error
+    /// Returns an error if the node does not exist, or if the multiplier is negative or NaN, as a search can not
+    /// handle negative costs.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{ConnectionError, NavGraph};
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// graph.set_node_cost_multiplier(p0, 3.0).unwrap();
+    /// assert_eq!(graph.set_node_cost_multiplier(p0, -1.0), Err(ConnectionError::InvalidMultiplier));
+    /// ```
+    pub fn set_node_cost_multiplier(
+        &mut self,
+        node: usize,
+        multiplier: f32,
+    ) -> Result<(), ConnectionError> {
+        let nav_node = self.node_mut(node)?;
+        if multiplier.is_nan() || multiplier < 0.0 {
+            return Err(ConnectionError::InvalidMultiplier);
+        }
+        nav_node.cost_multiplier = multiplier;
+        self.generation = fresh_id();
+        Ok(())
+    }
+
+    /// Moves a node to a new position. The stored costs of its edges are not updated, which allows
+    /// moving several nodes before repairing all edges at once with [`NavGraph::recompute_edge_costs`].
+    ///
+    /// # Error
+    /// Returns an error if the node does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// graph.set_node_position(p0, [1.0, 2.0]).unwrap();
+    /// assert_eq!(graph.find_nearest_node_with_radius([1.0, 2.0], 0.01), Some(p0));
+    /// ```
+    pub fn set_node_position(
+        &mut self,
+        node: usize,
+        position: [f32; 2],
+    ) -> Result<(), ConnectionError> {
+        self.node_mut(node)?.position = Vec2::from(position);
+        self.layout_id = fresh_id();
+        self.generation = fresh_id();
+        Ok(())
+    }
+
+    /// Recomputes the stored cost of every edge as the distance between the current positions of its
+    /// end nodes. This is the repair step after node positions have been changed.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.set_node_position(p1, [3.0, 0.0]).unwrap();
+    /// graph.recompute_edge_costs();
+    /// assert_eq!(graph.path_cost(&[p0, p1]), Some(3.0));
+    /// ```
+    pub fn recompute_edge_costs(&mut self) {
+        let positions: Vec<Vec2> = self.nodes.iter().map(|node| node.position).collect();
+        for (index, node) in self.nodes.iter_mut().enumerate() {
+            for (partner, cost) in node.connections.iter_mut() {
+                *cost = positions[index].dist_to(&positions[*partner]);
+            }
+        }
+        self.generation = fresh_id();
+    }
+
+    /// Smooths the stored edge costs of noisy terrain data, so that the route choices get more stable. Every iteration
+    /// blends the cost factor of an edge, which is its stored cost divided by its length, by the indicated factor
+    /// towards the mean cost factor of the edges sharing a node with it. A factor of 0 keeps the costs and a factor
+    /// of 1 replaces them with the mean. As only the cost factors get blended, edges whose costs are their lengths
+    /// keep them, so a graph without custom costs stays untouched. The cost factors never drop below the lowest
+    /// one before smoothing, so the heuristic stays admissible if it was before. Edges of length 0 are skipped.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 2.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// graph.smooth_edge_costs(3, 0.5);
+    /// assert_eq!(graph.path_cost(&[p0, p1, p2]), Some(3.0));
+    /// ```
+    pub fn smooth_edge_costs(&mut self, iterations: usize, factor: f32) {
+        let factor = factor.clamp(0.0, 1.0);
+        let mut node_links: Vec<Vec<usize>> = vec![Vec::new(); self.nodes.len()];
+        for (index, (node1, node2)) in self.links.iter().enumerate() {
+            node_links[*node1].push(index);
+            node_links[*node2].push(index);
+        }
+        let lengths: Vec<f32> = self
+            .links
+            .iter()
+            .map(|(node1, node2)| {
+                self.nodes[*node1]
+                    .position
+                    .dist_to(&self.nodes[*node2].position)
+            })
+            .collect();
+        let mut ratios: Vec<Option<f32>> = self
+            .links
+            .iter()
+            .zip(lengths.iter())
+            .map(|((node1, node2), length)| {
+                (*length > 0.0).then(|| self.get_edge(*node1, *node2).unwrap() / length)
+            })
+            .collect();
+
+        let mut changed = false;
+        for _ in 0..iterations {
+            let mut blended = ratios.clone();
+            for (index, (node1, node2)) in self.links.iter().enumerate() {
+                let Some(ratio) = ratios[index] else {
+                    continue;
+                };
+                let (sum, count) = node_links[*node1]
+                    .iter()
+                    .chain(node_links[*node2].iter())
+                    .filter(|other| **other != index)
+                    .filter_map(|other| ratios[*other])
+                    .fold((0.0, 0), |(sum, count), other| (sum + other, count + 1));
+                if count > 0 {
+                    let smoothed = ratio + factor * (sum / count as f32 - ratio);
+                    changed |= smoothed != ratio;
+                    blended[index] = Some(smoothed);
+                }
+            }
+            ratios = blended;
+        }
+
+        if changed {
+            for index in 0..self.links.len() {
+                if let Some(ratio) = ratios[index] {
+                    let (node1, node2) = self.links[index];
+                    self.set_stored_cost(node1, node2, ratio * lengths[index]);
+                }
+            }
+        }
+    }
+
+    /// Sets the category tags of a node as bit flags, for example to mark it as water, road, or cover.
+    /// The default is [`ALL_TAGS`]. The tags of nodes are meant for the own classification of the
+    /// application, they do not influence the searches.
+    ///
+    /// # Error
+    /// Returns an error if the node does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// const WATER: u32 = 1;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// graph.set_node_tags(p0, WATER).unwrap();
+    /// assert_eq!(graph.node_tags(p0), Some(WATER));
+    /// ```
+    pub fn set_node_tags(&mut self, node: usize, tags: u32) -> Result<(), ConnectionError> {
+        self.node_mut(node)?.tags = tags;
+        self.generation = fresh_id();
+        Ok(())
+    }
+
+    /// Gets the category tags of a node. Returns None if the node does not exist.
+    pub fn node_tags(&self, node: usize) -> Option<u32> {
+        self.nodes.get(node).map(|nav_node| nav_node.tags)
+    }
+
+    /// Sets the height of a node, the default is 0.0. The heights are only taken into account by
+    /// [`NavGraph::search_graph_with_elevation`], all other functions work on the plane.
+    ///
+    /// # Error
+    /// Returns an error if the node does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// graph.set_node_height(p0, 2.5).unwrap();
+    /// assert_eq!(graph.node_height(p0), Some(2.5));
+    /// ```
+    pub fn set_node_height(&mut self, node: usize, height: f32) -> Result<(), ConnectionError> {
+        self.node_mut(node)?.height = height;
+        self.generation = fresh_id();
+        Ok(())
+    }
+
+    /// Gets the height of a node. Returns None if the node does not exist.
+    pub fn node_height(&self, node: usize) -> Option<f32> {
+        self.nodes.get(node).map(|nav_node| nav_node.height)
+    }
+
+    /// Sets the category tags of an existing edge as bit flags. The default is [`ALL_TAGS`].
+    /// The tags are used by [`NavGraph::search_graph_with_mask`] to restrict the edges a unit may use.
+    ///
+    /// # Error
+    /// Returns an error if the link does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// const WATER: u32 = 1;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.set_edge_tags(p1, p0, WATER).unwrap();
+    /// assert_eq!(graph.edge_tags(p0, p1), Some(WATER));
+    /// ```
+    pub fn set_edge_tags(
+        &mut self,
+        node1: usize,
+        node2: usize,
+        tags: u32,
+    ) -> Result<(), ConnectionError> {
+        self.edge_attributes_mut(node1, node2)?.tags = tags;
+        Ok(())
+    }
+
+    /// Gets the category tags of an edge. Returns None if the link does not exist.
+    pub fn edge_tags(&self, node1: usize, node2: usize) -> Option<u32> {
+        self.get_edge(node1, node2)?;
+        Some(self.edge_tags_unchecked(node1, node2))
+    }
+
+    /// Gets the category tags of an edge that is known to exist.
+    fn edge_tags_unchecked(&self, node1: usize, node2: usize) -> u32 {
+        self.edge_attributes
+            .get(&edge_key(node1, node2))
+            .map_or(ALL_TAGS, |attributes| attributes.tags)
+    }
+
+    /// Sets the clearance of an existing edge, which is the width of the corridor around it that is free of
+    /// obstacles. The default is infinity, which means the edge is not constrained.
+    ///
+    /// # Error
+    /// Returns an error if the link does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.set_edge_clearance(p0, p1, 0.5).unwrap();
+    /// assert_eq!(graph.edge_clearance(p1, p0), Some(0.5));
+    /// ```
+    pub fn set_edge_clearance(
+        &mut self,
+        node1: usize,
+        node2: usize,
+        clearance: f32,
+    ) -> Result<(), ConnectionError> {
+        self.edge_attributes_mut(node1, node2)?.clearance = clearance;
+        Ok(())
+    }
+
+    /// Gets the clearance of an edge. Returns None if the link does not exist.
+    pub fn edge_clearance(&self, node1: usize, node2: usize) -> Option<f32> {
+        self.get_edge(node1, node2)?;
+        Some(
+            self.edge_attributes
+                .get(&edge_key(node1, node2))
+                .map_or(f32::INFINITY, |attributes| attributes.clearance),
+        )
+    }
+
+    /// Enables or disables an existing edge, the default is enabled. A disabled edge keeps its cost and attributes,
+    /// but the searches, the Dijkstra based distances, the centralities and the connectivity queries do not use it,
+    /// like a door that gets locked. Enabling it again restores it. The structural queries like [`NavGraph::get_edge`],
+    /// [`NavGraph::node_degree`], [`NavGraph::k_hop_neighborhood`] and [`NavGraph::to_csr`] still list it.
+    ///
+    /// # Error
+    /// Returns an error if the link does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.set_edge_enabled(p0, p1, false).unwrap();
+    /// assert_eq!(graph.edge_enabled(p1, p0), Some(false));
+    /// assert_eq!(graph.search_graph(p0, p1), None);
+    /// ```
+    pub fn set_edge_enabled(
+        &mut self,
+        node1: usize,
+        node2: usize,
+        enabled: bool,
+    ) -> Result<(), ConnectionError> {
+        self.edge_attributes_mut(node1, node2)?.enabled = enabled;
+        Ok(())
+    }
+
+    /// Gets if an edge is enabled. Returns None if the link does not exist.
+    pub fn edge_enabled(&self, node1: usize, node2: usize) -> Option<bool> {
+        self.get_edge(node1, node2)?;
+        Some(self.edge_enabled_unchecked(node1, node2))
+    }
+
+    /// Gets if an edge that is known to exist is enabled. Graphs without any edge attributes skip the lookup.
+    fn edge_enabled_unchecked(&self, node1: usize, node2: usize) -> bool {
+        self.edge_attributes.is_empty()
+            || self
+                .edge_attributes
+                .get(&edge_key(node1, node2))
+                .is_none_or(|attributes| attributes.enabled)
+    }
+
+    /// Gets the smallest clearance of the edges along the path of node handles, which tells
+    /// whether a unit of a certain size may pass the route. A path without edges is not constrained and
+    /// has infinite clearance. Returns None if the path is not valid, as checked by [`NavGraph::is_valid_path`].
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.set_edge_clearance(p1, p2, 0.2).unwrap();
+    /// assert_eq!(graph.path_min_clearance(&[p0, p1, p2]), Some(0.2));
+    /// ```
+    pub fn path_min_clearance(&self, path: &[usize]) -> Option<f32> {
+        if path.iter().any(|node| *node >= self.nodes.len()) {
+            return None;
+        }
+        path.windows(2)
+            .map(|pair| self.edge_clearance(pair[0], pair[1]))
+            .try_fold(f32::INFINITY, |min, clearance| Some(min.min(clearance?)))
+    }
+
+    /// Gets the attributes of an existing edge for modification, which creates them with the defaults if needed.
+    fn edge_attributes_mut(
+        &mut self,
+        node1: usize,
+        node2: usize,
+    ) -> Result<&mut EdgeAttributes, ConnectionError> {
+        if self.get_link_index(node1, node2).is_none() {
+            return Err(ConnectionError::LinkDoesntExist);
+        }
+        self.generation = fresh_id();
+        Ok(self
+            .edge_attributes
+            .entry(edge_key(node1, node2))
+            .or_default())
+    }
+
+    /// Gets the effective cost of traversing a connection with the indicated distance between
+    /// the two nodes, taking the cost multipliers of the nodes into account.
+    fn traversal_cost(&self, from: usize, to: usize, distance: f32) -> f32 {
+        distance * 0.5 * (self.nodes[from].cost_multiplier + self.nodes[to].cost_multiplier)
+    }
+
+    /// Computes the shortest path distances from the source node to all nodes with the Dijkstra
+    /// algorithm. The distances take the node cost multipliers into account, just as the search does.
+    /// The entry for a node is None if it can not be reached from the source.
+    /// The cost is O(E log V).
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([5.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// assert_eq!(graph.dijkstra_from(p0), [Some(0.0), Some(1.0), None]);
+    /// ```
+    pub fn dijkstra_from(&self, source: usize) -> Vec<Option<f32>> {
+        self.dijkstra_tree(source).0
+    }
+
+    /// Computes the shortest path tree from the source node with the Dijkstra algorithm, as the parent of
+    /// every node on its shortest path towards the source. The path from any node to the source can then be
+    /// reconstructed in O(depth) by following the parents, without searching again, which is the building block
+    /// for flow fields. The source is its own parent, and the entry for a node is None if it can not be reached.
+    /// The costs are the ones of [`NavGraph::dijkstra_from`].
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// let p3 = graph.add_node([5.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// assert_eq!(graph.shortest_path_tree(p0), [Some(p0), Some(p0), Some(p1), None]);
+    /// ```
+    pub fn shortest_path_tree(&self, source: usize) -> Vec<Option<usize>> {
+        self.dijkstra_tree(source).1
+    }
+
+    /// Computes the matrix of the shortest path costs between all pairs of the listed nodes, which may be fed
+    /// into external route optimizers like TSP solvers. The entry in row i and column j is the cost from the i-th
+    /// to the j-th listed node, as computed by [`NavGraph::dijkstra_from`] from every listed node.
+    /// Unreachable pairs and nodes that do not exist get an infinite cost.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([3.0, 4.0]);
+    /// let p2 = graph.add_node([9.0, 9.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let matrix = graph.distance_matrix(&[p0, p1, p2]);
+    /// assert_eq!(matrix[0], [0.0, 5.0, f32::INFINITY]);
+    /// assert_eq!(matrix[1][0], 5.0);
+    /// ```
+    pub fn distance_matrix(&self, nodes: &[usize]) -> Vec<Vec<f32>> {
+        nodes
+            .iter()
+            .map(|source| {
+                if *source >= self.nodes.len() {
+                    return vec![f32::INFINITY; nodes.len()];
+                }
+                let distances = self.dijkstra_from(*source);
+                nodes
+                    .iter()
+                    .map(|target| {
+                        distances
+                            .get(*target)
+                            .copied()
+                            .flatten()
+                            .unwrap_or(f32::INFINITY)
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Runs the Dijkstra algorithm from the source node and gets the distances and the parents of all nodes.
+    fn dijkstra_tree(&self, source: usize) -> (Vec<Option<f32>>, Vec<Option<usize>>) {
+        let mut distances: Vec<Option<f32>> = vec![None; self.nodes.len()];
+        let mut parents: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut queue = BinaryHeap::new();
+        distances[source] = Some(0.0);
+        parents[source] = Some(source);
+        queue.push(HeapEntry {
+            cost: 0.0,
+            node: source,
+        });
+
+        while let Some(HeapEntry { cost, node }) = queue.pop() {
+            // Outdated entries of nodes that have been reached cheaper in the meantime get skipped.
+            if distances[node].is_some_and(|best| cost > best) {
+                continue;
+            }
+            for (partner, distance) in self.nodes[node].connections.iter() {
+                if !self.edge_enabled_unchecked(node, *partner) {
+                    continue;
+                }
+                let new_cost = cost + self.traversal_cost(node, *partner, *distance);
+                if distances[*partner].is_none_or(|best| new_cost < best) {
+                    distances[*partner] = Some(new_cost);
+                    parents[*partner] = Some(node);
+                    queue.push(HeapEntry {
+                        cost: new_cost,
+                        node: *partner,
+                    });
+                }
+            }
+        }
+        (distances, parents)
+    }
+
+    /// Follows a distance field, as computed by [`NavGraph::dijkstra_from`] for a goal, by repeatedly moving to the
+    /// neighbor with the smallest field value until no neighbor is lower. For a field computed from a goal
+    /// this leads every reachable node to the goal, so many agents may share one field instead of searching
+    /// individually. The path is not necessarily the shortest one, as the edge costs are not considered. The returned path starts with the indicated node.
+    /// Returns an empty path if the node does not exist or has no field value.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// let field = graph.dijkstra_from(p2);
+    /// assert_eq!(graph.descend_field(&field, p0), [p0, p1, p2]);
+    /// ```
+    pub fn descend_field(&self, field: &[Option<f32>], from: usize) -> Vec<usize> {
+        let value = |node: usize| field.get(node).copied().flatten();
+        if from >= self.nodes.len() {
+            return Vec::new();
+        }
+        let Some(mut current_value) = value(from) else {
+            return Vec::new();
+        };
+
+        let mut path = vec![from];
+        let mut current = from;
+        while let Some((next, next_value)) = self.nodes[current]
+            .connections
+            .iter()
+            .filter(|(partner, _)| self.edge_enabled_unchecked(current, *partner))
+            .filter_map(|(partner, _)| {
+                value(*partner).map(|partner_value| (*partner, partner_value))
+            })
+            .min_by_key(|(_, partner_value)| OrderedF32(*partner_value))
+        {
+            // The strict decrease also guarantees the termination.
+            if next_value >= current_value {
+                break;
+            }
+            path.push(next);
+            current = next;
+            current_value = next_value;
+        }
+        path
+    }
+
+    /// Computes the betweenness centrality of every node, in the order of the handles: the number of shortest paths
+    /// between all pairs of other nodes that pass through the node, where a pair with several equally short paths
+    /// contributes the fraction of them passing through it. Chokepoints score highest, so this may be used to place
+    /// towers or ambushes. Every unordered pair of nodes counts once, and the costs include the node cost multipliers.
+    /// Costs within a relative tolerance of 0.00001 count as equal.
+    /// This uses the algorithm of Brandes with a Dijkstra search from every node, so the cost is O(V E log V).
+    /// This is only acceptable for small to medium graphs.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// // Only the path from p0 to p2 passes through another node.
+    /// assert_eq!(graph.betweenness_centrality(), [0.0, 1.0, 0.0]);
+    /// ```
+    pub fn betweenness_centrality(&self) -> Vec<f32> {
+        let mut scores = vec![0.0_f64; self.nodes.len()];
+        for source in 0..self.nodes.len() {
+            let (order, predecessors, path_counts) = self.brandes_pass(source);
+            // The dependencies accumulate from the farthest nodes back towards the source.
+            let mut dependencies = vec![0.0_f64; self.nodes.len()];
+            for node in order.iter().rev() {
+                for predecessor in predecessors[*node].iter() {
+                    dependencies[*predecessor] += path_counts[*predecessor] / path_counts[*node]
+                        * (1.0 + dependencies[*node]);
+                }
+                if *node != source {
+                    scores[*node] += dependencies[*node];
+                }
+            }
+        }
+
+        // Every pair has been counted from both of its nodes.
+        scores.iter().map(|score| (score * 0.5) as f32).collect()
+    }
+
+    /// Computes the edge betweenness of every link, in the order of the links: the number of shortest paths
+    /// between all pairs of nodes that traverse the link, where a pair with several equally short paths contributes
+    /// the fraction of them using the link. The main roads of a map score highest, so this may drive
+    /// the tiers of roads in procedural maps. This is the link counterpart of [`NavGraph::betweenness_centrality`],
+    /// with the same counting and the same cost of O(V E log V).
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// // Both links are on the paths p0-p1 or p1-p2 and on the path p0-p2.
+    /// assert_eq!(graph.edge_betweenness(), [2.0, 2.0]);
+    /// ```
+    pub fn edge_betweenness(&self) -> Vec<f32> {
+        let link_indices: HashMap<(usize, usize), usize> = self
+            .links
+            .iter()
+            .enumerate()
+            .map(|(index, (node1, node2))| (edge_key(*node1, *node2), index))
+            .collect();
+        let mut scores = vec![0.0_f64; self.links.len()];
+
+        for source in 0..self.nodes.len() {
+            let (order, predecessors, path_counts) = self.brandes_pass(source);
+            // The dependencies accumulate from the farthest nodes back towards the source.
+            let mut dependencies = vec![0.0_f64; self.nodes.len()];
+            for node in order.iter().rev() {
+                for predecessor in predecessors[*node].iter() {
+                    let share = path_counts[*predecessor] / path_counts[*node]
+                        * (1.0 + dependencies[*node]);
+                    scores[link_indices[&edge_key(*predecessor, *node)]] += share;
+                    dependencies[*predecessor] += share;
+                }
+            }
+        }
+
+        // Every pair has been counted from both of its nodes.
+        scores.iter().map(|score| (score * 0.5) as f32).collect()
+    }
+
+    /// Runs the Dijkstra search of the algorithm of Brandes from the source. Returns the reached nodes in the order
+    /// they were settled, the predecessors of every node on its shortest paths and the number of these paths.
+    fn brandes_pass(&self, source: usize) -> (Vec<usize>, Vec<Vec<usize>>, Vec<f64>) {
+        let count = self.nodes.len();
+        let tolerance = |best: f32| 0.00001 * best.max(1.0);
+        let mut distances: Vec<Option<f32>> = vec![None; count];
+        let mut path_counts = vec![0.0_f64; count];
+        let mut predecessors: Vec<Vec<usize>> = vec![Vec::new(); count];
+        let mut settled = vec![false; count];
+        let mut order = Vec::new();
+        let mut queue = BinaryHeap::new();
+        distances[source] = Some(0.0);
+        path_counts[source] = 1.0;
+        queue.push(HeapEntry {
+            cost: 0.0,
+            node: source,
+        });
+
+        while let Some(HeapEntry { cost, node }) = queue.pop() {
+            if settled[node] {
+                continue;
+            }
+            settled[node] = true;
+            order.push(node);
+            for (partner, distance) in self.nodes[node].connections.iter() {
+                if settled[*partner] || !self.edge_enabled_unchecked(node, *partner) {
+                    continue;
+                }
+                let new_cost = cost + self.traversal_cost(node, *partner, *distance);
+                match distances[*partner] {
+                    Some(best) if (new_cost - best).abs() <= tolerance(best) => {
+                        path_counts[*partner] += path_counts[node];
+                        predecessors[*partner].push(node);
+                    }
+                    Some(best) if new_cost > best => {}
+                    _ => {
+                        distances[*partner] = Some(new_cost);
+                        path_counts[*partner] = path_counts[node];
+                        predecessors[*partner] = vec![node];
+                        queue.push(HeapEntry {
+                            cost: new_cost,
+                            node: *partner,
+                        });
+                    }
+                }
+            }
+        }
+        (order, predecessors, path_counts)
+    }
+
+    /// Computes the diameter of the graph, this is the largest shortest path distance between any
+    /// pair of connected nodes. It tells the worst case traversal distance across the map.
+    /// Returns the pair of nodes and their distance, or None if no two nodes are connected.
+    ///
+    /// This runs the Dijkstra algorithm from every node, so the cost is O(V·(E log V)).
+    /// This is only acceptable for small to medium graphs.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// assert_eq!(graph.diameter(), Some((p0, p2, 2.0)));
+    /// ```
+    pub fn diameter(&self) -> Option<(usize, usize, f32)> {
+        let mut result: Option<(usize, usize, f32)> = None;
+        for source in 0..self.nodes.len() {
+            for (destination, distance) in self.dijkstra_from(source).into_iter().enumerate() {
+                if destination <= source {
+                    continue;
+                }
+                if let Some(distance) = distance
+                    && result.is_none_or(|(_, _, best)| distance > best)
+                {
+                    result = Some((source, destination, distance));
+                }
+            }
+        }
+        result
+    }
+
+    /// Finds the node that is farthest from the source, which is the reachable node with the largest shortest path
+    /// distance, for example to place an objective as far as possible from a spawn point. That distance is the
+    /// eccentricity of the source. The distances are the ones of [`NavGraph::dijkstra_from`], and of several nodes
+    /// with the same distance the one with the lowest handle is returned. An isolated source is its own farthest node.
+    /// Returns None if the source does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 2.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// assert_eq!(graph.farthest_node(p0), Some((p2, 3.0)));
+    /// ```
+    pub fn farthest_node(&self, from: usize) -> Option<(usize, f32)> {
+        self.node(from).ok()?;
+        let mut result = (from, 0.0);
+        for (node, distance) in self.dijkstra_from(from).into_iter().enumerate() {
+            if let Some(distance) = distance
+                && distance > result.1
+            {
+                result = (node, distance);
+            }
+        }
+        Some(result)
+    }
+
+    /// Finds the center of the graph, which is the node whose largest shortest path distance to any other node,
+    /// as obtained with [`NavGraph::farthest_node`], is the smallest. This is the most central place for a hub or an
+    /// objective. Of several such nodes the one with the lowest handle is returned.
+    /// Returns None if the graph is empty or not connected, as some nodes are infinitely far away then.
+    ///
+    /// This runs the Dijkstra algorithm from every node, so the cost is O(V·(E log V)).
+    /// This is only acceptable for small to medium graphs.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// assert_eq!(graph.graph_center(), Some(p1));
+    /// ```
+    pub fn graph_center(&self) -> Option<usize> {
+        let mut result: Option<(usize, f32)> = None;
+        for source in 0..self.nodes.len() {
+            let distances = self.dijkstra_from(source);
+            if distances.contains(&None) {
+                return None;
+            }
+            let eccentricity = distances.into_iter().flatten().fold(0.0, f32::max);
+            if result.is_none_or(|(_, best)| eccentricity < best) {
+                result = Some((source, eccentricity));
+            }
+        }
+        result.map(|(node, _)| node)
+    }
+
+    /// Enumerates all shortest paths from the start to the destination node, while a search only returns one of
+    /// them when several paths have the same cost. This may be used to pick between them fairly or to visualize
+    /// symmetric routes. The costs are the ones of [`NavGraph::dijkstra_from`]. Paths whose costs differ by less than
+    /// a relative tolerance of 1e-5 count as equal, so that rounding in the summation does not drop one of them.
+    /// As the number of shortest paths may grow exponentially, for example on an open grid, the enumeration stops
+    /// after the maximum number of paths. The paths are returned in lexicographic order of their handles, and
+    /// a capped result contains the first paths of that order. The result is empty if the destination can not
+    /// be reached.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([0.0, 1.0]);
+    /// let p3 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p0, p2).unwrap();
+    /// graph.connect_nodes(p1, p3).unwrap();
+    /// graph.connect_nodes(p2, p3).unwrap();
+    ///
+    /// assert_eq!(graph.all_shortest_paths(p0, p3, 10), [[p0, p1, p3], [p0, p2, p3]]);
+    /// ```
+    pub fn all_shortest_paths(
+        &self,
+        start_index: usize,
+        destination_index: usize,
+        max_paths: usize,
+    ) -> Vec<Vec<usize>> {
+        if self.node(start_index).is_err() || self.node(destination_index).is_err() {
+            return Vec::new();
+        }
+        let distances = self.dijkstra_from(start_index);
+        if distances[destination_index].is_none() || max_paths == 0 {
+            return Vec::new();
+        }
+
+        // The predecessors of a node are all neighbors through which it is reached at its shortest distance.
+        let predecessors: Vec<Vec<usize>> = (0..self.nodes.len())
+            .map(|node| {
+                let Some(node_distance) = distances[node] else {
+                    return Vec::new();
+                };
+                self.nodes[node]
+                    .connections
+                    .iter()
+                    .filter(|(partner, distance)| {
+                        self.edge_enabled_unchecked(*partner, node)
+                            && distances[*partner].is_some_and(|partner_distance| {
+                                let cost = self.traversal_cost(*partner, node, *distance);
+                                (partner_distance + cost - node_distance).abs()
+                                    <= SHORTEST_PATH_TOLERANCE * node_distance
+                            })
+                    })
+                    .map(|(partner, _)| *partner)
+                    .collect()
+            })
+            .collect();
+
+        // Only the nodes from which the predecessors lead back to the destination are on a shortest path.
+        let mut on_shortest = vec![false; self.nodes.len()];
+        on_shortest[destination_index] = true;
+        let mut todo = vec![destination_index];
+        while let Some(node) = todo.pop() {
+            for predecessor in predecessors[node].iter() {
+                if !on_shortest[*predecessor] {
+                    on_shortest[*predecessor] = true;
+                    todo.push(*predecessor);
+                }
+            }
+        }
+        let mut successors: Vec<Vec<usize>> = vec![Vec::new(); self.nodes.len()];
+        for node in (0..self.nodes.len()).filter(|node| on_shortest[*node]) {
+            for predecessor in predecessors[node].iter() {
+                successors[*predecessor].push(node);
+            }
+        }
+
+        // The paths get enumerated forwards from the start with a depth-first search. As the successors are
+        // ascending, the paths are found in lexicographic order, so the first paths are kept when capping.
+        let mut result: Vec<Vec<usize>> = Vec::new();
+        let mut path = vec![start_index];
+        let mut cursors = vec![0];
+        let mut on_path = vec![false; self.nodes.len()];
+        on_path[start_index] = true;
+        while let Some(cursor) = cursors.last().copied() {
+            let current = *path.last().unwrap();
+            let next = successors[current].get(cursor).copied();
+            if current != destination_index
+                && let Some(next) = next
+            {
+                *cursors.last_mut().unwrap() += 1;
+                if !on_path[next] {
+                    on_path[next] = true;
+                    path.push(next);
+                    cursors.push(0);
+                }
+                continue;
+            }
+            if current == destination_index {
+                result.push(path.clone());
+                if result.len() == max_paths {
+                    break;
+                }
+            }
+            on_path[current] = false;
+            path.pop();
+            cursors.pop();
+        }
+        result
+    }
+
+    /// Enumerates all simple paths from the start to the destination node whose cost does not
+    /// exceed the maximum cost. This may be used to present alternative routes. The enumeration
+    /// is a bounded depth-first search, that stops after the maximum number of paths has been found
+    /// to avoid a combinatorial explosion. The found paths are returned with their costs, sorted by
+    /// ascending cost. The result is empty if one of the nodes does not exist or no path is requested.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p0, p2).unwrap();
+    ///
+    /// let paths = graph.all_paths(p0, p2, 10.0, 10);
+    /// assert_eq!(paths.len(), 2);
+    /// ```
+    pub fn all_paths(
+        &self,
+        start_index: usize,
+        destination_index: usize,
+        max_cost: f32,
+        max_paths: usize,
+    ) -> Vec<(Vec<usize>, f32)> {
+        let mut result = Vec::new();
+        if self.node(start_index).is_err()
+            || self.node(destination_index).is_err()
+            || max_paths == 0
+        {
+            return result;
+        }
+        let mut on_path = vec![false; self.nodes.len()];
+        let mut path = vec![start_index];
+        on_path[start_index] = true;
+        self.collect_paths(
+            destination_index,
+            max_cost,
+            max_paths,
+            0.0,
+            &mut path,
+            &mut on_path,
+            &mut result,
+        );
+        result.sort_by_key(|(_, cost)| OrderedF32(*cost));
+        result
+    }
+
+    /// The recursive depth-first search for [`NavGraph::all_paths`] extending the current path.
+    #[allow(clippy::too_many_arguments)]
+    fn collect_paths(
+        &self,
+        destination_index: usize,
+        max_cost: f32,
+        max_paths: usize,
+        cost: f32,
+        path: &mut Vec<usize>,
+        on_path: &mut [bool],
+        result: &mut Vec<(Vec<usize>, f32)>,
+    ) {
+        let current = *path.last().unwrap();
+        if current == destination_index {
+            result.push((path.clone(), cost));
+            return;
+        }
+
+        for (partner, distance) in self.nodes[current].connections.iter() {
+            if result.len() >= max_paths {
+                return;
+            }
+            if !self.edge_enabled_unchecked(current, *partner) {
+                continue;
+            }
+            let new_cost = cost + self.traversal_cost(current, *partner, *distance);
+            if on_path[*partner] || new_cost > max_cost {
+                continue;
+            }
+            on_path[*partner] = true;
+            path.push(*partner);
+            self.collect_paths(
+                destination_index,
+                max_cost,
+                max_paths,
+                new_cost,
+                path,
+                on_path,
+                result,
+            );
+            path.pop();
+            on_path[*partner] = false;
+        }
+    }
+
+    /// Does the real search from the start point to the end point of the graph. This method is the real search operation.
+    /// # Parameters:
+    /// * start: The start point to start searching for,
+    /// * end: The end point of the search.
+    /// # Returns
+    /// If the algorithm could find a path, it returns the positions of the path; otherwise, it returns None.
+    /// The positions are the node indicators that have been generated by *add_node*.
+    ///
+    /// # Example:
+    ///
+    ///  ```
+    ///  use astar_lib::a_star::NavGraph;
+    ///  let mut graph = NavGraph::new();
+    ///  let p0 = graph.add_node([0.0, 0.0]);
+    ///  let p1 = graph.add_node([0.5, 0.5]);
+    ///  let p2 = graph.add_node([1.0, 0.0]);
+    ///  let p3 = graph.add_node([1.0, 1.0]);
+    ///  let p4 = graph.add_node([0.1, 0.0]);
+    ///  graph.connect_nodes(p0, p1).unwrap();
+    ///  graph.connect_nodes(p1, p2).unwrap();
+    ///  graph.connect_nodes(p0, p2).unwrap();
+    ///  graph.connect_nodes(p1, p4).unwrap();
+    ///  graph.connect_nodes(p4, p3).unwrap();
+    ///  graph.connect_nodes(p2, p3).unwrap();
+    ///
+    ///  let result = graph.search_graph(p0, p3);
+    ///
+    ///  if let Some(result) = result {
+    ///      for pos in result.iter() {
+    ///          println!("{:?}", pos);
+    ///       } }
+    ///  ```
+    pub fn search_graph(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+    ) -> Option<Vec<usize>> {
+        self.search_graph_with_options(start_index, destination_index, &SearchOptions::default())
+            .into_path()
+    }
+
+    /// Performs the search like [`NavGraph::search_graph`] with the indicated options, which combine the heuristic weight,
+    /// a cap on the number of closed nodes, and the danger, the mask and the avoided nodes of the specialized searches.
+    /// This way a UI may, for example, preview a quick weighted path while hovering and search the optimal one when an
+    /// order is given. If the cap is hit, the node states show the partial search.
+    ///
+    /// # Panics
+    /// Panics if the heuristic weight of the options is negative, infinite or NaN.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{NavGraph, SearchOptions, SearchResult};
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// let p3 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p0, p3).unwrap();
+    /// graph.connect_nodes(p3, p2).unwrap();
+    ///
+    /// let options = SearchOptions { avoid: &[p1], ..SearchOptions::default() };
+    /// assert_eq!(graph.search_graph_with_options(p0, p2, &options), SearchResult::Found(vec![p0, p3, p2]));
+    /// let options = SearchOptions { max_expansions: Some(1), ..SearchOptions::default() };
+    /// assert_eq!(graph.search_graph_with_options(p0, p2, &options), SearchResult::Capped);
+    /// ```
+    pub fn search_graph_with_options(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+        options: &SearchOptions,
+    ) -> SearchResult {
+        if let Some(weight) = options.heuristic_weight {
+            assert_valid_heuristic_weight(weight);
+        }
+        let mut scratch = std::mem::take(&mut self.last_search);
+        scratch.cache_heuristic(
+            (destination_index, self.layout_id),
+            self.nodes.iter().map(|node| &node.position),
+            self.nodes[destination_index].position,
+        );
+        let heuristic_values = std::mem::take(&mut scratch.heuristic_values);
+        let weight = options.heuristic_weight.unwrap_or(self.heuristic_weight);
+        let heuristic = |node: usize| weight * heuristic_values[node];
+        let entry_cost = |from: usize, to: usize| {
+            if let Some(mask) = options.allowed_mask
+                && self.edge_tags_unchecked(from, to) & mask == 0
+            {
+                return None;
+            }
+            if options.avoid.contains(&to) {
+                return None;
+            }
+            Some(options.danger.get(to).copied().unwrap_or(0.0))
+        };
+
+        let use_heap = self.nodes.len() >= self.heap_threshold;
+        self.seed_search(&[start_index], &mut scratch, &heuristic, use_heap);
+        let mut result = SearchResult::Capped;
+        for _ in 0..options.max_expansions.unwrap_or(usize::MAX) {
+            match self.search_step(destination_index, &mut scratch, &entry_cost, &heuristic) {
+                SearchStatus::Running => {}
+                SearchStatus::Found(path) => {
+                    result = SearchResult::Found(path);
+                    break;
+                }
+                SearchStatus::NoPath => {
+                    result = SearchResult::NoPath;
+                    break;
+                }
+            }
+        }
+        scratch.heuristic_values = heuristic_values;
+        self.last_search = scratch;
+        self.last_query = Some((start_index, destination_index));
+        result
+    }
+
+    /// Does the search like [`NavGraph::search_graph`], but remembers the results of the last 16 queries, so that
+    /// routes requested repeatedly, like the ones between patrol waypoints, are returned without searching again.
+    /// The least recently used result gets dropped when the cache is full, so the memory stays bounded by
+    /// 16 paths. Every change of the graph that updates its [`NavGraph::generation`], as well as
+    /// [`NavGraph::set_heuristic_weight`], invalidates all cached results, so they are never stale.
+    /// A cache hit performs no search and therefore clears the search markings, like
+    /// [`NavGraph::clear_search_state`], but it still counts as the [`NavGraph::last_query`].
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// assert_eq!(graph.search_graph_cached(p0, p1), Some(vec![p0, p1]));
+    /// assert_eq!(graph.search_graph_cached(p0, p1), Some(vec![p0, p1]));
+    /// assert_eq!(graph.closed_nodes().count(), 0);
+    /// ```
+    pub fn search_graph_cached(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+    ) -> Option<Vec<usize>> {
+        if self.path_cache_generation != self.generation {
+            self.path_cache.clear();
+            self.path_cache_generation = self.generation;
+        }
+        let key = (start_index, destination_index);
+        if let Some(position) = self.path_cache.iter().position(|(entry, _)| *entry == key) {
+            let entry = self.path_cache.remove(position).unwrap();
+            let result = entry.1.clone();
+            self.path_cache.push_front(entry);
+            self.clear_search_state();
+            self.last_query = Some(key);
+            return result;
+        }
+
+        let result = self.search_graph(start_index, destination_index);
+        if self.path_cache.len() == PATH_CACHE_SIZE {
+            self.path_cache.pop_back();
+        }
+        self.path_cache.push_front((key, result.clone()));
+        result
+    }
+
+    /// Does the search like [`NavGraph::search_graph`], but never gives up. If the destination can not be reached,
+    /// the path leads to the reachable node that is closest to the destination instead. This lets an agent
+    /// make progress towards a sealed area, for example to wait at its door. As the search has closed all reachable
+    /// nodes in that case, the closest node is found among the closed ones. The nodes of the returned path are
+    /// marked as solution in both cases.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([3.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// assert!(graph.search_graph(p0, p2).is_none());
+    /// assert_eq!(graph.search_graph_best_effort(p0, p2), [p0, p1]);
+    /// ```
+    pub fn search_graph_best_effort(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+    ) -> Vec<usize> {
+        if let Some(path) = self.search_graph(start_index, destination_index) {
+            return path;
+        }
+        let dest_point = self.nodes[destination_index].position;
+        let closest = self
+            .closed_nodes()
+            .min_by_key(|node| OrderedF32(self.nodes[*node].position.dist_to(&dest_point)))
+            .unwrap_or(start_index);
+        self.last_search.get_path(closest)
+    }
+
+    /// Searches the path with the fewest links from the start to the destination node, ignoring the distances,
+    /// for example for the fewest teleports or decisions. This is a breadth-first search, so every link costs 1.0,
+    /// and the cost of a node from [`NavGraph::cost_to`] is the number of links to it. The node states are kept
+    /// like in [`NavGraph::search_graph`]. Returns None if there is no path or if one of the nodes does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p0, p2).unwrap();
+    ///
+    /// assert_eq!(graph.search_graph_fewest_hops(p0, p2), Some(vec![p0, p2]));
+    /// ```
+    pub fn search_graph_fewest_hops(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+    ) -> Option<Vec<usize>> {
+        if start_index.max(destination_index) >= self.nodes.len() {
+            return None;
+        }
+        self.last_query = Some((start_index, destination_index));
+        let mut scratch = std::mem::take(&mut self.last_search);
+        scratch.reset(self.nodes.len(), false);
+        scratch.states[start_index] = NodeState::Visited;
+        scratch.g_values[start_index] = 0.0;
+        scratch.ancestors[start_index] = start_index;
+        let mut todo = VecDeque::from([start_index]);
+
+        let mut result = None;
+        while let Some(node) = todo.pop_front() {
+            scratch.states[node] = NodeState::Closed;
+            if node == destination_index {
+                result = Some(scratch.get_path(destination_index));
+                break;
+            }
+            for (partner, _) in self.nodes[node].connections.iter() {
+                if scratch.states[*partner] != NodeState::Clear
+                    || !self.edge_enabled_unchecked(node, *partner)
+                {
+                    continue;
+                }
+                scratch.states[*partner] = NodeState::Visited;
+                scratch.g_values[*partner] = scratch.g_values[node] + 1.0;
+                scratch.ancestors[*partner] = node;
+                todo.push_back(*partner);
+            }
+        }
+        self.last_search = scratch;
+        result
+    }
+
+    /// Searches the shortest path from the start node through all waypoints in the indicated order to the
+    /// destination, for example for patrol routes. Every leg is searched like in [`NavGraph::search_graph`], and the
+    /// legs are concatenated, where the junction node between two legs appears only once. The returned cost is the
+    /// sum of the costs of the legs, including the node cost multipliers. Afterwards, the node states are the ones of the last leg.
+    /// Returns None if one of the legs can not be found.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// let (path, cost) = graph.search_via(p1, &[p0], p2).unwrap();
+    /// assert_eq!(path, [p1, p0, p1, p2]);
+    /// assert_eq!(cost, 3.0);
+    /// ```
+    pub fn search_via(
+        &mut self,
+        start_index: usize,
+        waypoints: &[usize],
+        destination_index: usize,
+    ) -> Option<(Vec<usize>, f32)> {
+        let mut stops = vec![start_index];
+        stops.extend_from_slice(waypoints);
+        stops.push(destination_index);
+
+        let mut path = vec![start_index];
+        let mut cost = 0.0;
+        for leg in stops.windows(2) {
+            let leg_path = self.search_graph(leg[0], leg[1])?;
+            cost += self.cost_to(leg[1])?;
+            path.extend_from_slice(&leg_path[1..]);
+        }
+        Some((path, cost))
+    }
+
+    /// Repairs a path after one of its edges got blocked, for example by a collapsing bridge. The path is kept up to the
+    /// node before the blocked edge, and only the rest gets searched again from there like in [`NavGraph::search_graph`],
+    /// without using the blocked edge. This is cheaper than a search from the start, especially if the agent has
+    /// already walked most of the path, but the result may not be the shortest path from the start any more.
+    /// The edge does not need to be disconnected, it is avoided in any case. The node states are the ones of
+    /// the new search afterwards. If the blocked edge is not part of the path, the path is returned unchanged.
+    /// Returns None if the path is empty or the destination can not be reached without the blocked edge.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// let p3 = graph.add_node([1.5, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p1, p3).unwrap();
+    /// graph.connect_nodes(p3, p2).unwrap();
+    ///
+    /// let path = graph.search_graph(p0, p2).unwrap();
+    /// assert_eq!(graph.reroute_avoiding_edge(&path, (p2, p1)).unwrap(), [p0, p1, p3, p2]);
+    /// ```
+    pub fn reroute_avoiding_edge(
+        &mut self,
+        path: &[usize],
+        blocked_edge: (usize, usize),
+    ) -> Option<Vec<usize>> {
+        let destination_index = *path.last()?;
+        let blocked_key = edge_key(blocked_edge.0, blocked_edge.1);
+        let Some(position) = path
+            .windows(2)
+            .position(|pair| edge_key(pair[0], pair[1]) == blocked_key)
+        else {
+            return Some(path.to_vec());
+        };
+
+        let detour =
+            self.search_graph_with_entry_cost(&[path[position]], destination_index, |from, to| {
+                (edge_key(from, to) != blocked_key).then_some(0.0)
+            })?;
+        let mut result = path[..position].to_vec();
+        result.extend_from_slice(&detour);
+        Some(result)
+    }
+
+    /// Plans a tour from the start node that visits all waypoints in an order chosen by the nearest neighbor heuristic:
+    /// the next waypoint is always the remaining one that is cheapest to reach from the current one. The costs
+    /// are computed with [`NavGraph::dijkstra_from`] from the start and from every waypoint reached, and the legs are searched
+    /// and concatenated with [`NavGraph::search_via`]. The tour ends at the last waypoint visited. This is heuristic,
+    /// the tour is usually reasonable but may be considerably more expensive than the optimal one.
+    /// Returns None if one of the waypoints can not be reached.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// let (path, cost) = graph.plan_tour(p0, &[p2, p1]).unwrap();
+    /// assert_eq!(path, [p0, p1, p2]);
+    /// assert_eq!(cost, 2.0);
+    /// ```
+    pub fn plan_tour(
+        &mut self,
+        start_index: usize,
+        waypoints: &[usize],
+    ) -> Option<(Vec<usize>, f32)> {
+        let mut remaining = waypoints.to_vec();
+        let mut order = Vec::with_capacity(waypoints.len());
+        let mut current = start_index;
+        while !remaining.is_empty() {
+            let costs = self.dijkstra_from(current);
+            let (position, cost) = remaining
+                .iter()
+                .enumerate()
+                .map(|(position, waypoint)| (position, costs[*waypoint]))
+                .min_by_key(|(_, cost)| OrderedF32(cost.unwrap_or(f32::INFINITY)))?;
+            cost?;
+            current = remaining.remove(position);
+            order.push(current);
+        }
+
+        match order.split_last() {
+            Some((destination, via)) => self.search_via(start_index, via, *destination),
+            None => self.search_via(start_index, &[], start_index),
+        }
+    }
+
+    /// Searches a path between two arbitrary points, for callers that do not want to deal with node handles.
+    /// Both points are snapped to their nearest nodes within the snap radius with
+    /// [`NavGraph::find_nearest_node_with_radius`], and the path between these nodes is searched like in
+    /// [`NavGraph::search_graph`]. The returned polyline starts at the start point and ends at the destination point,
+    /// so the stubs between the points and their nodes are included, unless a point coincides with its node.
+    /// Returns None if one of the points has no node within the radius or if there is no path.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let path = graph.path_between_points([-0.1, 0.0], [1.0, 0.0], 0.2).unwrap();
+    /// assert_eq!(path, [[-0.1, 0.0], [0.0, 0.0], [1.0, 0.0]]);
+    /// ```
+    pub fn path_between_points(
+        &mut self,
+        start_pos: [f32; 2],
+        dest_pos: [f32; 2],
+        snap_radius: f32,
+    ) -> Option<Vec<[f32; 2]>> {
+        let start_index = self.find_nearest_node_with_radius(start_pos, snap_radius)?;
+        let destination_index = self.find_nearest_node_with_radius(dest_pos, snap_radius)?;
+        let path = self.search_graph(start_index, destination_index)?;
+
+        let mut positions = Vec::with_capacity(path.len() + 2);
+        if self.nodes[start_index].position != Vec2::from(start_pos) {
+            positions.push(start_pos);
+        }
+        positions.extend(
+            path.iter()
+                .map(|node| <[f32; 2]>::from(self.nodes[*node].position)),
+        );
+        if self.nodes[destination_index].position != Vec2::from(dest_pos) {
+            positions.push(dest_pos);
+        }
+        Some(positions)
+    }
+
+    /// Does the search like [`NavGraph::search_graph`], but treats the indicated nodes as impassable
+    /// for this single query. This may be used to route around a danger zone without
+    /// permanently changing the graph.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// let p3 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p0, p3).unwrap();
+    /// graph.connect_nodes(p3, p2).unwrap();
+    ///
+    /// let result = graph.search_graph_avoiding(p0, p2, &[p1]);
+    /// assert_eq!(result.unwrap(), [p0, p3, p2]);
+    /// ```
+    pub fn search_graph_avoiding(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+        avoid: &[usize],
+    ) -> Option<Vec<usize>> {
+        let options = SearchOptions {
+            avoid,
+            ..SearchOptions::default()
+        };
+        self.search_graph_with_options(start_index, destination_index, &options)
+            .into_path()
+    }
+
+    /// Does the search like [`NavGraph::search_graph`], but adds a danger cost to the cost of
+    /// a path whenever it enters a node. The search then prefers safer routes, but may still
+    /// traverse dangerous nodes if the detour gets too long. The danger values are indexed by
+    /// the node handles; missing entries count as 0.0. Negative danger values break the
+    /// admissibility of the heuristic.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// let p3 = graph.add_node([1.0, 0.5]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p0, p3).unwrap();
+    /// graph.connect_nodes(p3, p2).unwrap();
+    ///
+    /// let result = graph.search_graph_with_danger(p0, p2, &[0.0, 1.0, 0.0, 0.0]);
+    /// assert_eq!(result.unwrap(), [p0, p3, p2]);
+    /// ```
+    pub fn search_graph_with_danger(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+        danger: &[f32],
+    ) -> Option<Vec<usize>> {
+        let options = SearchOptions {
+            danger,
+            ..SearchOptions::default()
+        };
+        self.search_graph_with_options(start_index, destination_index, &options)
+            .into_path()
+    }
+
+    /// Does the search like [`NavGraph::search_graph`], but takes the heights of the nodes set with
+    /// [`NavGraph::set_node_height`] into account. The length of an edge is then the length in space,
+    /// which is the square root of the squared distance in the plane plus the squared height difference,
+    /// and the node cost multipliers apply to that length. Climbing and descending cost the same.
+    /// The heuristic stays the distance in the plane, which is still a lower bound of the cost.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// let p3 = graph.add_node([1.0, 0.5]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p0, p3).unwrap();
+    /// graph.connect_nodes(p3, p2).unwrap();
+    /// graph.set_node_height(p1, 3.0).unwrap();
+    ///
+    /// assert_eq!(graph.search_graph(p0, p2).unwrap(), [p0, p1, p2]);
+    /// assert_eq!(graph.search_graph_with_elevation(p0, p2).unwrap(), [p0, p3, p2]);
+    /// ```
+    pub fn search_graph_with_elevation(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+    ) -> Option<Vec<usize>> {
+        let climb_costs: Vec<Vec<(usize, f32)>> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                node.connections
+                    .iter()
+                    .map(|(partner, distance)| {
+                        let climb = self.nodes[*partner].height - node.height;
+                        let length = (distance * distance + climb * climb).sqrt();
+                        let extra = self.traversal_cost(index, *partner, length)
+                            - self.traversal_cost(index, *partner, *distance);
+                        (*partner, extra)
+                    })
+                    .collect()
+            })
+            .collect();
+        // The climb is charged as an additional cost on top of the distance in the plane.
+        self.search_graph_with_entry_cost(&[start_index], destination_index, |from, to| {
+            climb_costs[from]
+                .iter()
+                .find(|(partner, _)| *partner == to)
+                .map(|(_, extra)| *extra)
+        })
+    }
+
+    /// Performs the same search as [`NavGraph::search_graph`], but only traverses edges whose tags
+    /// intersect the allowed mask. This way an amphibious unit may use water edges while a ground unit can not.
+    /// Edges that have not been tagged carry [`ALL_TAGS`] and may always be used.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// const LAND: u32 = 1;
+    /// const WATER: u32 = 2;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.set_edge_tags(p0, p1, WATER).unwrap();
+    ///
+    /// assert_eq!(graph.search_graph_with_mask(p0, p1, LAND), None);
+    /// assert_eq!(graph.search_graph_with_mask(p0, p1, LAND | WATER).unwrap(), [p0, p1]);
+    /// ```
+    pub fn search_graph_with_mask(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+        allowed_mask: u32,
+    ) -> Option<Vec<usize>> {
+        let options = SearchOptions {
+            allowed_mask: Some(allowed_mask),
+            ..SearchOptions::default()
+        };
+        self.search_graph_with_options(start_index, destination_index, &options)
+            .into_path()
+    }
+
+    /// Performs the same search as [`NavGraph::search_graph`], but with a custom heuristic instead of the
+    /// straight-line distance. The heuristic gets invoked with the position of a node and the position of the
+    /// destination, and estimates the remaining cost. It has to be admissible, which means it never overestimates
+    /// the actual cost, otherwise the path found may not be the shortest one.
+    /// To find such mistakes, the check of [`NavGraph::set_heuristic_check`] may be enabled.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// // The maximum norm never exceeds the Euclidean distance.
+    /// let max_norm = |a: [f32; 2], b: [f32; 2]| (a[0] - b[0]).abs().max((a[1] - b[1]).abs());
+    /// let result = graph.search_graph_with_heuristic(p0, p2, max_norm);
+    /// assert_eq!(result.unwrap(), [p0, p1, p2]);
+    /// ```
+    pub fn search_graph_with_heuristic<H>(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+        heuristic: H,
+    ) -> Option<Vec<usize>>
+    where
+        H: Fn([f32; 2], [f32; 2]) -> f32,
+    {
+        let dest_point: [f32; 2] = self.nodes[destination_index].position.into();
+        let node_heuristic = |node: usize| heuristic(self.nodes[node].position.into(), dest_point);
+
+        let mut scratch = std::mem::take(&mut self.last_search);
+        let result = self.search_in_scratch_with_heuristic(
+            &[start_index],
+            destination_index,
+            &mut scratch,
+            |_, _| Some(0.0),
+            node_heuristic,
+        );
+        if cfg!(debug_assertions) && self.check_heuristic && result.is_some() {
+            self.assert_admissible(&scratch, destination_index, node_heuristic);
+        }
+        self.last_search = scratch;
+        self.last_query = Some((start_index, destination_index));
+        result
+    }
+
+    /// Gets the generation of the graph, which changes with every modification of the nodes, the links
+    /// or their attributes. A structure derived from the graph may store the generation it was built at,
+    /// and it is outdated once the generation differs. Generations only ever increase, and they are
+    /// unique over all graphs, so a structure built for another graph is never taken as current.
+    /// Searches and reservations do not change the generation.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let before = graph.generation();
+    /// graph.add_node([0.0, 0.0]);
+    /// assert!(graph.generation() > before);
+    /// ```
+    pub fn generation(&self) -> u64 {
+        self.generation
+    }
+
+    /// Enables or disables the admissibility check of [`NavGraph::search_graph_with_heuristic`], which is
+    /// only performed in debug builds. After a successful search, the check compares the heuristic of
+    /// every closed node with the actual remaining cost, computed with the Dijkstra algorithm, and panics
+    /// if the heuristic overestimates it. This is expensive and meant for debugging custom heuristics.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// graph.set_heuristic_check(true);
+    /// ```
+    pub fn set_heuristic_check(&mut self, enabled: bool) {
+        self.check_heuristic = enabled;
+    }
+
+    /// Sets the weight the straight-line heuristic gets multiplied with in all following searches like
+    /// [`NavGraph::search_graph`], the default is 1.0. This is a global tradeoff between quality and speed:
+    /// a weight w above 1.0 makes the search greedier, so it expands fewer nodes, but the cost of the found path
+    /// may exceed the optimal cost by up to the factor w. The admissible weight 1.0 always finds the shortest path.
+    /// Searches with their own heuristic, the step by step searches and frozen graphs are not affected.
+    ///
+    /// # Panics
+    /// Panics if the weight is negative, infinite or NaN.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// graph.set_heuristic_weight(1.5);
+    /// assert_eq!(graph.search_graph(p0, p1), Some(vec![p0, p1]));
+    /// ```
+    pub fn set_heuristic_weight(&mut self, weight: f32) {
+        assert_valid_heuristic_weight(weight);
+        self.heuristic_weight = weight;
+        self.path_cache.clear();
+    }
+
+    /// Sets the relative amount by which a new route to a visited node has to be cheaper than the known one,
+    /// so that the search replaces the ancestor of the node. The default is 1e-6. Routes of nearly the same cost,
+    /// whose costs only differ by floating point noise, then keep the ancestor found first instead of flip-flopping
+    /// between them, so the choice is stable and redundant updates of the open list are avoided. In exchange,
+    /// an improvement smaller than the epsilon is missed, so the cost of the found path may exceed the optimal cost
+    /// by up to that fraction per visited node on it. An epsilon of 0.0 accepts every improvement.
+    /// This applies to the searches sharing the A\* core of [`NavGraph::search_graph`], and a frozen graph keeps the epsilon.
+    ///
+    /// # Panics
+    /// Panics if the epsilon is negative, NaN or not below 1.0, as the search would then reject all improvements.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// graph.set_improvement_epsilon(0.0);
+    /// assert_eq!(graph.search_graph(p0, p1), Some(vec![p0, p1]));
+    /// ```
+    pub fn set_improvement_epsilon(&mut self, epsilon: f32) {
+        assert!(
+            (0.0..1.0).contains(&epsilon),
+            "The improvement epsilon has to be at least 0.0 and below 1.0."
+        );
+        self.improvement_epsilon = epsilon;
+        self.path_cache.clear();
+    }
+
+    /// Sets the number of nodes from which on the searches keep their open nodes in a binary heap, the default is 32.
+    /// The best open node is found in logarithmic time then, while smaller graphs scan the open list linearly,
+    /// which has less overhead for the few nodes of a typical waypoint graph. Both strategies find the same paths.
+    /// The step by step searches like [`NavGraph::begin_search`] always use the open list.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// graph.set_heap_threshold(0);
+    /// assert_eq!(graph.search_graph(p0, p1), Some(vec![p0, p1]));
+    /// ```
+    pub fn set_heap_threshold(&mut self, node_count: usize) {
+        self.heap_threshold = node_count;
+    }
+
+    /// Checks that the straight-line heuristic of the searches is admissible, which requires that no edge
+    /// is cheaper than the distance between its end nodes. Otherwise the heuristic overestimates and the
+    /// found paths may not be the shortest ones. The effective cost of an edge is checked, so this catches
+    /// stored costs that are outdated after [`NavGraph::set_node_position`] as well as cost multipliers below 1.0.
+    ///
+    /// # Error
+    /// Returns the under-priced edges as pairs of node handles, the smaller handle first, in ascending order.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// assert!(graph.check_heuristic_admissible().is_ok());
+    ///
+    /// graph.set_node_cost_multiplier(p1, 0.5).unwrap();
+    /// assert_eq!(graph.check_heuristic_admissible(), Err(vec![(p0, p1)]));
+    /// ```
+    pub fn check_heuristic_admissible(&self) -> Result<(), Vec<(usize, usize)>> {
+        let mut violations = Vec::new();
+        for (index, node) in self.nodes.iter().enumerate() {
+            for (partner, distance) in node.connections.iter() {
+                if *partner < index {
+                    continue;
+                }
+                let cost = self.traversal_cost(index, *partner, *distance);
+                let straight = node.position.dist_to(&self.nodes[*partner].position);
+                // The tolerance accounts for rounding errors of the stored distances.
+                if cost < straight - 0.00001 * straight.max(1.0) {
+                    violations.push((index, *partner));
+                }
+            }
+        }
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            violations.sort_unstable();
+            Err(violations)
+        }
+    }
+
+    /// Panics if the heuristic overestimates the remaining cost of a node closed in the search.
+    fn assert_admissible<H>(&self, scratch: &SearchScratch, destination_index: usize, heuristic: H)
+    where
+        H: Fn(usize) -> f32,
+    {
+        let remaining = self.dijkstra_from(destination_index);
+        for (node, state) in scratch.states.iter().enumerate() {
+            if !matches!(state, NodeState::Closed | NodeState::Solution) {
+                continue;
+            }
+            let Some(actual) = remaining[node] else {
+                continue;
+            };
+            let estimate = heuristic(node);
+            assert!(
+                estimate <= actual + 0.0001 * actual.max(1.0),
+                "The heuristic is not admissible: it estimates {estimate} for node {node}, but the remaining cost is {actual}."
+            );
+        }
+    }
+
+    /// Searches the cheapest path from any of the start nodes to the destination node, for example
+    /// to find out which of several guards reaches a position first. All start nodes begin the search
+    /// with no cost. The result is the chosen start node and the path from it, which begins with that start node.
+    /// If none of the start nodes can reach the destination, None is returned.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([3.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// let (start, path) = graph.search_graph_multi_start(&[p0, p2], p1).unwrap();
+    /// assert_eq!(start, p0);
+    /// assert_eq!(path, [p0, p1]);
+    /// ```
+    pub fn search_graph_multi_start(
+        &mut self,
+        start_indices: &[usize],
+        destination_index: usize,
+    ) -> Option<(usize, Vec<usize>)> {
+        let path =
+            self.search_graph_with_entry_cost(start_indices, destination_index, |_, _| Some(0.0))?;
+        Some((path[0], path))
+    }
+
+    /// Searches a path with a bounded open list, which is a simplified memory bounded A\* (SMA\*) for
+    /// memory constrained targets. Whenever the open list exceeds the maximum size, the open node with the
+    /// highest f value gets forgotten and its f value is remembered on its parent. A parent without any
+    /// remaining open successor is put back on the open list with the lowest remembered f value, so
+    /// that the forgotten part may be regenerated later. This trades optimality for bounded memory:
+    /// the path found is valid, but not necessarily the shortest one.
+    /// The node states are kept for visualization as with [`NavGraph::search_graph`].
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p0, p2).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// assert_eq!(graph.search_graph_memory_bounded(p0, p2, 1).unwrap(), [p0, p2]);
+    /// ```
+    pub fn search_graph_memory_bounded(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+        max_open: usize,
+    ) -> Option<Vec<usize>> {
+        let mut scratch = std::mem::take(&mut self.last_search);
+        let result = self.search_memory_bounded_in_scratch(
+            start_index,
+            destination_index,
+            max_open,
+            &mut scratch,
+        );
+        self.last_search = scratch;
+        self.last_query = Some((start_index, destination_index));
+        result
+    }
+
+    /// The search operation of [`NavGraph::search_graph_memory_bounded`].
+    fn search_memory_bounded_in_scratch(
+        &self,
+        start_index: usize,
+        destination_index: usize,
+        max_open: usize,
+        scratch: &mut SearchScratch,
+    ) -> Option<Vec<usize>> {
+        let dest_point = self.nodes[destination_index].position;
+        let heuristic = |node: usize| self.nodes[node].position.dist_to(&dest_point);
+        let mut forgotten_f = vec![f32::INFINITY; self.nodes.len()];
+        let mut reopened = vec![false; self.nodes.len()];
+        self.seed_search(&[start_index], scratch, &heuristic, false);
+
+        loop {
+            match self.search_step(destination_index, scratch, &|_, _| Some(0.0), &heuristic) {
+                SearchStatus::Running => {}
+                SearchStatus::Found(path) => return Some(path),
+                SearchStatus::NoPath => {
+                    // Before giving up, the forgotten part of the search gets regenerated.
+                    let parent = (0..self.nodes.len())
+                        .filter(|node| forgotten_f[*node] < f32::INFINITY)
+                        .min_by_key(|node| OrderedF32(forgotten_f[*node]))?;
+                    Self::reopen(parent, scratch, &mut forgotten_f, &mut reopened);
+                    continue;
+                }
+            }
+
+            // Every parent put back replaces a deeper node, so the pruning terminates.
+            // Parents that have been put back only lose their place on the open list, but stay closed,
+            // so that the ancestors of the closed nodes always form a tree.
+            while scratch.open_list.len() > max_open.max(1) {
+                let (worst_index, worst) = scratch
+                    .open_list
+                    .iter()
+                    .enumerate()
+                    .max_by_key(|(_, node)| OrderedF32(scratch.f_values[**node]))
+                    .map(|(index, node)| (index, *node))
+                    .unwrap();
+                scratch.open_list.swap_remove(worst_index);
+                if reopened[worst] {
+                    // Its forgotten successors have not been regenerated yet.
+                    scratch.states[worst] = NodeState::Closed;
+                    forgotten_f[worst] = forgotten_f[worst].min(scratch.f_values[worst]);
+                } else {
+                    scratch.states[worst] = NodeState::Clear;
+                }
+
+                let parent = scratch.ancestors[worst];
+                if parent == worst {
+                    continue;
+                }
+                forgotten_f[parent] = forgotten_f[parent].min(scratch.f_values[worst]);
+                if scratch.states[parent] == NodeState::Closed
+                    && scratch
+                        .open_list
+                        .iter()
+                        .all(|node| scratch.ancestors[*node] != parent)
+                {
+                    Self::reopen(parent, scratch, &mut forgotten_f, &mut reopened);
+                }
+            }
+        }
+    }
+
+    /// Puts a closed node back on the open list with the lowest f value of its forgotten successors.
+    fn reopen(
+        node: usize,
+        scratch: &mut SearchScratch,
+        forgotten_f: &mut [f32],
+        reopened: &mut [bool],
+    ) {
+        scratch.states[node] = NodeState::Visited;
+        scratch.f_values[node] = scratch.f_values[node].max(forgotten_f[node]);
+        forgotten_f[node] = f32::INFINITY;
+        reopened[node] = true;
+        scratch.enter_open(node, true);
+    }
+
+    /// Reserves a node for the indicated time step, so that the cooperative searches of other agents
+    /// avoid it at that time. See [`NavGraph::search_graph_cooperative`].
+    ///
+    /// # Error
+    /// Returns an error if the node does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// graph.reserve_node(p0, 3).unwrap();
+    /// assert!(graph.is_reserved(p0, 3));
+    /// assert!(!graph.is_reserved(p0, 4));
+    /// ```
+    pub fn reserve_node(&mut self, node: usize, time_step: usize) -> Result<(), ConnectionError> {
+        self.node(node)?;
+        self.reservations.insert((node, time_step));
+        Ok(())
+    }
+
+    /// Reserves the nodes of a path as returned by [`NavGraph::search_graph_cooperative`], where the
+    /// first node is occupied at the start time and every further node one time step later.
+    ///
+    /// # Error
+    /// Returns an error if a node does not exist, in which case nothing is reserved.
+    pub fn reserve_path(
+        &mut self,
+        path: &[usize],
+        start_time: usize,
+    ) -> Result<(), ConnectionError> {
+        for node in path.iter() {
+            self.node(*node)?;
+        }
+        for (offset, node) in path.iter().enumerate() {
+            self.reservations.insert((*node, start_time + offset));
+        }
+        Ok(())
+    }
+
+    /// Checks if the node is reserved for the indicated time step.
+    pub fn is_reserved(&self, node: usize, time_step: usize) -> bool {
+        self.reservations.contains(&(node, time_step))
+    }
+
+    /// Removes all reservations, for example before planning the next window of a cooperative search.
+    pub fn clear_reservations(&mut self) {
+        self.reservations.clear();
+    }
+
+    /// Searches a path for one agent of a group in space and time, which is the cooperative A\* used by
+    /// windowed hierarchical cooperative A\* (WHCA\*) for swarms. The agents are planned one after the other,
+    /// every agent reserves its path with [`NavGraph::reserve_path`], and the later agents avoid the reserved nodes
+    /// at the reserved times. Every step takes one time step and is either a move along a link or waiting at a node.
+    ///
+    /// The returned path contains the node of the agent for every time step, starting with the start node at the
+    /// start time, so waiting shows up as a repeated node. The search minimizes the arrival time first and the cost
+    /// of the moves second. The destination is only accepted at a time after which it is never reserved, so that
+    /// the agent may stay there. Only nodes are reserved, so two agents may still swap their nodes along a link.
+    /// Returns None if the destination can not be reached. The node states of the graph are left untouched.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// graph.reserve_node(p1, 1).unwrap();
+    /// assert_eq!(graph.search_graph_cooperative(p0, p1, 0).unwrap(), [p0, p0, p1]);
+    /// ```
+    pub fn search_graph_cooperative(
+        &self,
+        start_index: usize,
+        destination_index: usize,
+        start_time: usize,
+    ) -> Option<Vec<usize>> {
+        // The number of moves ignoring all reservations is a lower bound of the remaining time.
+        let mut hops: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        hops[destination_index] = Some(0);
+        let mut todo = VecDeque::from([destination_index]);
+        while let Some(node) = todo.pop_front() {
+            let next_hops = hops[node].map(|count| count + 1);
+            for (partner, _) in self.nodes[node].connections.iter() {
+                if hops[*partner].is_none() && self.edge_enabled_unchecked(node, *partner) {
+                    hops[*partner] = next_hops;
+                    todo.push_back(*partner);
+                }
+            }
+        }
+        let start_hops = hops[start_index]?;
+
+        // Waiting longer than it takes for all reservations to pass is never needed.
+        let last_reservation = self.reservations.iter().map(|(_, time)| *time).max();
+        let time_limit = last_reservation.unwrap_or(start_time).max(start_time) + self.nodes.len();
+        let free_after = |node: usize, time: usize| {
+            !self
+                .reservations
+                .iter()
+                .any(|(reserved, reserved_time)| *reserved == node && *reserved_time >= time)
+        };
+
+        let mut best: HashMap<(usize, usize), (f32, usize)> = HashMap::new();
+        best.insert((start_index, start_time), (0.0, start_index));
+        let mut closed: HashSet<(usize, usize)> = HashSet::new();
+        let mut heap = BinaryHeap::from([TimedEntry {
+            arrival: start_time + start_hops,
+            cost: 0.0,
+            node: start_index,
+            time: start_time,
+        }]);
+        while let Some(TimedEntry {
+            cost, node, time, ..
+        }) = heap.pop()
+        {
+            if !closed.insert((node, time)) {
+                continue;
+            }
+            if node == destination_index && free_after(node, time) {
+                let mut path = vec![node];
+                let mut scan = (node, time);
+                while scan.1 > start_time {
+                    scan = (best[&scan].1, scan.1 - 1);
+                    path.push(scan.0);
+                }
+                path.reverse();
+                return Some(path);
+            }
+            if time >= time_limit {
+                continue;
+            }
+
+            let next_time = time + 1;
+            let wait = std::iter::once((node, 0.0));
+            let moves = self.nodes[node]
+                .connections
+                .iter()
+                .filter(|(partner, _)| self.edge_enabled_unchecked(node, *partner))
+                .map(|(partner, distance)| {
+                    (*partner, self.traversal_cost(node, *partner, *distance))
+                });
+            for (next, step_cost) in moves.chain(wait) {
+                let Some(next_hops) = hops[next] else {
+                    continue;
+                };
+                if self.is_reserved(next, next_time) {
+                    continue;
+                }
+                let new_cost = cost + step_cost;
+                if best
+                    .get(&(next, next_time))
+                    .is_some_and(|(known_cost, _)| *known_cost <= new_cost)
+                {
+                    continue;
+                }
+                best.insert((next, next_time), (new_cost, node));
+                heap.push(TimedEntry {
+                    arrival: next_time + next_hops,
+                    cost: new_cost,
+                    node: next,
+                    time: next_time,
+                });
+            }
+        }
+        None
+    }
+
+    /// Computes the k shortest loopless paths from the start to the destination node with Yen's
+    /// algorithm. This may be used to offer the best few alternative routes. The paths are returned
+    /// with their costs in ascending cost order. Fewer than k paths are returned if there are no more.
+    ///
+    /// Every further path requires a spur search for each node of the previous path, each of them
+    /// is an A\* search with some nodes and edges temporarily removed.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 1.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p0, p2).unwrap();
+    ///
+    /// let paths = graph.k_shortest_paths(p0, p2, 3);
+    /// assert_eq!(paths[0], (vec![p0, p2], 2.0));
+    /// assert_eq!(paths[1].0, [p0, p1, p2]);
+    /// assert_eq!(paths.len(), 2);
+    /// ```
+    pub fn k_shortest_paths(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+        k: usize,
+    ) -> Vec<(Vec<usize>, f32)> {
+        let mut result: Vec<(Vec<usize>, f32)> = Vec::new();
+        if k == 0 {
+            return result;
+        }
+        let Some(first_path) = self.search_graph(start_index, destination_index) else {
+            return result;
+        };
+        let first_cost = self.search_cost_of_path(&first_path);
+        result.push((first_path, first_cost));
+        let mut candidates: Vec<(Vec<usize>, f32)> = Vec::new();
+
+        while result.len() < k {
+            let previous = result.last().unwrap().0.clone();
+            for spur_index in 0..previous.len() - 1 {
+                let spur_node = previous[spur_index];
+                let root_path = &previous[..=spur_index];
+
+                // Edges that continue the root path like an already found path are removed,
+                // as are the nodes of the root path, to keep the path loopless.
+                let removed_edges: Vec<(usize, usize)> = result
+                    .iter()
+                    .filter(|(path, _)| path.len() > spur_index + 1 && path.starts_with(root_path))
+                    .map(|(path, _)| (path[spur_index], path[spur_index + 1]))
+                    .collect();
+                let removed_nodes = &root_path[..spur_index];
+
+                let spur_path = self.search_graph_with_entry_cost(
+                    &[spur_node],
+                    destination_index,
+                    |from, to| {
+                        let removed = removed_nodes.contains(&to)
+                            || removed_edges.contains(&(from, to))
+                            || removed_edges.contains(&(to, from));
+                        (!removed).then_some(0.0)
+                    },
+                );
+                let Some(spur_path) = spur_path else {
+                    continue;
+                };
+
+                let mut total_path = root_path[..spur_index].to_vec();
+                total_path.extend(spur_path);
+                if candidates.iter().any(|(path, _)| *path == total_path) {
+                    continue;
+                }
+                let cost = self.search_cost_of_path(&total_path);
+                candidates.push((total_path, cost));
+            }
+
+            let Some(best) = candidates
+                .iter()
+                .enumerate()
+                .min_by_key(|(_, (_, cost))| OrderedF32(*cost))
+                .map(|(index, _)| index)
+            else {
+                break;
+            };
+            result.push(candidates.swap_remove(best));
+        }
+        result
+    }
+
+    /// Computes the cost of a valid path as the search sees it, including the node cost multipliers.
+    fn search_cost_of_path(&self, path: &[usize]) -> f32 {
+        path.windows(2)
+            .map(|pair| {
+                let distance = self.get_edge(pair[0], pair[1]).unwrap();
+                self.traversal_cost(pair[0], pair[1], distance)
+            })
+            .sum()
+    }
+
+    /// Runs the search operation in the scratch of the graph, which is kept for visualization purposes.
+    fn search_graph_with_entry_cost<F>(
+        &mut self,
+        start_indices: &[usize],
+        destination_index: usize,
+        entry_cost: F,
+    ) -> Option<Vec<usize>>
+    where
+        F: Fn(usize, usize) -> Option<f32>,
+    {
+        let mut scratch = std::mem::take(&mut self.last_search);
+        let result =
+            self.search_in_scratch(start_indices, destination_index, &mut scratch, entry_cost);
+        self.last_search = scratch;
+        self.last_query = match start_indices {
+            [start_index] => Some((*start_index, destination_index)),
+            _ => None,
+        };
+        result
+    }
+
+    /// Does the same search as [`NavGraph::search_graph`], but keeps all the mutable search state in
+    /// the handed over scratch instead of the graph. The graph is therefore only borrowed immutably,
+    /// and several searches on the same graph may run in parallel on different threads, each with its
+    /// own scratch. The node states of the graph itself remain untouched, they can be queried
+    /// from the scratch instead.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{NavGraph, SearchScratch};
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let mut scratch = SearchScratch::new();
+    /// let result = graph.search_graph_with_scratch(p0, p1, &mut scratch);
+    /// assert_eq!(result.unwrap(), [p0, p1]);
+    /// ```
+    pub fn search_graph_with_scratch(
+        &self,
+        start_index: usize,
+        destination_index: usize,
+        scratch: &mut SearchScratch,
+    ) -> Option<Vec<usize>> {
+        self.search_in_scratch(&[start_index], destination_index, scratch, |_, _| Some(0.0))
+    }
+
+    /// Searches the shortest path in the indicated scratch like [`NavGraph::search_graph_with_scratch`], but
+    /// does not mark the nodes of the path as solution in the scratch. This saves the marking for
+    /// high throughput pathfinding, where only the handles of the path are used.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{NavGraph, NodeState, SearchScratch};
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let mut scratch = SearchScratch::new();
+    /// assert_eq!(graph.find_path_indices_only(p0, p1, &mut scratch).unwrap(), [p0, p1]);
+    /// assert_eq!(scratch.node_state(p1), Some(&NodeState::Closed));
+    /// ```
+    pub fn find_path_indices_only(
+        &self,
+        start_index: usize,
+        destination_index: usize,
+        scratch: &mut SearchScratch,
+    ) -> Option<Vec<usize>> {
+        scratch.skip_solution_marks = true;
+        let result = self.search_graph_with_scratch(start_index, destination_index, scratch);
+        scratch.skip_solution_marks = false;
+        result
+    }
+
+    /// Finds the shortest path like [`NavGraph::search_graph`], but leaves the node states of the graph untouched.
+    /// It only needs an immutable graph and is meant for users who want the path without
+    /// the visualization state. As it allocates a new scratch on every call, [`NavGraph::search_graph_with_scratch`]
+    /// is preferable for many searches.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// assert_eq!(graph.find_path(p0, p1).unwrap(), [p0, p1]);
+    /// ```
+    pub fn find_path(&self, start_index: usize, destination_index: usize) -> Option<Vec<usize>> {
+        self.search_graph_with_scratch(start_index, destination_index, &mut SearchScratch::new())
+    }
+
+    /// Searches the shortest path and returns a context that owns all the state of the search, while the graph
+    /// is only borrowed. This separates the map from the searches performed on it, so several contexts of one
+    /// graph may coexist and be inspected independently.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{NavGraph, NodeState};
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let context = graph.search(p0, p1);
+    /// assert_eq!(context.path().unwrap(), [p0, p1]);
+    /// assert_eq!(context.expanded(), 2);
+    /// assert_eq!(context.node_state(p1), Some(&NodeState::Solution));
+    /// ```
+    pub fn search(&self, start_index: usize, destination_index: usize) -> SearchContext<'_> {
+        let mut scratch = SearchScratch::new();
+        let path = self.search_graph_with_scratch(start_index, destination_index, &mut scratch);
+        SearchContext {
+            graph: self,
+            scratch,
+            path,
+        }
+    }
+
+    /// Searches the paths for many pairs of start and destination nodes at once, for example for all agents
+    /// of an AI system. One scratch gets reused for all searches, which avoids an allocation per search.
+    /// The node states of the graph are left untouched. The results are in the order of the queries.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let results = graph.search_batch(&[(p0, p1), (p0, p2)]);
+    /// assert_eq!(results, [Some(vec![p0, p1]), None]);
+    /// ```
+    pub fn search_batch(&self, queries: &[(usize, usize)]) -> Vec<Option<Vec<usize>>> {
+        let mut scratch = SearchScratch::new();
+        queries
+            .iter()
+            .map(|(start_index, destination_index)| {
+                self.search_graph_with_scratch(*start_index, *destination_index, &mut scratch)
+            })
+            .collect()
+    }
+
+    /// Counts how often every link appears in the paths of many searches, which shows the heavy traffic
+    /// routes, for example to pave the most used ones over time. The counts are in the order of
+    /// [`NavGraph::links_in_order`]. Queries without a path do not count.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// assert_eq!(graph.edge_usage(&[(p0, p2), (p1, p2)]), [1, 2]);
+    /// ```
+    pub fn edge_usage(&self, queries: &[(usize, usize)]) -> Vec<u32> {
+        let mut usage = vec![0; self.links.len()];
+        for path in self.search_batch(queries).iter().flatten() {
+            for pair in path.windows(2) {
+                if let Some(link) = self.get_link_index(pair[0], pair[1]) {
+                    usage[link] += 1;
+                }
+            }
+        }
+        usage
+    }
+
+    /// Does the search like [`NavGraph::search_graph`], but with the octile distance as heuristic, which is the
+    /// length of the shortest path on an open grid with straight and diagonal moves. It is larger than the straight-line
+    /// distance, so the search expands fewer nodes, and it stays admissible for graphs whose links are grid moves
+    /// with their lengths as costs, as built by [`NavGraph::from_walkability`] with any spacing and any
+    /// [`DiagonalMoves`]. On other graphs the path may not be the shortest one.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{DiagonalMoves, NavGraph};
+    /// let mut graph = NavGraph::from_walkability(3, 3, &[true; 9], 1.0, DiagonalMoves::NoCornerCutting);
+    /// assert_eq!(graph.search_grid_octile(0, 8).unwrap(), [0, 4, 8]);
+    /// ```
+    pub fn search_grid_octile(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+    ) -> Option<Vec<usize>> {
+        self.search_graph_with_heuristic(start_index, destination_index, |position, dest_point| {
+            let delta_x = (position[0] - dest_point[0]).abs();
+            let delta_y = (position[1] - dest_point[1]).abs();
+            delta_x.max(delta_y) + (std::f32::consts::SQRT_2 - 1.0) * delta_x.min(delta_y)
+        })
+    }
+
+    /// Selects the indicated number of landmarks for [`NavGraph::search_graph_alt`] and precomputes the
+    /// distances from each of them to all nodes with [`NavGraph::dijkstra_from`]. The landmarks are spread out
+    /// by farthest point selection: the first one is the node farthest from node 0, and every further one is the
+    /// node farthest from all landmarks selected so far, where nodes of components without a landmark come first.
+    /// Fewer landmarks are selected if the graph has fewer nodes. The cost is O(count · E log V) and the memory
+    /// O(count · V), so this is meant for static graphs that are searched often. Any modification of the graph
+    /// invalidates the landmarks, and they have to be selected again. Returns the selected landmarks.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// assert_eq!(graph.select_landmarks(2), [p2, p0]);
+    /// ```
+    pub fn select_landmarks(&mut self, count: usize) -> Vec<usize> {
+        let mut landmarks = Vec::new();
+        let mut distances = Vec::new();
+        // The smallest distance of every node to the selected landmarks, where None stands for unreachable.
+        let mut closest: Vec<Option<f32>> = vec![None; self.nodes.len()];
+        let mut is_landmark = vec![false; self.nodes.len()];
+        if !self.nodes.is_empty() {
+            // Only the nodes reachable from node 0 are candidates for the first landmark.
+            closest = self
+                .dijkstra_from(0)
+                .into_iter()
+                .map(|distance| Some(distance.unwrap_or(0.0)))
+                .collect();
+        }
+        while landmarks.len() < count.min(self.nodes.len()) {
+            let landmark = (0..self.nodes.len())
+                .filter(|node| !is_landmark[*node])
+                .max_by_key(|node| {
+                    (
+                        closest[*node].is_none(),
+                        OrderedF32(closest[*node].unwrap_or(0.0)),
+                        Reverse(*node),
+                    )
+                })
+                .unwrap();
+            let field = self.dijkstra_from(landmark);
+            for (best, distance) in closest.iter_mut().zip(field.iter()) {
+                if landmarks.is_empty() {
+                    *best = *distance;
+                } else if let Some(distance) = distance {
+                    *best = Some(best.map_or(*distance, |best| best.min(*distance)));
+                }
+            }
+            is_landmark[landmark] = true;
+            landmarks.push(landmark);
+            distances.push(field);
+        }
+        self.landmark_distances = distances;
+        self.landmark_generation = self.generation;
+        landmarks
+    }
+
+    /// Does the search like [`NavGraph::search_graph`], but with the ALT heuristic of the landmarks selected with
+    /// [`NavGraph::select_landmarks`]. By the triangle inequality, the remaining cost of a node is at least the
+    /// difference of the distances of the node and of the destination to any landmark. The heuristic takes the maximum
+    /// of these bounds and the straight-line distance, so it is admissible and at least as strong as the one of
+    /// [`NavGraph::search_graph`], and the search expands fewer nodes for the same shortest path. If no landmarks
+    /// are selected, or the graph has been modified since, the search falls back to the straight-line distance.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.select_landmarks(1);
+    /// assert_eq!(graph.search_graph_alt(p0, p2).unwrap(), [p0, p1, p2]);
+    /// ```
+    pub fn search_graph_alt(
+        &mut self,
+        start_index: usize,
+        destination_index: usize,
+    ) -> Option<Vec<usize>> {
+        let landmark_distances = if self.landmark_generation == self.generation {
+            std::mem::take(&mut self.landmark_distances)
+        } else {
+            Vec::new()
+        };
+        let dest_point = self.nodes[destination_index].position;
+        let nodes = &self.nodes;
+        let heuristic = |node: usize| {
+            landmark_distances
+                .iter()
+                .filter_map(|field| Some((field[node]? - field[destination_index]?).abs()))
+                .fold(nodes[node].position.dist_to(&dest_point), f32::max)
+        };
+
+        let mut scratch = std::mem::take(&mut self.last_search);
+        let result = self.search_in_scratch_with_heuristic(
+            &[start_index],
+            destination_index,
+            &mut scratch,
+            |_, _| Some(0.0),
+            heuristic,
+        );
+        if cfg!(debug_assertions) && self.check_heuristic && result.is_some() {
+            self.assert_admissible(&scratch, destination_index, heuristic);
+        }
+        self.last_search = scratch;
+        self.last_query = Some((start_index, destination_index));
+        if self.landmark_generation == self.generation {
+            self.landmark_distances = landmark_distances;
+        }
+        result
+    }
+
+    /// Searches a path with jump point search on a graph that represents a uniform grid, which is
+    /// a fast path for tile games. The grid has the indicated number of columns and rows, and the node
+    /// handle of the cell in column x and row y is y * cols + x, so the graph has to be built row by row.
+    /// The search runs on the grid representation directly, with straight moves of cost 1 and diagonal moves of
+    /// cost √2 between free cells. Diagonal moves may not cut corners of blocked cells.
+    /// As jump point search skips the symmetric paths of the uniform grid, it expands far fewer nodes than A\*.
+    /// The returned path contains every cell along the way.
+    ///
+    /// Returns None if the grid does not match the number of nodes, if the start or the destination
+    /// is blocked, or if there is no path.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// for y in 0..3 {
+    ///     for x in 0..3 {
+    ///         graph.add_node([x as f32, y as f32]);
+    ///     }
+    /// }
+    /// let blocked = [false, false, false, false, true, false, false, false, false];
+    /// let path = graph.search_grid_jps(3, 3, &blocked, 0, 8).unwrap();
+    /// assert_eq!(path.len(), 5);
+    /// ```
+    pub fn search_grid_jps(
+        &self,
+        cols: usize,
+        rows: usize,
+        blocked: &[bool],
+        start_index: usize,
+        destination_index: usize,
+    ) -> Option<Vec<usize>> {
+        self.jump_point_search(cols, rows, blocked, start_index, destination_index)
+            .0
+    }
+
+    /// Performs the jump point search and returns the path together with the number of expanded jump points.
+    fn jump_point_search(
+        &self,
+        cols: usize,
+        rows: usize,
+        blocked: &[bool],
+        start_index: usize,
+        destination_index: usize,
+    ) -> (Option<Vec<usize>>, usize) {
+        let cell_count = self.nodes.len();
+        if cols * rows != cell_count
+            || blocked.len() != cell_count
+            || start_index >= cell_count
+            || destination_index >= cell_count
+            || blocked[start_index]
+            || blocked[destination_index]
+        {
+            return (None, 0);
+        }
+        let grid = JpsGrid {
+            cols,
+            rows,
+            blocked,
+        };
+        let goal = grid.coordinates(destination_index);
+
+        let mut g_values = vec![f32::INFINITY; cell_count];
+        let mut ancestors: Vec<usize> = (0..cell_count).collect();
+        let mut closed = vec![false; cell_count];
+        let mut heap = BinaryHeap::new();
+        let mut expansions = 0;
+        g_values[start_index] = 0.0;
+        heap.push(HeapEntry {
+            cost: octile_distance(grid.coordinates(start_index), goal),
+            node: start_index,
+        });
+
+        while let Some(HeapEntry { node, .. }) = heap.pop() {
+            if closed[node] {
+                continue;
+            }
+            closed[node] = true;
+            expansions += 1;
+            if node == destination_index {
+                return (Some(grid.expand_path(&ancestors, node)), expansions);
+            }
+
+            let current = grid.coordinates(node);
+            let direction = (node != ancestors[node]).then(|| {
+                let parent = grid.coordinates(ancestors[node]);
+                (
+                    (current.0 - parent.0).signum(),
+                    (current.1 - parent.1).signum(),
+                )
+            });
+            for (dx, dy) in grid.directions(current, direction) {
+                let Some(jump_point) = grid.jump(current, (dx, dy), goal) else {
+                    continue;
+                };
+                let jump_index = grid.index(jump_point);
+                if closed[jump_index] {
+                    continue;
+                }
+                let new_g_value = g_values[node] + octile_distance(current, jump_point);
+                if new_g_value < g_values[jump_index] {
+                    g_values[jump_index] = new_g_value;
+                    ancestors[jump_index] = node;
+                    heap.push(HeapEntry {
+                        cost: new_g_value + octile_distance(jump_point, goal),
+                        node: jump_index,
+                    });
+                }
+            }
+        }
+        (None, expansions)
+    }
+
+    /// The search operation shared by the diverse search variants. The entry cost function gets
+    /// invoked with the index of the current node and the index of the node the search is about to enter
+    /// through their connection. It returns an additional cost for entering the node, or None if the
+    /// connection may not be used at all.
+    /// The additional costs must not be negative to keep the heuristic admissible.
+    fn search_in_scratch<F>(
+        &self,
+        start_indices: &[usize],
+        destination_index: usize,
+        scratch: &mut SearchScratch,
+        entry_cost: F,
+    ) -> Option<Vec<usize>>
+    where
+        F: Fn(usize, usize) -> Option<f32>,
+    {
+        scratch.cache_heuristic(
+            (destination_index, self.layout_id),
+            self.nodes.iter().map(|node| &node.position),
+            self.nodes[destination_index].position,
+        );
+
+        let heuristic_values = std::mem::take(&mut scratch.heuristic_values);
+        let weight = self.heuristic_weight;
+        let result = self.search_in_scratch_with_heuristic(
+            start_indices,
+            destination_index,
+            scratch,
+            entry_cost,
+            |node| weight * heuristic_values[node],
+        );
+        scratch.heuristic_values = heuristic_values;
+        result
+    }
+
+    /// The same as [`NavGraph::search_in_scratch`], but with a heuristic function that estimates the remaining cost
+    /// from the node with the indicated index to the destination.
+    fn search_in_scratch_with_heuristic<F, H>(
+        &self,
+        start_indices: &[usize],
+        destination_index: usize,
+        scratch: &mut SearchScratch,
+        entry_cost: F,
+        heuristic: H,
+    ) -> Option<Vec<usize>>
+    where
+        F: Fn(usize, usize) -> Option<f32>,
+        H: Fn(usize) -> f32,
+    {
+        let use_heap = self.nodes.len() >= self.heap_threshold;
+        self.seed_search(start_indices, scratch, &heuristic, use_heap);
+        loop {
+            match self.search_step(destination_index, scratch, &entry_cost, &heuristic) {
+                SearchStatus::Running => {}
+                SearchStatus::Found(path) => return Some(path),
+                // In this case, there is no path, so we return none.
+                SearchStatus::NoPath => return None,
+            }
+        }
+    }
+
+    /// Resets the scratch and puts the start nodes on the open list, or on the heap if indicated.
+    fn seed_search<H>(
+        &self,
+        start_indices: &[usize],
+        scratch: &mut SearchScratch,
+        heuristic: &H,
+        use_heap: bool,
+    ) where
+        H: Fn(usize) -> f32,
+    {
+        scratch.reset(self.nodes.len(), use_heap);
+
+        for start_index in start_indices.iter() {
+            let start_index = *start_index;
+            if scratch.states[start_index] != NodeState::Clear {
+                continue;
+            }
+            scratch.states[start_index] = NodeState::Visited;
+            scratch.g_values[start_index] = 0.0;
+            scratch.f_values[start_index] = heuristic(start_index);
+            scratch.ancestors[start_index] = start_index;
+            scratch.enter_open(start_index, true);
+        }
+    }
+
+    /// Performs one step of the search, which closes the best node of the open list and expands its neighbors.
+    fn search_step<F, H>(
+        &self,
+        destination_index: usize,
+        scratch: &mut SearchScratch,
+        entry_cost: &F,
+        heuristic: &H,
+    ) -> SearchStatus
+    where
+        F: Fn(usize, usize) -> Option<f32>,
+        H: Fn(usize) -> f32,
+    {
+        let Some(best_candidate) = scratch.pop_best() else {
+            return SearchStatus::NoPath;
+        };
+
+        scratch.states[best_candidate] = NodeState::Closed;
+
+        if best_candidate == destination_index {
+            return SearchStatus::Found(scratch.get_path(destination_index));
+        }
+
+        let root_g_value = scratch.g_values[best_candidate];
+
+        for (global_index, distance) in self.nodes[best_candidate].connections.iter() {
+            let global_index = *global_index;
+            if scratch.states[global_index] == NodeState::Closed
+                || !self.edge_enabled_unchecked(best_candidate, global_index)
+            {
+                continue;
+            }
+            let Some(additional_cost) = entry_cost(best_candidate, global_index) else {
+                continue;
+            };
+            let new_g_value = root_g_value
+                + self.traversal_cost(best_candidate, global_index, *distance)
+                + additional_cost;
+
+            let newly_visited = match scratch.states[global_index] {
+                NodeState::Clear => {
+                    scratch.states[global_index] = NodeState::Visited;
+                    true
+                }
+                NodeState::Visited => {
+                    let old_g_value = scratch.g_values[global_index];
+                    if new_g_value >= old_g_value - self.improvement_epsilon * old_g_value {
+                        continue;
+                    }
+                    false
+                }
+                NodeState::Closed | NodeState::Solution => {
+                    panic!("Case should not happen")
+                }
+            };
+            scratch.ancestors[global_index] = best_candidate;
+            scratch.g_values[global_index] = new_g_value;
+            scratch.f_values[global_index] = new_g_value + heuristic(global_index);
+            scratch.enter_open(global_index, newly_visited);
+        }
+        SearchStatus::Running
+    }
+
+    /// Begins a search from the start to the destination node that is performed step by step with
+    /// [`SearchSession::step`]. This is useful for visualizing the progress of the algorithm, or for spreading
+    /// a search over several frames.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{NavGraph, SearchStatus};
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let mut session = graph.begin_search(p0, p1);
+    /// assert_eq!(session.step(), SearchStatus::Running);
+    /// assert_eq!(session.frontier(), [p1]);
+    /// assert_eq!(session.step(), SearchStatus::Found(vec![p0, p1]));
+    /// ```
+    pub fn begin_search(&self, start_index: usize, destination_index: usize) -> SearchSession<'_> {
+        let mut scratch = SearchScratch::new();
+        let dest_point = self.nodes[destination_index].position;
+        self.seed_search(
+            &[start_index],
+            &mut scratch,
+            &|node| self.nodes[node].position.dist_to(&dest_point),
+            false,
+        );
+        SearchSession {
+            graph: self,
+            destination_index,
+            scratch,
+            status: SearchStatus::Running,
+        }
+    }
+
+    /// Searches from the start to the destination node, but closes at most the indicated number of nodes.
+    /// This bounds the work per frame without measuring time. If the cap is hit first, the result holds the
+    /// session of the search, which continues next frame with [`SearchSession::resume`].
+    /// The graph is only borrowed immutably, the node states of the graph remain untouched.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::{NavGraph, SearchProgress};
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([2.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    ///
+    /// let SearchProgress::Incomplete(session) = graph.search_graph_with_iteration_cap(p0, p2, 1) else {
+    ///     panic!("The cap is too small to find the path.");
+    /// };
+    /// let SearchProgress::Found(path) = session.resume(10) else {
+    ///     panic!("The path should be found on resume.");
+    /// };
+    /// assert_eq!(path, [p0, p1, p2]);
+    /// ```
+    pub fn search_graph_with_iteration_cap(
+        &self,
+        start_index: usize,
+        destination_index: usize,
+        max_expansions: usize,
+    ) -> SearchProgress<'_> {
+        self.begin_search(start_index, destination_index)
+            .resume(max_expansions)
+    }
+
+    /// Begins an anytime search from the start to the destination node, which immediately searches a path
+    /// with the heuristic inflated by the initial weight. Such a path is found quickly, but its cost may
+    /// exceed the optimal one by up to that factor. Calling [`AnytimeSearch::improve`] lowers the weight and
+    /// searches again, until the weight reaches 1 and the path is optimal. Real time agents may use the best path
+    /// found within their time budget. Unlike ARA\*, every improvement is a complete weighted A\* search
+    /// from scratch, which neither reuses the expanded nodes of the previous one nor prunes with the cost of
+    /// the best path, so each call costs about as much as a search with [`NavGraph::search_graph`].
+    /// Weights below 1 are treated as 1.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    ///
+    /// let mut search = graph.begin_anytime(p0, p1, 3.0);
+    /// assert_eq!(search.best_path().unwrap(), [p0, p1]);
+    /// while !search.is_optimal() {
+    ///     search.improve();
+    /// }
+    /// assert_eq!(search.best_path().unwrap(), [p0, p1]);
+    /// ```
+    pub fn begin_anytime(
+        &self,
+        start_index: usize,
+        destination_index: usize,
+        initial_weight: f32,
+    ) -> AnytimeSearch<'_> {
+        let mut search = AnytimeSearch {
+            graph: self,
+            start_index,
+            destination_index,
+            weight: initial_weight.max(1.0),
+            scratch: SearchScratch::new(),
+            best: None,
+        };
+        search.run();
+        search
+    }
+
+    /// Begins an incremental D* Lite search from the start to the goal node. The returned search
+    /// object takes a copy of the current edge costs. Edge costs may then be changed on the search
+    /// object with [`DStarLite::update_edge`], and [`DStarLite::replan`] repairs the path
+    /// instead of computing it from scratch. This is useful for agents that re-plan while the world changes.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([1.0, 1.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p1, p2).unwrap();
+    /// graph.connect_nodes(p0, p2).unwrap();
+    ///
+    /// let mut search = graph.begin_dstar(p0, p2);
+    /// assert_eq!(search.replan().unwrap(), [p0, p2]);
+    /// search.update_edge(p0, p2, f32::INFINITY).unwrap();
+    /// assert_eq!(search.replan().unwrap(), [p0, p1, p2]);
+    /// ```
+    pub fn begin_dstar(&self, start_index: usize, goal_index: usize) -> DStarLite {
+        let positions: Vec<Vec2> = self.nodes.iter().map(|node| node.position).collect();
+        let connections: Vec<Vec<(usize, f32)>> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .map(|(index, node)| {
+                node.connections
+                    .iter()
+                    .map(|(partner, distance)| {
+                        // A disabled edge stays known with an infinite cost, so that it may be updated later.
+                        let cost = if self.edge_enabled_unchecked(index, *partner) {
+                            self.traversal_cost(index, *partner, *distance)
+                        } else {
+                            f32::INFINITY
+                        };
+                        (*partner, cost)
+                    })
+                    .collect()
+            })
+            .collect();
+        DStarLite::new(
+            positions,
+            connections,
+            start_index,
+            goal_index,
+            self.generation,
+        )
+    }
+}
+
+/// Simplifies a path of positions with the Ramer-Douglas-Peucker algorithm, which removes the
+/// points that deviate less than the tolerance from the simplified path. This is useful to reduce the
+/// vertex count of a dense or noisy polyline. The end points are always kept.
+///
+/// # Example
+/// ```
+/// use astar_lib::a_star::simplify_path_rdp;
+/// let path = [[0.0, 0.0], [1.0, 0.01], [2.0, 0.0], [2.0, 1.0]];
+/// assert_eq!(simplify_path_rdp(&path, 0.1), [[0.0, 0.0], [2.0, 0.0], [2.0, 1.0]]);
+/// ```
+pub fn simplify_path_rdp(path: &[[f32; 2]], tolerance: f32) -> Vec<[f32; 2]> {
+    if path.len() < 3 {
+        return path.to_vec();
+    }
+    let mut keep = vec![false; path.len()];
+    keep[0] = true;
+    keep[path.len() - 1] = true;
+
+    let mut todo = vec![(0, path.len() - 1)];
+    while let Some((first, last)) = todo.pop() {
+        let line = Line::new(Vec2::from(path[first]), Vec2::from(path[last]));
+        let farthest = ((first + 1)..last)
+            .map(|index| (index, line.distance_to_point(Vec2::from(path[index]))))
+            .max_by_key(|(_, distance)| OrderedF32(*distance));
+        if let Some((index, distance)) = farthest
+            && distance > tolerance
+        {
+            keep[index] = true;
+            todo.push((first, index));
+            todo.push((index, last));
+        }
+    }
+
+    path.iter()
+        .zip(keep.iter())
+        .filter(|(_, keep)| **keep)
+        .map(|(point, _)| *point)
+        .collect()
+}
+
+/// Gets the key of an edge in the attribute map, which does not depend on the order of the nodes.
+fn edge_key(node1: usize, node2: usize) -> (usize, usize) {
+    (node1.min(node2), node1.max(node2))
+}
+
+/// Interleaves the bits of the two coordinates into a Morton code, with the bits of x in the even positions.
+fn morton_code(x: u32, y: u32) -> u64 {
+    let spread = |value: u32| {
+        let mut value = value as u64;
+        value = (value | (value << 16)) & 0x0000_FFFF_0000_FFFF;
+        value = (value | (value << 8)) & 0x00FF_00FF_00FF_00FF;
+        value = (value | (value << 4)) & 0x0F0F_0F0F_0F0F_0F0F;
+        value = (value | (value << 2)) & 0x3333_3333_3333_3333;
+        (value | (value << 1)) & 0x5555_5555_5555_5555
+    };
+    spread(x) | (spread(y) << 1)
+}
+
+/// Gets the smallest circle through both points, given by its center and radius.
+fn circle_from_two(first: &Vec2, second: &Vec2) -> (Vec2, f32) {
+    let center = first.lerp(second, 0.5);
+    (center, center.dist_to(first).max(center.dist_to(second)))
+}
+
+/// Gets the circle through all three points, or the smallest circle containing them if they are collinear.
+fn circle_from_three(first: &Vec2, second: &Vec2, third: &Vec2) -> (Vec2, f32) {
+    let to_second = *second - *first;
+    let to_third = *third - *first;
+    let cross = to_second.x * to_third.y - to_second.y * to_third.x;
+    if cross.abs() <= f32::EPSILON * to_second.magnitude() * to_third.magnitude() {
+        let pairs = [(first, second), (first, third), (second, third)];
+        let (from, to) = pairs
+            .into_iter()
+            .max_by_key(|(from, to)| OrderedF32(from.dist_to(to)))
+            .unwrap();
+        return circle_from_two(from, to);
+    }
+    let second_square = to_second.dot(to_second);
+    let third_square = to_third.dot(to_third);
+    let offset = Vec2::new(
+        (to_third.y * second_square - to_second.y * third_square) / (2.0 * cross),
+        (to_second.x * third_square - to_third.x * second_square) / (2.0 * cross),
+    );
+    let center = *first + offset;
+    let radius = center
+        .dist_to(first)
+        .max(center.dist_to(second))
+        .max(center.dist_to(third));
+    (center, radius)
+}
+
+/// A view on the cells of a uniform grid for the jump point search. The cell in column x and row y has the index y * cols + x.
+struct JpsGrid<'a> {
+    cols: usize,
+    rows: usize,
+    blocked: &'a [bool],
+}
+
+impl JpsGrid<'_> {
+    /// Gets the index of the cell with the indicated coordinates.
+    fn index(&self, (x, y): (i64, i64)) -> usize {
+        y as usize * self.cols + x as usize
+    }
+
+    /// Gets the column and the row of the cell with the indicated index.
+    fn coordinates(&self, index: usize) -> (i64, i64) {
+        ((index % self.cols) as i64, (index / self.cols) as i64)
+    }
+
+    /// Checks if the coordinates are inside the grid and the cell is not blocked.
+    fn walkable(&self, x: i64, y: i64) -> bool {
+        x >= 0
+            && y >= 0
+            && (x as usize) < self.cols
+            && (y as usize) < self.rows
+            && !self.blocked[self.index((x, y))]
+    }
+
+    /// Checks if the move in the indicated direction is possible. Diagonal moves also need both
+    /// orthogonal neighbors to be free, so that no corner gets cut.
+    fn can_move(&self, (x, y): (i64, i64), (dx, dy): (i64, i64)) -> bool {
+        self.walkable(x + dx, y + dy)
+            && (dx == 0 || dy == 0 || (self.walkable(x + dx, y) && self.walkable(x, y + dy)))
+    }
+
+    /// Checks if a cell reached with a straight move has a forced neighbor, which is a side
+    /// cell that can not be reached cheaper without passing the cell.
+    fn has_forced_neighbor(&self, (x, y): (i64, i64), (dx, dy): (i64, i64)) -> bool {
+        [-1, 1].iter().any(|side| {
+            if dx != 0 {
+                self.walkable(x, y + side) && !self.walkable(x - dx, y + side)
+            } else {
+                self.walkable(x + side, y) && !self.walkable(x + side, y - dy)
+            }
+        })
+    }
+
+    /// Gets the directions to explore from a cell that has been reached with the indicated direction.
+    /// All other neighbors can be reached at least as cheap without passing the cell, so they get pruned.
+    /// Without a direction, which is the case for the start, all possible moves are explored.
+    fn directions(&self, cell: (i64, i64), direction: Option<(i64, i64)>) -> Vec<(i64, i64)> {
+        let Some((dx, dy)) = direction else {
+            let mut result = Vec::new();
+            for dx in -1..=1 {
+                for dy in -1..=1 {
+                    if (dx, dy) != (0, 0) && self.can_move(cell, (dx, dy)) {
+                        result.push((dx, dy));
+                    }
+                }
+            }
+            return result;
+        };
+
+        let (x, y) = cell;
+        let mut candidates = vec![(dx, dy)];
+        if dx != 0 && dy != 0 {
+            candidates.push((dx, 0));
+            candidates.push((0, dy));
+        } else {
+            for side in [-1, 1] {
+                let forced = if dx != 0 {
+                    self.walkable(x, y + side) && !self.walkable(x - dx, y + side)
+                } else {
+                    self.walkable(x + side, y) && !self.walkable(x + side, y - dy)
+                };
+                if forced {
+                    if dx != 0 {
+                        candidates.push((0, side));
+                        candidates.push((dx, side));
+                    } else {
+                        candidates.push((side, 0));
+                        candidates.push((side, dy));
+                    }
+                }
+            }
+        }
+        candidates
+            .into_iter()
+            .filter(|direction| self.can_move(cell, *direction))
+            .collect()
+    }
+
+    /// Moves from the cell in the indicated direction until a jump point is found, which is the goal,
+    /// a cell with a forced neighbor, or for diagonal moves a cell from which a straight move finds a jump point.
+    /// Returns None if the move runs into a blocked cell or the border.
+    fn jump(
+        &self,
+        cell: (i64, i64),
+        direction: (i64, i64),
+        goal: (i64, i64),
+    ) -> Option<(i64, i64)> {
+        let (dx, dy) = direction;
+        let mut current = cell;
+        loop {
+            if !self.can_move(current, direction) {
+                return None;
+            }
+            current = (current.0 + dx, current.1 + dy);
+            if current == goal {
+                return Some(current);
+            }
+            if dx != 0 && dy != 0 {
+                if self.jump(current, (dx, 0), goal).is_some()
+                    || self.jump(current, (0, dy), goal).is_some()
+                {
+                    return Some(current);
+                }
+            } else if self.has_forced_neighbor(current, direction) {
+                return Some(current);
+            }
+        }
+    }
+
+    /// Reconstructs the path from the ancestors of the jump points and fills in the cells between them.
+    fn expand_path(&self, ancestors: &[usize], destination_index: usize) -> Vec<usize> {
+        let mut path = vec![destination_index];
+        let mut scan = destination_index;
+        while scan != ancestors[scan] {
+            let target = self.coordinates(ancestors[scan]);
+            let mut current = self.coordinates(scan);
+            let step = (
+                (target.0 - current.0).signum(),
+                (target.1 - current.1).signum(),
+            );
+            while current != target {
+                current = (current.0 + step.0, current.1 + step.1);
+                path.push(self.index(current));
+            }
+            scan = ancestors[scan];
+        }
+        path.reverse();
+        path
+    }
+}
+
+/// Gets the length of the shortest path between two cells on an open grid with straight and diagonal moves.
+fn octile_distance(first: (i64, i64), second: (i64, i64)) -> f32 {
+    let delta_x = (first.0 - second.0).abs() as f32;
+    let delta_y = (first.1 - second.1).abs() as f32;
+    delta_x.max(delta_y) + (std::f32::consts::SQRT_2 - 1.0) * delta_x.min(delta_y)
+}
+
+/// Computes the total length of a path of positions by summing up the distances between consecutive points.
+/// This may be used to normalize the movement speed along a path or to estimate the arrival time.
+/// Empty and single point paths have the length 0.0.
+///
+/// # Example
+/// ```
+/// use astar_lib::a_star::path_length;
+/// let length = path_length(&[[0.0, 0.0], [3.0, 0.0], [3.0, 4.0]]);
+/// assert_eq!(length, 7.0);
+/// ```
+pub fn path_length(path: &[[f32; 2]]) -> f32 {
+    path.windows(2)
+        .map(|pair| Vec2::from(pair[0]).dist_to(&Vec2::from(pair[1])))
+        .sum()
+}
+
+/// Computes the area enclosed by a closed path of positions with the shoelace formula, for example the territory
+/// enclosed by a patrol loop. The path is closed implicitly from the last to the first point, which may also be repeated
+/// at the end. The area is signed: it is positive for counter-clockwise paths and negative for clockwise ones, so its
+/// absolute value is the size. Self-intersecting paths add up the signed areas of their loops.
+/// Paths with less than three points enclose no area.
+///
+/// # Example
+/// ```
+/// use astar_lib::a_star::path_area;
+/// let triangle = [[0.0, 0.0], [4.0, 0.0], [0.0, 3.0]];
+/// assert_eq!(path_area(&triangle), 6.0);
+/// ```
+pub fn path_area(path: &[[f32; 2]]) -> f32 {
+    if path.len() < 3 {
+        return 0.0;
+    }
+    // Relative coordinates keep the products small for paths far from the origin.
+    let origin = Vec2::from(path[0]);
+    let doubled: f32 = (0..path.len())
+        .map(|index| {
+            let current = Vec2::from(path[index]) - origin;
+            let next = Vec2::from(path[(index + 1) % path.len()]) - origin;
+            current.x * next.y - next.x * current.y
+        })
+        .sum();
+    0.5 * doubled
+}
+
+/// Samples a smooth curve through the positions of a path, so that agents following it move on natural curves
+/// instead of straight segments. The curve is a uniform Catmull-Rom spline, which passes through every position of
+/// the path, and the first and the last position are duplicated as the outer control points. Every segment
+/// between two positions gets sampled the indicated number of times, at least once, starting with its first
+/// position, and the last position of the path ends the result. So a path of n positions gives
+/// (n - 1) · samples + 1 points. Like the path itself, the curve may cut corners, and it may slightly overshoot
+/// at sharp turns.
+///
+/// # Example
+/// ```
+/// use astar_lib::a_star::spline_path;
+/// let curve = spline_path(&[[0.0, 0.0], [1.0, 0.0], [1.0, 1.0]], 4);
+/// assert_eq!(curve.len(), 9);
+/// assert_eq!(curve[0], [0.0, 0.0]);
+/// assert_eq!(curve[4], [1.0, 0.0]);
+/// assert_eq!(curve[8], [1.0, 1.0]);
+/// ```
+pub fn spline_path(path: &[[f32; 2]], samples_per_segment: usize) -> Vec<[f32; 2]> {
+    let Some(last) = path.last() else {
+        return Vec::new();
+    };
+    let samples = samples_per_segment.max(1);
+    let mut result = Vec::with_capacity((path.len() - 1) * samples + 1);
+    for index in 0..path.len() - 1 {
+        let p0 = path[index.saturating_sub(1)];
+        let p1 = path[index];
+        let p2 = path[index + 1];
+        let p3 = path[(index + 2).min(path.len() - 1)];
+        for sample in 0..samples {
+            let t = sample as f32 / samples as f32;
+            let coordinate = |axis: usize| {
+                let (a, b, c, d) = (p0[axis], p1[axis], p2[axis], p3[axis]);
+                0.5 * (2.0 * b
+                    + (c - a) * t
+                    + (2.0 * a - 5.0 * b + 4.0 * c - d) * t * t
+                    + (3.0 * b - a - 3.0 * c + d) * t * t * t)
+            };
+            result.push([coordinate(0), coordinate(1)]);
+        }
+    }
+    result.push(*last);
+    result
+}
+
+/// Computes the time it takes to travel along a path of positions at the indicated speed, which is the
+/// estimated time of arrival a game may display for a moving unit. The time is in the units of the speed,
+/// so a speed in meters per second gives seconds. A path of length 0.0 takes no time, while a speed that is not
+/// positive never arrives at all and gives an infinite time.
+///
+/// # Example
+/// ```
+/// use astar_lib::a_star::path_eta;
+/// let eta = path_eta(&[[0.0, 0.0], [3.0, 0.0], [3.0, 4.0]], 2.0);
+/// assert_eq!(eta, 3.5);
+/// ```
+pub fn path_eta(path: &[[f32; 2]], speed: f32) -> f32 {
+    let length = path_length(path);
+    if length == 0.0 {
+        0.0
+    } else if speed > 0.0 {
+        length / speed
+    } else {
+        f32::INFINITY
+    }
+}
+
+/// Reduces a path of positions to its turning points, which are the points where the heading changes
+/// by more than the angle threshold in radians. The end points are always kept. This is what a waypoint
+/// following agent needs, as it may move straight between the turning points. Repeated points are ignored.
+///
+/// # Example
+/// ```
+/// use astar_lib::a_star::turning_points;
+/// let path = [[0.0, 0.0], [1.0, 0.0], [2.0, 0.0], [2.0, 1.0]];
+/// assert_eq!(turning_points(&path, 0.1), [[0.0, 0.0], [2.0, 0.0], [2.0, 1.0]]);
+/// ```
+pub fn turning_points(path: &[[f32; 2]], angle_threshold: f32) -> Vec<[f32; 2]> {
+    let mut points: Vec<Vec2> = path.iter().map(|point| Vec2::from(*point)).collect();
+    points.dedup();
+    if points.len() < 3 {
+        return points.into_iter().map(|point| point.into()).collect();
+    }
+
+    let mut result = vec![points[0]];
+    for window in points.windows(3) {
+        let incoming = window[1] - window[0];
+        let outgoing = window[2] - window[1];
+        if incoming.angle_to(&outgoing) > angle_threshold {
+            result.push(window[1]);
+        }
+    }
+    result.push(points[points.len() - 1]);
+    result.into_iter().map(|point| point.into()).collect()
+}
+
+/// An entry of the priority queue of the D* Lite search with the two component key.
+/// The ordering is reversed, so that the binary heap pops the lowest key first.
+#[derive(Debug, Clone, Copy)]
+struct KeyEntry {
+    key: (f32, f32),
+    node: usize,
+}
+
+impl PartialEq for KeyEntry {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for KeyEntry {}
+
+impl PartialOrd for KeyEntry {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for KeyEntry {
+    fn cmp(&self, other: &Self) -> Ordering {
+        compare_keys(other.key, self.key).then_with(|| other.node.cmp(&self.node))
+    }
+}
+
+/// Compares two keys of the D* Lite search lexicographically.
+fn compare_keys(first: (f32, f32), second: (f32, f32)) -> Ordering {
+    (OrderedF32(first.0), OrderedF32(first.1)).cmp(&(OrderedF32(second.0), OrderedF32(second.1)))
+}
+
+/// The result of a search together with its state, as created by [`NavGraph::search`].
+pub struct SearchContext<'a> {
+    graph: &'a NavGraph,
+    scratch: SearchScratch,
+    path: Option<Vec<usize>>,
+}
+
+impl SearchContext<'_> {
+    /// Gets the path found, or None if the destination can not be reached.
+    pub fn path(&self) -> Option<&[usize]> {
+        self.path.as_deref()
+    }
+
+    /// Gets the positions of the nodes of the path found, or None if the destination can not be reached.
+    pub fn path_positions(&self) -> Option<Vec<[f32; 2]>> {
+        self.path.as_ref().map(|path| {
+            path.iter()
+                .map(|node| self.graph.nodes[*node].position.into())
+                .collect()
+        })
+    }
+
+    /// Gets the number of nodes the search has expanded, which are the closed nodes including the solution.
+    pub fn expanded(&self) -> usize {
+        self.scratch
+            .states
+            .iter()
+            .filter(|state| matches!(state, NodeState::Closed | NodeState::Solution))
+            .count()
+    }
+
+    /// Gets the state of the indicated node in the search, or None if the node does not exist.
+    pub fn node_state(&self, node: usize) -> Option<&NodeState> {
+        self.scratch.node_state(node)
+    }
+}
+
+/// The progress of a search performed with a [`SearchSession`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum SearchStatus {
+    /// The search has not finished yet.
+    Running,
+    /// The search has found the contained path.
+    Found(Vec<usize>),
+    /// The search has finished without finding a path.
+    NoPath,
+}
+
+/// The result of a search with a limited number of expansions, as created by
+/// [`NavGraph::search_graph_with_iteration_cap`].
+// The session gets handed back and forth by value for every slice, boxing it would allocate each time.
+#[allow(clippy::large_enum_variant)]
+pub enum SearchProgress<'a> {
+    /// The search has found the contained path.
+    Found(Vec<usize>),
+    /// The search has explored everything reachable without finding a path.
+    Exhausted,
+    /// The search has hit the cap, it may be continued with the contained session.
+    Incomplete(SearchSession<'a>),
+}
+
+/// A search that is performed step by step, as created by [`NavGraph::begin_search`].
+/// Every step closes one node, so that the intermediate state of the search may be inspected.
+pub struct SearchSession<'a> {
+    graph: &'a NavGraph,
+    destination_index: usize,
+    scratch: SearchScratch,
+    status: SearchStatus,
+}
+
+impl<'a> SearchSession<'a> {
+    /// Performs one step of the search and returns the resulting status. Once the search has finished,
+    /// further steps do nothing and return the final status again.
+    pub fn step(&mut self) -> SearchStatus {
+        if self.status == SearchStatus::Running {
+            let graph = self.graph;
+            let dest_point = graph.nodes[self.destination_index].position;
+            self.status = graph.search_step(
+                self.destination_index,
+                &mut self.scratch,
+                &|_, _| Some(0.0),
+                &|node| graph.nodes[node].position.dist_to(&dest_point),
+            );
+        }
+        self.status.clone()
+    }
+
+    /// Continues the search for at most the indicated number of steps. The session is handed back
+    /// in the result, if the search has not finished by then.
+    pub fn resume(mut self, max_expansions: usize) -> SearchProgress<'a> {
+        for _ in 0..max_expansions {
+            match self.step() {
+                SearchStatus::Running => {}
+                SearchStatus::Found(path) => return SearchProgress::Found(path),
+                SearchStatus::NoPath => return SearchProgress::Exhausted,
+            }
+        }
+        SearchProgress::Incomplete(self)
+    }
+
+    /// Gets the nodes on the open list at the current step, which is the fringe of the exploration.
+    /// The order of the nodes is unspecified.
+    pub fn frontier(&self) -> &[usize] {
+        &self.scratch.open_list
+    }
+
+    /// Gets the nodes on the open list at the current step together with their f-values, which are the
+    /// cost so far plus the heuristic. The snapshot is sorted by ascending f-value, where nodes of equal value
+    /// are sorted by the time their value was set, so the first entry is the node the next step expands.
+    /// This is meant for debugging the ordering of the open list and custom heuristics.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::a_star::NavGraph;
+    /// let mut graph = NavGraph::new();
+    /// let p0 = graph.add_node([0.0, 0.0]);
+    /// let p1 = graph.add_node([1.0, 0.0]);
+    /// let p2 = graph.add_node([0.0, 3.0]);
+    /// graph.connect_nodes(p0, p1).unwrap();
+    /// graph.connect_nodes(p0, p2).unwrap();
+    ///
+    /// let mut session = graph.begin_search(p0, p1);
+    /// session.step();
+    /// assert_eq!(session.open_set_snapshot(), [(p1, 1.0), (p2, 3.0 + 10.0_f32.sqrt())]);
+    /// ```
+    pub fn open_set_snapshot(&self) -> Vec<(usize, f32)> {
+        let mut snapshot: Vec<(usize, f32)> = self
+            .scratch
+            .open_list
+            .iter()
+            .map(|node| (*node, self.scratch.f_values[*node]))
+            .collect();
+        snapshot.sort_by_key(|(node, f_value)| {
+            (OrderedF32(*f_value), self.scratch.entry_orders[*node])
+        });
+        snapshot
+    }
+
+    /// Gets the state of the indicated node at the current step, or None if the node does not exist.
+    pub fn node_state(&self, node: usize) -> Option<&NodeState> {
+        self.scratch.node_state(node)
+    }
+}
+
+/// The weight at which an anytime search switches to the optimal weight of 1.
+const MIN_WEIGHT_EXCESS: f32 = 0.01;
+
+/// An anytime search, as created by [`NavGraph::begin_anytime`]. It always holds the best path found so far,
+/// which may improve with every call to [`AnytimeSearch::improve`] until it is optimal.
+pub struct AnytimeSearch<'a> {
+    graph: &'a NavGraph,
+    start_index: usize,
+    destination_index: usize,
+    weight: f32,
+    scratch: SearchScratch,
+    best: Option<(Vec<usize>, f32)>,
+}
+
+impl AnytimeSearch<'_> {
+    /// Halves the excess of the weight over 1, searches again from scratch and returns the best path found so far.
+    /// A new path only replaces the previous one if it is cheaper, so the cost never increases.
+    /// Once the search is optimal, this does not search again.
+    pub fn improve(&mut self) -> Option<&[usize]> {
+        if !self.is_optimal() {
+            self.weight = 1.0 + (self.weight - 1.0) * 0.5;
+            if self.weight - 1.0 < MIN_WEIGHT_EXCESS {
+                self.weight = 1.0;
+            }
+            self.run();
+        }
+        self.best_path()
+    }
+
+    /// Gets the best path found so far, or None if the destination can not be reached.
+    pub fn best_path(&self) -> Option<&[usize]> {
+        self.best.as_ref().map(|(path, _)| path.as_slice())
+    }
+
+    /// Gets the cost of the best path, including the node cost multipliers.
+    pub fn best_cost(&self) -> Option<f32> {
+        self.best.as_ref().map(|(_, cost)| *cost)
+    }
+
+    /// Gets the current weight of the heuristic.
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+
+    /// Checks if the weight has reached 1, in which case the best path is optimal.
+    pub fn is_optimal(&self) -> bool {
+        self.weight <= 1.0
+    }
+
+    /// Searches with the current weight and keeps the result if it is the cheapest one so far.
+    fn run(&mut self) {
+        let graph = self.graph;
+        let weight = self.weight;
+        let dest_point = graph.nodes[self.destination_index].position;
+        let Some(path) = graph.search_in_scratch_with_heuristic(
+            &[self.start_index],
+            self.destination_index,
+            &mut self.scratch,
+            |_, _| Some(0.0),
+            |node| weight * graph.nodes[node].position.dist_to(&dest_point),
+        ) else {
+            return;
+        };
+        let cost = graph.search_cost_of_path(&path);
+        if self.best_cost().is_none_or(|best_cost| cost < best_cost) {
+            self.best = Some((path, cost));
+        }
+    }
+}
+
+/// An immutable nav graph that is optimized for queries, as created by [`NavGraph::freeze`].
+/// It offers no methods to change the graph, and all searches only borrow it, so it may be shared between threads.
+#[derive(Debug, Clone)]
+pub struct FrozenNavGraph {
+    positions: Vec<Vec2>,
+    offsets: Vec<usize>,
+    neighbors: Vec<usize>,
+    costs: Vec<f32>,
+    cell_size: f32,
+    cells: HashMap<(i64, i64), Vec<usize>>,
+    layout_id: u64,
+    heuristic_weight: f32,
+    improvement_epsilon: f32,
+}
+
+impl FrozenNavGraph {
+    /// Gets the number of nodes of the graph.
+    pub fn node_count(&self) -> usize {
+        self.positions.len()
+    }
+
+    /// Gets the position of the indicated node, or None if the node does not exist.
+    pub fn node_position(&self, node: usize) -> Option<[f32; 2]> {
+        self.positions.get(node).map(|position| (*position).into())
+    }
+
+    /// Gets the neighbors of the indicated node together with the costs of reaching them,
+    /// which include the node cost multipliers. Returns an empty iterator if the node does not exist.
+    pub fn neighbors(&self, node: usize) -> impl Iterator<Item = (usize, f32)> + '_ {
+        let range = match self.offsets.get(node..node + 2) {
+            Some(bounds) => bounds[0]..bounds[1],
+            None => 0..0,
+        };
+        self.neighbors[range.clone()]
+            .iter()
+            .copied()
+            .zip(self.costs[range].iter().copied())
+    }
+
+    /// Finds the nearest node to the indicated position within a certain maximum radius like
+    /// [`NavGraph::find_nearest_node_with_radius`], but only checks the cells of the spatial grid
+    /// that overlap the radius, so the cost does not grow with the size of the graph.
+    pub fn find_nearest_node_with_radius(&self, position: [f32; 2], radius: f32) -> Option<usize> {
+        let probing = Vec2::from(position);
+        let extent = Vec2::new(radius, radius);
+        let (min_x, min_y) = (probing - extent).quantized_key(self.cell_size);
+        let (max_x, max_y) = (probing + extent).quantized_key(self.cell_size);
+        let closer = |best: Option<(f32, usize)>, index: usize| {
+            let dist = self.positions[index].dist_to(&probing);
+            match best {
+                Some((best_dist, best_index))
+                    if (best_dist, best_index) <= (dist, index) || dist.is_nan() =>
+                {
+                    best
+                }
+                _ => Some((dist, index)),
+            }
+        };
+
+        let cell_count =
+            (max_x.saturating_sub(min_x) as u128 + 1) * (max_y.saturating_sub(min_y) as u128 + 1);
+        let best = if cell_count > self.cells.len() as u128 {
+            // For large radii scanning all occupied cells is cheaper.
+            self.cells.values().flatten().copied().fold(None, closer)
+        } else {
+            (min_x..=max_x)
+                .flat_map(|x| (min_y..=max_y).map(move |y| (x, y)))
+                .filter_map(|key| self.cells.get(&key))
+                .flatten()
+                .copied()
+                .fold(None, closer)
+        };
+        best.filter(|(dist, _)| *dist <= radius)
+            .map(|(_, index)| index)
+    }
+
+    /// Searches the shortest path with the handed over scratch like [`NavGraph::search_graph_with_scratch`]
+    /// and returns the same path for the graph it was frozen from.
+    pub fn search_graph_with_scratch(
+        &self,
+        start_index: usize,
+        destination_index: usize,
+        scratch: &mut SearchScratch,
+    ) -> Option<Vec<usize>> {
+        scratch.cache_heuristic(
+            (destination_index, self.layout_id),
+            self.positions.iter(),
+            self.positions[destination_index],
+        );
+        scratch.reset(self.positions.len(), false);
+        scratch.states[start_index] = NodeState::Visited;
+        scratch.g_values[start_index] = 0.0;
+        scratch.f_values[start_index] =
+            self.heuristic_weight * scratch.heuristic_values[start_index];
+        scratch.ancestors[start_index] = start_index;
+        scratch.enter_open(start_index, true);
+
+        while let Some(best_candidate) = scratch.pop_best() {
+            scratch.states[best_candidate] = NodeState::Closed;
+            if best_candidate == destination_index {
+                return Some(scratch.get_path(destination_index));
+            }
+
+            let root_g_value = scratch.g_values[best_candidate];
+            for (global_index, cost) in self.neighbors(best_candidate) {
+                let new_g_value = root_g_value + cost;
+                let newly_visited = match scratch.states[global_index] {
+                    NodeState::Clear => {
+                        scratch.states[global_index] = NodeState::Visited;
+                        true
+                    }
+                    NodeState::Visited => {
+                        let old_g_value = scratch.g_values[global_index];
+                        if new_g_value >= old_g_value - self.improvement_epsilon * old_g_value {
+                            continue;
+                        }
+                        false
+                    }
+                    NodeState::Closed | NodeState::Solution => continue,
+                };
+                scratch.ancestors[global_index] = best_candidate;
+                scratch.g_values[global_index] = new_g_value;
+                scratch.f_values[global_index] =
+                    new_g_value + self.heuristic_weight * scratch.heuristic_values[global_index];
+                scratch.enter_open(global_index, newly_visited);
+            }
+        }
+        None
+    }
+
+    /// Finds the shortest path like [`FrozenNavGraph::search_graph_with_scratch`], but with a new scratch.
+    pub fn find_path(&self, start_index: usize, destination_index: usize) -> Option<Vec<usize>> {
+        self.search_graph_with_scratch(start_index, destination_index, &mut SearchScratch::new())
+    }
+}
+
+/// An incremental search with the D* Lite algorithm, as created by [`NavGraph::begin_dstar`].
+/// The search runs backwards from the goal, so that changed edge costs only require repairing the
+/// affected part of the search and the start may move along the path. Blocked edges are represented
+/// by an infinite cost.
+pub struct DStarLite {
+    positions: Vec<Vec2>,
+    connections: Vec<Vec<(usize, f32)>>,
+    start: usize,
+    goal: usize,
+    key_modifier: f32,
+    g_values: Vec<f32>,
+    rhs_values: Vec<f32>,
+    queue: BinaryHeap<KeyEntry>,
+    queued_keys: Vec<Option<(f32, f32)>>,
+    generation: u64,
+}
+
+impl DStarLite {
+    fn new(
+        positions: Vec<Vec2>,
+        connections: Vec<Vec<(usize, f32)>>,
+        start: usize,
+        goal: usize,
+        generation: u64,
+    ) -> DStarLite {
+        let node_count = positions.len();
+        let mut result = DStarLite {
+            positions,
+            connections,
+            start,
+            goal,
+            key_modifier: 0.0,
+            g_values: vec![f32::INFINITY; node_count],
+            rhs_values: vec![f32::INFINITY; node_count],
+            queue: BinaryHeap::new(),
+            queued_keys: vec![None; node_count],
+            generation,
+        };
+        result.rhs_values[goal] = 0.0;
+        result.enqueue(goal);
+        result
+    }
+
+    /// The heuristic distance between two nodes.
+    fn heuristic(&self, first: usize, second: usize) -> f32 {
+        self.positions[first].dist_to(&self.positions[second])
+    }
+
+    fn calculate_key(&self, node: usize) -> (f32, f32) {
+        let min_value = self.g_values[node].min(self.rhs_values[node]);
+        (
+            min_value + self.heuristic(self.start, node) + self.key_modifier,
+            min_value,
+        )
+    }
+
+    /// Puts the node in the queue with its current key. Former entries of the node become outdated.
+    fn enqueue(&mut self, node: usize) {
+        let key = self.calculate_key(node);
+        self.queued_keys[node] = Some(key);
+        self.queue.push(KeyEntry { key, node });
+    }
+
+    /// Removes outdated entries from the top of the queue and returns the top entry.
+    fn peek_valid(&mut self) -> Option<KeyEntry> {
+        while let Some(entry) = self.queue.peek() {
+            if self.queued_keys[entry.node] == Some(entry.key) {
+                return Some(*entry);
+            }
+            self.queue.pop();
+        }
+        None
+    }
+
+    fn update_vertex(&mut self, node: usize) {
+        if node != self.goal {
+            self.rhs_values[node] = self.connections[node]
+                .iter()
+                .map(|(partner, cost)| cost + self.g_values[*partner])
+                .fold(f32::INFINITY, f32::min);
+        }
+        self.queued_keys[node] = None;
+        if self.g_values[node] != self.rhs_values[node] {
+            self.enqueue(node);
+        }
+    }
+
+    fn compute_shortest_path(&mut self) {
+        while let Some(entry) = self.peek_valid() {
+            let start_key = self.calculate_key(self.start);
+            if compare_keys(entry.key, start_key) != Ordering::Less
+                && self.rhs_values[self.start] == self.g_values[self.start]
+            {
+                break;
+            }
+            self.queue.pop();
+            self.queued_keys[entry.node] = None;
+            let node = entry.node;
+
+            let new_key = self.calculate_key(node);
+            if compare_keys(entry.key, new_key) == Ordering::Less {
+                self.enqueue(node);
+            } else if self.g_values[node] > self.rhs_values[node] {
+                self.g_values[node] = self.rhs_values[node];
+                for index in 0..self.connections[node].len() {
+                    self.update_vertex(self.connections[node][index].0);
+                }
+            } else {
+                self.g_values[node] = f32::INFINITY;
+                self.update_vertex(node);
+                for index in 0..self.connections[node].len() {
+                    self.update_vertex(self.connections[node][index].0);
+                }
+            }
+        }
+    }
+
+    /// Changes the cost of the edge between the two nodes for this search. An infinite cost blocks the edge.
+    /// The path gets repaired on the next call of [`DStarLite::replan`].
+    ///
+    /// # Error
+    /// Returns an error if the two nodes are not connected in the graph the search has been created from.
+    pub fn update_edge(
+        &mut self,
+        node1: usize,
+        node2: usize,
+        new_cost: f32,
+    ) -> Result<(), ConnectionError> {
+        for (from, to) in [(node1, node2), (node2, node1)] {
+            let entry = self
+                .connections
+                .get_mut(from)
+                .and_then(|list| list.iter_mut().find(|(partner, _)| *partner == to))
+                .ok_or(ConnectionError::LinkDoesntExist)?;
+            entry.1 = new_cost;
+        }
+        self.update_vertex(node1);
+        self.update_vertex(node2);
+        Ok(())
+    }
+
+    /// Moves the start of the search to a different node, as an agent does when it follows the path.
+    pub fn move_start(&mut self, new_start: usize) {
+        self.key_modifier += self.heuristic(self.start, new_start);
+        self.start = new_start;
+    }
+
+    /// Checks if the indicated graph has changed since the search has been created from it, or if the search
+    /// has been created from another graph. In that case the copied edge costs are outdated, and the search
+    /// should be created again with [`NavGraph::begin_dstar`].
+    pub fn is_stale(&self, graph: &NavGraph) -> bool {
+        self.generation != graph.generation()
+    }
+
+    /// Repairs the search after changes and returns the path from the current start to the goal,
+    /// or None if the goal can not be reached.
+    pub fn replan(&mut self) -> Option<Vec<usize>> {
+        self.compute_shortest_path();
+        if self.g_values[self.start] == f32::INFINITY {
+            return None;
+        }
+
+        let mut path = vec![self.start];
+        let mut scan = self.start;
+        while scan != self.goal {
+            let (next, _) = self.connections[scan]
+                .iter()
+                .map(|(partner, cost)| (*partner, cost + self.g_values[*partner]))
+                .min_by_key(|(_, cost)| OrderedF32(*cost))?;
+            // A path can never be longer than the number of nodes.
+            if path.len() > self.positions.len() {
+                return None;
+            }
+            path.push(next);
+            scan = next;
+        }
+        Some(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base_test() {
+        let mut graph = NavGraph::new();
+
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([0.5, 0.5]);
+        let p2 = graph.add_node([1.0, 0.0]);
+        let p3 = graph.add_node([1.0, 1.0]);
+        let p4 = graph.add_node([0.1, 0.0]);
+        let p5 = graph.add_node([2.0, 2.0]);
+
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p2).unwrap();
+        graph.connect_nodes(p1, p4).unwrap();
+        graph.connect_nodes(p4, p3).unwrap();
+        graph.connect_nodes(p2, p3).unwrap();
+
+        let double_con_test = graph.connect_nodes(p1, p0);
+        assert_eq!(double_con_test, Err(ConnectionError::LinkAlreadyExists));
+
+        let result = graph.search_graph(p0, p3);
+        assert!(result.is_some());
+
+        let result = result.unwrap();
+        assert_eq!(result, [0, 2, 3]);
+
+        for (source, destination, solution) in graph.get_all_links_with_solution_hint() {
+            println!("{:?} -> {:?} : {}", source, destination, solution);
+        }
+
+        for (position, state) in graph.get_all_nodes_with_state() {
+            println!("{:?} : {:?}", position, state);
+        }
+
+        assert_eq!(
+            result.len(),
+            graph
+                .get_all_nodes_with_state()
+                .filter(|(_, state)| **state == NodeState::Solution)
+                .count(),
+            "They should be the same."
+        );
+
+        let result = graph.search_graph(p0, p5);
+        assert!(result.is_none(), "There should not be a solution!");
+
+        graph.disconnect_nodes(p3, p2).unwrap();
+        let result = graph.search_graph(p0, p3).unwrap();
+        assert_eq!(result, [0, 1, 4, 3]);
+
+        let test = graph.disconnect_nodes(p3, p2);
+        assert_eq!(
+            test,
+            Err(ConnectionError::LinkDoesntExist),
+            "Should not work"
+        );
+    }
+
+    #[test]
+    fn invalid_handle_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let generation = graph.generation();
+
+        for handle in [2, 3, usize::MAX] {
+            let error = Err(ConnectionError::NodeDoesntExist(handle));
+            assert_eq!(graph.connect_nodes(p0, handle), error);
+            assert_eq!(graph.connect_nodes(handle, p1), error);
+            assert_eq!(graph.set_links(&[(p0, p1), (handle, p0)]), error);
+            assert_eq!(graph.set_node_cost_multiplier(handle, 2.0), error);
+            assert_eq!(graph.set_node_position(handle, [0.0, 1.0]), error);
+            assert_eq!(graph.set_node_tags(handle, 1), error);
+            assert_eq!(graph.set_node_height(handle, 1.0), error);
+            assert_eq!(graph.reserve_node(handle, 0), error);
+            assert_eq!(graph.reserve_path(&[p0, handle], 0), error);
+            assert_eq!(
+                graph.split_edge(p0, handle, [0.5, 0.0]),
+                Err(ConnectionError::LinkDoesntExist)
+            );
+        }
+        assert_eq!(
+            graph.generation(),
+            generation,
+            "Failed edits leave the graph unchanged."
+        );
+        assert!(!graph.is_reserved(p0, 0));
+        graph.connect_nodes(p0, p1).unwrap();
+    }
+
+    #[test]
+    fn nearest_link_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([1.0, 1.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+
+        assert_eq!(
+            graph.find_nearest_link_with_radius([0.5, -0.01], 0.05),
+            Some((p0, p1))
+        );
+        assert_eq!(
+            graph.find_nearest_link_with_radius([0.98, 0.5], 0.05),
+            Some((p1, p2))
+        );
+        // Beyond the end of the segment the distance is measured to the end point.
+        assert_eq!(
+            graph.find_nearest_link_with_radius([1.0, 1.04], 0.05),
+            Some((p1, p2))
+        );
+        assert_eq!(graph.find_nearest_link_with_radius([1.0, 1.1], 0.05), None);
+        assert_eq!(graph.find_nearest_link_with_radius([0.5, 0.5], 0.05), None);
+    }
+
+    #[test]
+    fn subgraph_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([1.0, 1.0]);
+        let p3 = graph.add_node([3.0, 0.0]);
+        let p4 = graph.add_node([-1.0, 0.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p1, p3).unwrap();
+        graph.connect_nodes(p3, p4).unwrap();
+        graph.connect_nodes(p4, p0).unwrap();
+        graph.set_node_cost_multiplier(p2, 2.0).unwrap();
+
+        let (mut subgraph, original) = graph.subgraph_within_radius([0.5, 0.0], 1.5);
+        assert_eq!(original, [p0, p1, p2, p4]);
+        let links: Vec<(usize, usize)> = subgraph
+            .links_in_order()
+            .map(|(node1, node2)| (original[node1], original[node2]))
+            .collect();
+        assert_eq!(links, [(p0, p1), (p1, p2), (p4, p0)]);
+        assert_eq!(
+            subgraph.path_cost(&[0, 1, 2]),
+            graph.path_cost(&[p0, p1, p2])
+        );
+        assert_eq!(subgraph.search_graph(3, 2).unwrap(), [3, 0, 1, 2]);
+        assert_eq!(
+            subgraph.get_all_nodes_with_state().nth(3).unwrap().0,
+            [-1.0, 0.0]
+        );
+
+        let (empty, original) = graph.subgraph_within_radius([10.0, 10.0], 1.0);
+        assert!(original.is_empty());
+        assert_eq!(empty.get_all_nodes_with_state().count(), 0);
+    }
+
+    #[test]
+    fn remove_crossing_edges_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([1.0, 1.0]);
+        let p3 = graph.add_node([0.0, 1.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p2, p3).unwrap();
+        graph.connect_nodes(p3, p0).unwrap();
+        graph.connect_nodes(p0, p2).unwrap();
+        assert!(graph.remove_crossing_edges().is_empty());
+
+        // The second diagonal is slightly longer and crosses the first one.
+        let p4 = graph.add_node([1.1, -0.1]);
+        graph.connect_nodes(p3, p4).unwrap();
+        graph.connect_nodes(p4, p1).unwrap();
+        assert_eq!(graph.remove_crossing_edges(), [(p3, p4)]);
+        assert_eq!(graph.links_in_order().count(), 6);
+        assert!(graph.remove_crossing_edges().is_empty());
+
+        // Every cell of a grid has two crossing diagonals of the same length, the earlier one gets removed.
+        let mut graph = grid_graph(4, 4, &[false; 16]);
+        let diagonals = graph.links_in_order().count() - 24;
+        let removed = graph.remove_crossing_edges();
+        assert_eq!(removed.len(), 9);
+        assert_eq!(graph.links_in_order().count(), 24 + diagonals - 9);
+        for (node1, node2) in removed {
+            assert_eq!(node2, node1 + 5);
+        }
+        assert!(graph.remove_crossing_edges().is_empty());
+    }
+
+    #[test]
+    fn merge_collinear_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.001]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let p3 = graph.add_node([2.0, 1.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p2, p3).unwrap();
+        graph.set_edge_clearance(p0, p1, 0.5).unwrap();
+        graph.set_edge_tags(p1, p2, 0b0110).unwrap();
+
+        // The bent triple around p2 is preserved.
+        assert_eq!(graph.merge_collinear(0.01), [p1]);
+        assert_eq!(graph.node_degree(p1), Some(0));
+        assert_eq!(graph.get_edge(p0, p2), Some(2.0));
+        assert_eq!(graph.edge_clearance(p0, p2), Some(0.5));
+        assert_eq!(graph.edge_tags(p0, p2), Some(0b0110));
+        assert_eq!(graph.search_graph(p0, p3).unwrap(), [p0, p2, p3]);
+        assert!(graph.merge_collinear(0.01).is_empty());
+
+        // A triangle may not be merged into a double edge.
+        let mut triangle = NavGraph::new();
+        let q0 = triangle.add_node([0.0, 0.0]);
+        let q1 = triangle.add_node([1.0, 0.0]);
+        let q2 = triangle.add_node([2.0, 0.0]);
+        triangle.connect_nodes(q0, q1).unwrap();
+        triangle.connect_nodes(q1, q2).unwrap();
+        triangle.connect_nodes(q0, q2).unwrap();
+        assert!(triangle.merge_collinear(0.01).is_empty());
+    }
+
+    #[test]
+    fn set_links_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([1.0, 1.0]);
+        let p3 = graph.add_node([0.0, 1.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.set_edge_clearance(p1, p2, 0.5).unwrap();
+        graph.set_edge_tags(p0, p1, 0b01).unwrap();
+
+        graph.set_links(&[(p2, p1), (p2, p3), (p3, p0)]).unwrap();
+        assert_eq!(
+            graph.links_in_order().collect::<Vec<_>>(),
+            [(p2, p1), (p2, p3), (p3, p0)]
+        );
+        assert_eq!(graph.get_edge(p0, p1), None);
+        assert_eq!(graph.get_edge(p3, p2), Some(1.0));
+        assert_eq!(graph.edge_clearance(p1, p2), Some(0.5));
+        assert_eq!(graph.edge_tags(p0, p3), Some(ALL_TAGS));
+        assert_eq!(graph.search_graph(p0, p1).unwrap(), [p0, p3, p2, p1]);
+
+        // A failure leaves the graph unchanged.
+        let before: Vec<(usize, usize)> = graph.links_in_order().collect();
+        assert_eq!(
+            graph.set_links(&[(p0, p1), (p1, 9)]),
+            Err(ConnectionError::NodeDoesntExist(9))
+        );
+        assert_eq!(
+            graph.set_links(&[(p0, p1), (p2, p2)]),
+            Err(ConnectionError::NodeDoubled)
+        );
+        assert_eq!(
+            graph.set_links(&[(p0, p1), (p1, p0)]),
+            Err(ConnectionError::LinkAlreadyExists)
+        );
+        assert_eq!(graph.links_in_order().collect::<Vec<_>>(), before);
+        assert_eq!(graph.node_degree(p0), Some(1));
+
+        graph.set_links(&[]).unwrap();
+        assert_eq!(graph.degree_histogram(), [4]);
+    }
+
+    #[test]
+    fn duplicate_link_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([1.0, 1.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+
+        for (node1, node2) in [(p0, p1), (p1, p0), (p2, p1)] {
+            assert_eq!(
+                graph.connect_nodes(node1, node2),
+                Err(ConnectionError::LinkAlreadyExists)
+            );
+        }
+        assert_eq!(graph.stats().edges, 2);
+        assert_eq!(graph.node_degree(p1), Some(2));
+
+        for links in [&[(p0, p2), (p2, p0)][..], &[(p0, p1), (p1, p2), (p0, p1)]] {
+            assert_eq!(
+                graph.set_links(links),
+                Err(ConnectionError::LinkAlreadyExists)
+            );
+        }
+        assert_eq!(graph.stats().edges, 2);
+        assert_eq!(
+            graph.links_in_order().collect::<Vec<_>>(),
+            [(p0, p1), (p1, p2)]
+        );
+    }
+
+    #[test]
+    fn dedup_edges_test() {
+        // An import without checks, as done by from_petgraph, may leave parallel links.
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([1.0, 1.0]);
+        graph.push_link(p0, p1, 3.0);
+        graph.push_link(p1, p2, 1.0);
+        graph.push_link(p1, p0, 2.0);
+        graph.push_link(p0, p1, 4.0);
+        assert_eq!(graph.find_duplicate_edges(), [(p0, p1)]);
+        assert_eq!(graph.node_degree(p0), Some(3));
+
+        graph.dedup_edges();
+        assert!(graph.find_duplicate_edges().is_empty());
+        assert_eq!(
+            graph.links_in_order().collect::<Vec<_>>(),
+            [(p0, p1), (p1, p2)]
+        );
+        assert_eq!(graph.node_degree(p0), Some(1));
+        assert_eq!(graph.node_degree(p1), Some(2));
+        assert_eq!(graph.get_edge(p0, p1), Some(2.0));
+        assert_eq!(graph.get_edge(p1, p0), Some(2.0));
+        assert_eq!(graph.search_graph(p0, p2).unwrap(), [p0, p1, p2]);
+    }
+
+    #[test]
+    fn from_polylines_test() {
+        let roads = vec![
+            vec![[0.0, 0.0], [1.0, 0.0], [2.0, 0.0]],
+            vec![[2.0, 0.005], [2.0, 1.0], [2.0, 2.0]],
+            vec![[4.0, 0.0], [5.0, 0.0]],
+        ];
+        let mut graph = NavGraph::from_polylines(&roads, 0.01);
+        assert_eq!(graph.stats().nodes, 7);
+        assert_eq!(graph.stats().edges, 5);
+        assert_eq!(graph.stats().components, 2);
+        // The shared end point keeps the position of the first road.
+        assert_eq!(graph.snapshot().positions[2], [2.0, 0.0]);
+        assert_eq!(graph.node_degree(2), Some(2));
+        assert_eq!(graph.search_graph(0, 4).unwrap(), [0, 1, 2, 3, 4]);
+        assert_eq!(graph.search_graph(0, 5), None);
+
+        // Without joining, the roads stay apart.
+        let apart = NavGraph::from_polylines(&roads, 0.001);
+        assert_eq!(apart.stats().nodes, 8);
+        assert_eq!(apart.stats().components, 3);
+
+        // Repeated points and degenerate lines.
+        let graph = NavGraph::from_polylines(
+            &[
+                vec![[0.0, 0.0], [0.0, 0.0], [1.0, 0.0], [1.0, 0.0]],
+                vec![[1.0, 0.0], [0.0, 0.0]],
+                vec![[3.0, 3.0]],
+                vec![],
+            ],
+            0.01,
+        );
+        assert_eq!(graph.stats().nodes, 3);
+        assert_eq!(graph.stats().edges, 1);
+    }
+
+    #[test]
+    fn from_walkability_test() {
+        let bitmap = [
+            "..#...", //
+            "..#...", //
+            "..#.#.", //
+            "..#...", //
+        ];
+        let walkable: Vec<bool> = bitmap
+            .iter()
+            .flat_map(|row| row.chars().map(|cell| cell == '.'))
+            .collect();
+
+        let mut graph = NavGraph::from_walkability(6, 4, &walkable, 0.5, DiagonalMoves::Forbidden);
+        let stats = graph.stats();
+        assert_eq!(stats.nodes, 19);
+        assert_eq!(stats.components, 2);
+        assert_eq!(stats.bounding_box, Some(([0.0, 0.0], [2.5, 1.5])));
+        let left = graph
+            .find_nearest_node_with_radius([0.0, 0.0], 0.1)
+            .unwrap();
+        let right = graph
+            .find_nearest_node_with_radius([2.5, 1.5], 0.1)
+            .unwrap();
+        assert!(graph.search_graph(left, right).is_none());
+        let corner = graph
+            .find_nearest_node_with_radius([0.5, 1.5], 0.1)
+            .unwrap();
+        assert_eq!(graph.search_graph(left, corner).unwrap().len(), 5);
+
+        let mut diagonal =
+            NavGraph::from_walkability(6, 4, &walkable, 0.5, DiagonalMoves::NoCornerCutting);
+        assert_eq!(diagonal.stats().components, 2);
+        // Diagonals do not cut the corners of walls, so only the three free blocks of 2x2 cells on the left
+        // and the two on the right get their two diagonals.
+        assert_eq!(diagonal.stats().edges, graph.stats().edges + 2 * 5);
+        let corner = diagonal
+            .find_nearest_node_with_radius([0.5, 1.5], 0.1)
+            .unwrap();
+        assert_eq!(diagonal.search_graph(left, corner).unwrap().len(), 4);
+        assert_eq!(
+            NavGraph::from_walkability(3, 3, &[true; 4], 1.0, DiagonalMoves::NoCornerCutting)
+                .stats()
+                .nodes,
+            4
+        );
+    }
+
+    #[test]
+    fn octile_search_test() {
+        // The diagonal from the top left to the bottom right cell cuts the corner of the wall.
+        let walkable = [true, false, true, true];
+        let mut graph =
+            NavGraph::from_walkability(2, 2, &walkable, 2.0, DiagonalMoves::NoCornerCutting);
+        assert_eq!(graph.search_grid_octile(0, 2).unwrap(), [0, 1, 2]);
+        let mut cutting =
+            NavGraph::from_walkability(2, 2, &walkable, 2.0, DiagonalMoves::AllowCornerCutting);
+        assert_eq!(cutting.search_grid_octile(0, 2).unwrap(), [0, 2]);
+        let straight = NavGraph::from_walkability(2, 2, &walkable, 2.0, DiagonalMoves::Forbidden);
+        assert_eq!(straight.stats().edges, 2);
+
+        // The diagonal from the top left to the center cell passes between two walls.
+        let walkable = [true, false, true, false, true, true, true, true, true];
+        let mut graph =
+            NavGraph::from_walkability(3, 3, &walkable, 2.0, DiagonalMoves::NoCornerCutting);
+        assert!(graph.search_grid_octile(0, 6).is_none());
+        let mut cutting =
+            NavGraph::from_walkability(3, 3, &walkable, 2.0, DiagonalMoves::AllowCornerCutting);
+        assert_eq!(cutting.search_grid_octile(0, 6).unwrap(), [0, 2, 6]);
+        assert_eq!(cutting.stats().edges, graph.stats().edges + 3);
+
+        // On open and cluttered grids, the octile search is optimal and expands fewer nodes.
+        let mut blocked = vec![false; 400];
+        for index in [
+            45, 65, 85, 105, 125, 126, 127, 128, 250, 251, 252, 271, 292, 313,
+        ] {
+            blocked[index] = true;
+        }
+        let walkable: Vec<bool> = blocked.iter().map(|flag| !flag).collect();
+        for diagonal in [
+            DiagonalMoves::NoCornerCutting,
+            DiagonalMoves::AllowCornerCutting,
+        ] {
+            let mut graph = NavGraph::from_walkability(20, 20, &walkable, 0.5, diagonal);
+            let count = graph.stats().nodes;
+            let mut octile_expanded = 0;
+            let mut euclid_expanded = 0;
+            for (start, destination) in [(0, count - 1), (7, count - 30), (count - 1, 3)] {
+                let expected = graph.search_graph(start, destination).unwrap();
+                euclid_expanded += graph.closed_nodes().count();
+                let path = graph.search_grid_octile(start, destination).unwrap();
+                octile_expanded += graph.closed_nodes().count();
+                let difference =
+                    graph.path_cost(&path).unwrap() - graph.path_cost(&expected).unwrap();
+                assert!(difference.abs() < 0.0001);
+            }
+            assert!(octile_expanded < euclid_expanded);
+        }
+    }
+
+    #[test]
+    fn alt_search_test() {
+        // A wall with a gap at the bottom, so the straight-line distance underestimates a lot.
+        let mut blocked = vec![false; 400];
+        for y in 0..18 {
+            blocked[y * 20 + 10] = true;
+        }
+        let mut graph = grid_graph(20, 20, &blocked);
+        graph.set_heuristic_check(true);
+        let landmarks = graph.select_landmarks(4);
+        assert_eq!(landmarks.len(), 4);
+        assert_eq!(
+            landmarks[0], 19,
+            "The corner farthest from node 0 behind the wall."
+        );
+
+        let mut alt_expanded = 0;
+        let mut euclid_expanded = 0;
+        for (start, destination) in [(0, 19), (45, 52), (180, 399), (21, 378)] {
+            let expected = graph.search_graph(start, destination).unwrap();
+            euclid_expanded += graph.closed_nodes().count();
+            let path = graph.search_graph_alt(start, destination).unwrap();
+            alt_expanded += graph.closed_nodes().count();
+            let difference = graph.path_cost(&path).unwrap() - graph.path_cost(&expected).unwrap();
+            assert!(difference.abs() < 0.0001);
+        }
+        assert!(alt_expanded < euclid_expanded);
+
+        // Once the graph is modified, the landmarks are not used any more.
+        graph.disconnect_nodes(370, 371).unwrap();
+        let expected = graph.search_graph(0, 19).unwrap();
+        let euclid_expanded = graph.closed_nodes().count();
+        assert_eq!(graph.search_graph_alt(0, 19).unwrap(), expected);
+        assert_eq!(graph.closed_nodes().count(), euclid_expanded);
+
+        assert_eq!(NavGraph::new().select_landmarks(3), []);
+        let mut tiny = NavGraph::new();
+        tiny.add_node([0.0, 0.0]);
+        tiny.add_node([1.0, 0.0]);
+        assert_eq!(tiny.select_landmarks(5), [0, 1]);
+    }
+
+    #[test]
+    fn path_headings_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([3.0, 3.0]);
+        let p2 = graph.add_node([5.0, 1.0]);
+        let p3 = graph.add_node([5.0, 1.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p2, p3).unwrap();
+
+        let headings = graph.path_headings(&[p0, p1, p2]);
+        assert_eq!(headings.len(), 3);
+        let incoming = Vec2::from(headings[0]);
+        let outgoing = Vec2::from(headings[1]);
+        assert!((incoming.magnitude() - 1.0).abs() < 0.00001);
+        assert!((outgoing.magnitude() - 1.0).abs() < 0.00001);
+        assert!((incoming.angle_to(&outgoing) - std::f32::consts::FRAC_PI_2).abs() < 0.00001);
+        assert_eq!(
+            headings[2], headings[1],
+            "The last node keeps the incoming direction."
+        );
+
+        assert_eq!(graph.path_headings(&[p2, p3]), [[0.0, 0.0], [0.0, 0.0]]);
+        assert_eq!(graph.path_headings(&[p1]), [[0.0, 0.0]]);
+        assert!(graph.path_headings(&[]).is_empty());
+        assert!(graph.path_headings(&[p0, 7]).is_empty());
+    }
+
+    #[test]
+    fn cost_to_test() {
+        let mut blocked = vec![false; 100];
+        for y in 0..8 {
+            blocked[y * 10 + 5] = true;
+        }
+        let mut graph = grid_graph(10, 10, &blocked);
+        let path = graph.search_graph(0, 9).unwrap();
+        let cost = graph.cost_to(9).unwrap();
+        assert!((cost - graph.path_cost(&path).unwrap()).abs() < 0.0001);
+        assert_eq!(graph.cost_to(0), Some(0.0));
+        for node in path.iter() {
+            assert!(graph.cost_to(*node).unwrap() <= cost);
+        }
+        assert_eq!(graph.cost_to(5), None, "A blocked cell is never reached.");
+        assert_eq!(graph.cost_to(100), None);
+
+        graph.clear_search_state();
+        assert_eq!(graph.cost_to(0), None);
+    }
+
+    #[test]
+    fn with_capacity_test() {
+        let mut graph = NavGraph::with_capacity(100, 180);
+        let node_capacity = graph.nodes.capacity();
+        let link_capacity = graph.links.capacity();
+        assert!(node_capacity >= 100 && link_capacity >= 180);
+        for y in 0..10 {
+            for x in 0..10 {
+                graph.add_node([x as f32, y as f32]);
+            }
+        }
+        for y in 0..10 {
+            for x in 0..9 {
+                graph.connect_nodes(y * 10 + x, y * 10 + x + 1).unwrap();
+                graph.connect_nodes(x * 10 + y, x * 10 + y + 10).unwrap();
+            }
+        }
+        assert_eq!(graph.stats().edges, 180);
+        assert_eq!(graph.nodes.capacity(), node_capacity);
+        assert_eq!(graph.links.capacity(), link_capacity);
+    }
+
+    #[test]
+    fn search_via_test() {
+        let mut graph = grid_graph(6, 6, &[false; 36]);
+        let direct = graph.search_graph(0, 5).unwrap();
+        let direct_cost = graph.path_cost(&direct).unwrap();
+        let (path, cost) = graph.search_via(0, &[30, 35], 5).unwrap();
+        assert!(cost > direct_cost);
+        assert!((cost - graph.path_cost(&path).unwrap()).abs() < 0.0001);
+        assert!(graph.is_valid_path(&path));
+        let first = path.iter().position(|node| *node == 30).unwrap();
+        let second = path.iter().position(|node| *node == 35).unwrap();
+        assert!(0 < first && first < second && second < path.len() - 1);
+        assert_eq!(path[0], 0);
+        assert_eq!(*path.last().unwrap(), 5);
+        assert!(
+            path.windows(2).all(|pair| pair[0] != pair[1]),
+            "No duplicated junctions."
+        );
+
+        let (path, cost) = graph.search_via(0, &[], 5).unwrap();
+        assert_eq!(path, direct);
+        assert!((cost - direct_cost).abs() < 0.0001);
+
+        graph.add_node([10.0, 10.0]);
+        assert!(graph.search_via(0, &[36], 5).is_none());
+    }
+
+    #[test]
+    fn plan_tour_test() {
+        let mut blocked = vec![false; 64];
+        for y in 1..8 {
+            blocked[y * 8 + 4] = true;
+        }
+        let mut graph = grid_graph(8, 8, &blocked);
+        let waypoints = [63, 9, 58, 14, 3];
+        let (path, cost) = graph.plan_tour(0, &waypoints).unwrap();
+        assert_eq!(path[0], 0);
+        assert!(graph.is_valid_path(&path));
+        assert!((cost - graph.path_cost(&path).unwrap()).abs() < 0.0001);
+        for waypoint in waypoints.iter() {
+            assert!(path.contains(waypoint));
+        }
+        // The nearest neighbor order only returns through the gap in the wall for the last waypoint.
+        let mut order = waypoints.to_vec();
+        order.sort_by_key(|waypoint| path.iter().position(|node| node == waypoint).unwrap());
+        assert_eq!(order, [9, 3, 14, 63, 58]);
+        assert_eq!(*path.last().unwrap(), 58);
+
+        assert_eq!(graph.plan_tour(0, &[]).unwrap(), (vec![0], 0.0));
+        graph.add_node([20.0, 20.0]);
+        assert!(graph.plan_tour(0, &[9, 64]).is_none());
+    }
+
+    #[test]
+    fn kd_tree_test() {
+        // Dense clusters of nodes with a few scattered ones in between.
+        let mut graph = NavGraph::new();
+        let centers = [[0.0, 0.0], [10.0, 2.0], [3.0, 9.0]];
+        for index in 0..900 {
+            let center = centers[index % 3];
+            let angle = (index * 7919 % 1000) as f32 * 0.00628;
+            let radius = (index * 104729 % 1000) as f32 * 0.0005;
+            graph.add_node([
+                center[0] + radius * angle.cos(),
+                center[1] + radius * angle.sin(),
+            ]);
+        }
+        for index in 0..30 {
+            graph.add_node([(index % 6) as f32 * 2.0, (index / 6) as f32 * 2.5]);
+        }
+        graph.add_node([0.0, 0.0]);
+
+        let probes = [
+            [0.0, 0.0],
+            [0.1, -0.2],
+            [10.3, 2.1],
+            [5.0, 5.0],
+            [3.0, 9.4],
+            [-4.0, 12.0],
+            [20.0, -3.0],
+        ];
+        let brute_force: Vec<_> = probes
+            .iter()
+            .map(|probe| {
+                (
+                    graph.find_nearest_node_with_radius(*probe, 1.0),
+                    graph.find_k_nearest_nodes(*probe, 12),
+                    graph.nodes_within_radius(*probe, 0.3),
+                )
+            })
+            .collect();
+        graph.build_kdtree();
+        assert!(graph.current_kd_tree().is_some());
+        for (probe, expected) in probes.iter().zip(brute_force.iter()) {
+            assert_eq!(graph.find_nearest_node_with_radius(*probe, 1.0), expected.0);
+            assert_eq!(graph.find_k_nearest_nodes(*probe, 12), expected.1);
+            assert_eq!(graph.nodes_within_radius(*probe, 0.3), expected.2);
+        }
+        assert_eq!(
+            graph.find_nearest_node_with_radius([0.0, 0.0], 0.0),
+            Some(0)
+        );
+        assert_eq!(graph.find_k_nearest_nodes([0.0, 0.0], 3), [0, 900, 930]);
+        assert_eq!(graph.find_k_nearest_nodes([0.0, 0.0], 5000).len(), 931);
+
+        // Links do not invalidate the tree, but moving or adding nodes does.
+        graph.connect_nodes(0, 1).unwrap();
+        assert!(graph.current_kd_tree().is_some());
+        graph.set_node_position(0, [50.0, 50.0]).unwrap();
+        assert!(graph.current_kd_tree().is_none());
+        assert_eq!(
+            graph.find_nearest_node_with_radius([50.0, 50.0], 0.1),
+            Some(0)
+        );
+        graph.build_kdtree();
+        let added = graph.add_node([60.0, 60.0]);
+        assert!(graph.current_kd_tree().is_none());
+        assert_eq!(graph.find_k_nearest_nodes([59.0, 59.0], 1), [added]);
+    }
+
+    #[test]
+    fn add_node_dedup_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node_dedup([1.0, 1.0], 0.01);
+        let generation = graph.generation();
+        let p1 = graph.add_node_dedup([1.0, 1.004], 0.01);
+        assert_eq!(p0, p1);
+        assert_eq!(graph.stats().nodes, 1);
+        assert_eq!(
+            graph.generation(),
+            generation,
+            "The graph remains unchanged."
+        );
+
+        let p2 = graph.add_node_dedup([1.0, 1.02], 0.01);
+        assert_ne!(p2, p0);
+        assert_eq!(
+            graph.add_node_dedup([1.0, 1.012], 0.01),
+            p2,
+            "The nearest node is taken."
+        );
+        assert_eq!(graph.add_node_dedup([1.0, 1.0], 0.0), p0);
+        let p3 = graph.add_node_dedup([1.0, 1.0], -1.0);
+        assert_eq!(graph.stats().nodes, 3);
+        assert_eq!(graph.nodes[p3].position, Vec2::new(1.0, 1.0));
+    }
+
+    #[test]
+    fn open_set_snapshot_test() {
+        let mut blocked = vec![false; 64];
+        for y in 0..6 {
+            blocked[y * 8 + 3] = true;
+        }
+        let graph = grid_graph(8, 8, &blocked);
+        let mut session = graph.begin_search(16, 23);
+        assert_eq!(session.open_set_snapshot(), [(16, 7.0)]);
+        let mut expanded = 0;
+        loop {
+            let snapshot = session.open_set_snapshot();
+            assert_eq!(snapshot.len(), session.frontier().len());
+            assert!(snapshot.windows(2).all(|pair| pair[0].1 <= pair[1].1));
+            let (next, f_value) = snapshot[0];
+            assert!(snapshot.iter().all(|(_, other)| f_value <= *other));
+            let status = session.step();
+            assert_ne!(session.node_state(next), Some(&NodeState::Visited));
+            assert!(
+                !session.frontier().contains(&next),
+                "The minimum gets expanded."
+            );
+            expanded += 1;
+            if status != SearchStatus::Running {
+                assert!(matches!(status, SearchStatus::Found(_)));
+                break;
+            }
+        }
+        assert!(expanded > 10);
+    }
+
+    #[test]
+    fn spatial_grid_extent_test() {
+        let quadrants = [[1.0, 1.0], [-1.0, 1.0], [-1.0, -1.0], [1.0, -1.0]];
+        for scale in [0.001, 1.0, 1.0e6, 1.0e15] {
+            let mut graph = NavGraph::new();
+            for (index, [x, y]) in quadrants.iter().enumerate() {
+                for step in 1..6 {
+                    let offset = step as f32 * (index + 1) as f32;
+                    graph.add_node([x * offset * scale, y * step as f32 * scale]);
+                }
+            }
+            graph.add_node([-0.0, 0.0]);
+            let frozen = NavGraph {
+                nodes: graph.nodes.clone(),
+                ..NavGraph::new()
+            }
+            .freeze();
+            assert!(frozen.cells.len() <= frozen.node_count());
+            for [x, y] in quadrants.iter() {
+                for step in [0.3, 1.2, 4.7, 30.0] {
+                    let position = [x * step * scale, y * step * 0.8 * scale];
+                    for radius in [0.5 * scale, 3.0 * scale, f32::MAX] {
+                        assert_eq!(
+                            frozen.find_nearest_node_with_radius(position, radius),
+                            graph.find_nearest_node_with_radius(position, radius),
+                            "position {position:?} radius {radius}"
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn shortest_path_tree_test() {
+        let mut blocked = vec![false; 81];
+        for index in [3, 12, 21, 30, 39, 48, 50, 51, 52, 53] {
+            blocked[index] = true;
+        }
+        let mut graph = grid_graph(9, 9, &blocked);
+        graph.set_node_cost_multiplier(40, 5.0).unwrap();
+        graph.add_node([20.0, 20.0]);
+        let source = 9;
+        let tree = graph.shortest_path_tree(source);
+        let distances = graph.dijkstra_from(source);
+        assert_eq!(tree[source], Some(source));
+        assert_eq!(tree[81], None);
+        assert_eq!(tree[3], None, "Blocked cells are not reachable.");
+
+        for destination in [0, 8, 44, 62, 80, 72] {
+            let mut path = vec![destination];
+            while let Some(parent) = tree[*path.last().unwrap()]
+                && parent != *path.last().unwrap()
+            {
+                path.push(parent);
+            }
+            path.reverse();
+            assert_eq!(path[0], source);
+            assert!(graph.is_valid_path(&path));
+            let searched = graph.search_graph(source, destination).unwrap();
+            let cost = |path: &[usize]| -> f32 {
+                path.windows(2)
+                    .map(|pair| {
+                        graph.traversal_cost(
+                            pair[0],
+                            pair[1],
+                            graph.get_edge(pair[0], pair[1]).unwrap(),
+                        )
+                    })
+                    .sum()
+            };
+            assert!((cost(&path) - cost(&searched)).abs() < 0.0001);
+            assert!((cost(&path) - distances[destination].unwrap()).abs() < 0.0001);
+        }
+    }
+
+    #[test]
+    fn nearest_cached_test() {
+        let mut graph = grid_graph(12, 12, &[false; 144]);
+        graph.add_node([4.0, 4.0]);
+        let mut cache = NearestQueryCache::new();
+        let mut position = [0.3_f32, 0.2_f32];
+        let mut queries = 0;
+        while position[0] < 11.0 {
+            for radius in [0.2, 0.6] {
+                assert_eq!(
+                    graph.find_nearest_node_cached(position, radius, &mut cache),
+                    graph.find_nearest_node_with_radius(position, radius),
+                    "position {position:?}"
+                );
+                queries += 1;
+            }
+            position = [position[0] + 0.01, position[1] + 0.007];
+        }
+        assert!(
+            cache.checked < queries * 145 / 5,
+            "Most queries use the cached node."
+        );
+
+        // Moving nodes invalidates the cache.
+        let nearest = graph.find_nearest_node_cached([5.1, 5.1], 1.0, &mut cache);
+        assert_eq!(nearest, Some(65));
+        graph.set_node_position(12, [5.2, 5.2]).unwrap();
+        assert_eq!(
+            graph.find_nearest_node_cached([5.1, 5.1], 1.0, &mut cache),
+            Some(12)
+        );
+
+        graph.build_kdtree();
+        assert_eq!(
+            graph.find_nearest_node_cached([4.0, 4.05], 1.0, &mut cache),
+            Some(52)
+        );
+        assert_eq!(
+            graph.find_nearest_node_cached([4.0, 3.95], 1.0, &mut cache),
+            Some(52)
+        );
+        assert_eq!(
+            NavGraph::new().find_nearest_node_cached([0.0, 0.0], 1.0, &mut cache),
+            None
+        );
+    }
+
+    #[test]
+    fn path_between_points_test() {
+        let (mut graph, [p0, _, _, p3, _]) = readme_graph();
+
+        let expected = graph.search_graph(p0, p3).unwrap();
+        let path = graph
+            .path_between_points([-0.02, 0.01], [1.03, 0.98], 0.05)
+            .unwrap();
+        assert_eq!(path.len(), expected.len() + 2);
+        assert_eq!(path[0], [-0.02, 0.01]);
+        assert_eq!(*path.last().unwrap(), [1.03, 0.98]);
+        for (position, node) in path[1..path.len() - 1].iter().zip(expected.iter()) {
+            assert_eq!(Vec2::from(*position), graph.nodes[*node].position);
+        }
+
+        let exact = graph
+            .path_between_points([0.5, 0.5], [0.1, 0.0], 0.05)
+            .unwrap();
+        assert_eq!(exact, [[0.5, 0.5], [0.1, 0.0]]);
+        // The snapping takes the node nearest to the point.
+        let near = graph
+            .path_between_points([0.07, 0.0], [0.07, 0.0], 0.1)
+            .unwrap();
+        assert_eq!(near, [[0.07, 0.0], [0.1, 0.0], [0.07, 0.0]]);
+        assert!(
+            graph
+                .path_between_points([3.0, 3.0], [1.0, 1.0], 0.05)
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn split_edge_test() {
+        let mut graph = NavGraph::new();
+
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([0.5, 1.0]);
+
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p0, p2).unwrap();
+        graph.connect_nodes(p2, p1).unwrap();
+
+        let p3 = graph.split_edge(p0, p1, [0.25, 0.0]).unwrap();
+        assert_eq!(graph.search_graph(p0, p1).unwrap(), [p0, p3, p1]);
+        assert_eq!(
+            graph.split_edge(p0, p1, [0.5, 0.0]),
+            Err(ConnectionError::LinkDoesntExist),
+            "The edge has already been split."
+        );
+
+        let costs: Vec<f32> = graph.nodes[p3]
+            .connections
+            .iter()
+            .map(|(_, cost)| *cost)
+            .collect();
+        assert_eq!(costs, [0.25, 0.75]);
+    }
+
+    #[test]
+    fn stats_test() {
+        let empty = NavGraph::new().stats();
+        assert_eq!(
+            empty,
+            GraphStats {
+                nodes: 0,
+                edges: 0,
+                components: 0,
+                avg_degree: 0.0,
+                min_edge: 0.0,
+                max_edge: 0.0,
+                mean_edge: 0.0,
+                bounding_box: None,
+            }
+        );
+
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([3.0, 0.0]);
+        let p2 = graph.add_node([3.0, 4.0]);
+        let p3 = graph.add_node([-1.0, 5.0]);
+        let p4 = graph.add_node([-2.0, 5.0]);
+        graph.add_node([1.0, -1.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p2).unwrap();
+        graph.connect_nodes(p3, p4).unwrap();
+
+        let stats = graph.stats();
+        assert_eq!(stats.nodes, 6);
+        assert_eq!(stats.edges, 4);
+        assert_eq!(stats.components, 3);
+        assert_eq!(stats.avg_degree, 8.0 / 6.0);
+        assert_eq!(stats.min_edge, 1.0);
+        assert_eq!(stats.max_edge, 5.0);
+        assert_eq!(stats.mean_edge, 13.0 / 4.0);
+        assert_eq!(stats.bounding_box, Some(([-2.0, -1.0], [3.0, 5.0])));
+    }
+
+    #[test]
+    fn memory_footprint_test() {
+        let mut graph = NavGraph::new();
+        let empty = graph.memory_footprint();
+        assert_eq!(empty, size_of::<NavGraph>());
+
+        let small = grid_graph(3, 3, &[false; 9]);
+        let large = grid_graph(30, 30, &[false; 900]);
+        assert!(small.memory_footprint() > empty);
+        assert!(large.memory_footprint() > 50 * small.memory_footprint());
+        let minimum = 900 * size_of::<NavNode>()
+            + 2 * large.links.len() * size_of::<(usize, f32)>()
+            + large.links.len() * size_of::<(usize, usize)>();
+        assert!(large.memory_footprint() >= minimum);
+
+        // Reserved capacity counts, even before any node is added.
+        graph.nodes.reserve(100);
+        assert!(graph.memory_footprint() >= empty + 100 * size_of::<NavNode>());
+
+        let before = graph.memory_footprint();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.search_graph(p0, p1);
+        assert!(graph.memory_footprint() > before);
+
+        let before = graph.memory_footprint();
+        graph.search_graph_cached(p0, p1);
+        assert!(
+            graph.memory_footprint() >= before + size_of::<CachedPath>() + 2 * size_of::<usize>()
+        );
+    }
+
+    #[test]
+    fn solution_nodes_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([0.5, 0.5]);
+        let p2 = graph.add_node([1.0, 0.0]);
+        let p3 = graph.add_node([1.0, 1.0]);
+        let p4 = graph.add_node([0.1, 0.0]);
+        graph.connect_nodes(p3, p2).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p4).unwrap();
+        graph.connect_nodes(p4, p3).unwrap();
+        assert!(graph.solution_nodes().is_empty());
+
+        for (start, destination) in [(p3, p0), (p0, p3), (p4, p2), (p2, p2)] {
+            let path = graph.search_graph(start, destination).unwrap();
+            assert_eq!(graph.solution_nodes(), path);
+        }
+
+        let p5 = graph.add_node([5.0, 5.0]);
+        assert!(graph.search_graph(p0, p5).is_none());
+        assert!(graph.solution_nodes().is_empty());
+    }
+
+    #[test]
+    fn next_hop_test() {
+        let (mut graph, [p0, _, _, p3, p4]) = readme_graph();
+        assert_eq!(graph.next_hop(p0), None);
+
+        let path = graph.search_graph(p0, p3).unwrap();
+        let mut followed = vec![p0];
+        while let Some(next) = graph.next_hop(followed[followed.len() - 1]) {
+            followed.push(next);
+        }
+        assert_eq!(followed, path);
+        assert_eq!(followed[followed.len() - 1], p3);
+        assert_eq!(graph.next_hop(p4), None);
+        assert_eq!(graph.next_hop(10), None);
+
+        graph.search_graph(p0, p0);
+        assert_eq!(graph.next_hop(p0), None);
+    }
+
+    #[test]
+    fn solution_edge_indices_test() {
+        let (mut graph, [p0, _, _, p3, _]) = readme_graph();
+
+        assert!(graph.solution_edge_indices().is_empty());
+        let path = graph.search_graph(p0, p3).unwrap();
+        let indices = graph.solution_edge_indices();
+        assert_eq!(indices.len(), path.len() - 1);
+        let links: Vec<(usize, usize)> = graph.links_in_order().collect();
+        for (index, pair) in indices.iter().zip(path.windows(2)) {
+            let (node1, node2) = links[*index];
+            assert!((node1, node2) == (pair[0], pair[1]) || (node2, node1) == (pair[0], pair[1]));
+        }
+
+        graph.search_graph(p0, p0);
+        assert!(graph.solution_edge_indices().is_empty());
+        let p5 = graph.add_node([3.0, 3.0]);
+        graph.search_graph(p0, p5);
+        assert!(graph.solution_edge_indices().is_empty());
+    }
+
+    #[test]
+    fn iteration_order_test() {
+        let mut graph = NavGraph::new();
+        let positions = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        for position in positions {
+            graph.add_node(position);
+        }
+
+        let node_positions: Vec<[f32; 2]> = graph
+            .get_all_nodes_with_state()
+            .map(|(pos, _)| pos)
+            .collect();
+        assert_eq!(node_positions, positions);
+
+        let sequence = [(2, 3), (0, 1), (3, 0), (1, 2)];
+        for (first, second) in sequence {
+            graph.connect_nodes(first, second).unwrap();
+        }
+        assert!(graph.links_in_order().eq(sequence));
+
+        let link_positions: Vec<([f32; 2], [f32; 2])> = graph
+            .get_all_links_with_solution_hint()
+            .map(|(start, end, _)| (start, end))
+            .collect();
+        let expected: Vec<([f32; 2], [f32; 2])> = sequence
+            .iter()
+            .map(|(first, second)| (positions[*first], positions[*second]))
+            .collect();
+        assert_eq!(link_positions, expected);
+
+        graph.disconnect_nodes(0, 1).unwrap();
+        assert!(graph.links_in_order().eq([(2, 3), (3, 0), (1, 2)]));
+    }
+
+    #[test]
+    fn edges_test() {
+        let mut graph = grid_graph(3, 3, &[false; 9]);
+        let far = graph.add_node([7.0, -2.0]);
+        graph.connect_nodes(far, 4).unwrap();
+
+        let edges: Vec<(usize, usize, f32)> = graph.edges().collect();
+        assert_eq!(edges.len(), graph.links_in_order().count());
+        for ((node1, node2, cost), link) in edges.iter().zip(graph.links_in_order()) {
+            assert_eq!((*node1, *node2), link);
+            let dist = graph.nodes[*node1]
+                .position
+                .dist_to(&graph.nodes[*node2].position);
+            assert_eq!(*cost, dist);
+        }
+
+        // The stored cost stays until it gets recomputed.
+        graph.set_node_position(far, [4.0, 1.0]).unwrap();
+        assert_eq!(graph.edges().last(), Some((far, 4, 6.0_f32.hypot(3.0))));
+        graph.recompute_edge_costs();
+        assert_eq!(graph.edges().last(), Some((far, 4, 3.0)));
+    }
+
+    #[test]
+    fn get_edge_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([3.0, 4.0]);
+        let p2 = graph.add_node([3.0, 0.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+
+        assert_eq!(graph.get_edge(p0, p1), Some(5.0));
+        assert_eq!(graph.get_edge(p1, p0), Some(5.0));
+        assert_eq!(graph.get_edge(p2, p1), Some(4.0));
+        assert_eq!(graph.get_edge(p0, p2), None);
+        assert_eq!(graph.get_edge(p0, p0), None);
+        assert_eq!(graph.get_edge(p0, 7), None);
+        assert_eq!(graph.get_edge(7, p0), None);
+
+        graph.disconnect_nodes(p1, p0).unwrap();
+        assert_eq!(graph.get_edge(p0, p1), None);
+    }
+
+    #[test]
+    fn edge_geometry_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([-1.0, 2.0]);
+        let p1 = graph.add_node([3.0, 2.0]);
+        let p2 = graph.add_node([3.0, 2.0]);
+        let p3 = graph.add_node([0.0, -2.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p3, p1).unwrap();
+
+        assert_eq!(graph.edge_geometry(p0, p1), Some(([1.0, 2.0], [1.0, 0.0])));
+        assert_eq!(graph.edge_geometry(p1, p0), Some(([1.0, 2.0], [-1.0, 0.0])));
+        assert_eq!(
+            graph.edge_geometry(p1, p3),
+            Some(([1.5, 0.0], [-0.6, -0.8]))
+        );
+        assert_eq!(
+            graph.edge_geometry(p1, p2),
+            Some(([3.0, 2.0], [0.0, 0.0])),
+            "Coincident nodes have no direction."
+        );
+        assert_eq!(graph.edge_geometry(p0, p3), None);
+        assert_eq!(graph.edge_geometry(p0, 9), None);
+    }
+
+    #[test]
+    fn path_validation_test() {
+        let mut graph = NavGraph::new();
+
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([1.0, 1.0]);
+        let p3 = graph.add_node([0.0, 1.0]);
+
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p2, p3).unwrap();
+
+        let path = graph.search_graph(p0, p3).unwrap();
+        assert!(graph.is_valid_path(&path));
+        assert!(graph.is_valid_path(&[p3, p2, p1]));
+        assert!(graph.is_valid_path(&[p2]));
+
+        assert!(!graph.is_valid_path(&[p0, p1, p3]), "Non adjacent pair.");
+        assert!(!graph.is_valid_path(&[p0, 17]), "Index out of range.");
+
+        graph.disconnect_nodes(p1, p2).unwrap();
+        assert!(!graph.is_valid_path(&path), "Path broken by the change.");
+    }
+
+    #[test]
+    fn path_cost_test() {
+        let (mut graph, [p0, p1, _, p3, p4]) = readme_graph();
+
+        let result = graph.search_graph(p0, p3).unwrap();
+        assert_eq!(result, [0, 2, 3]);
+        assert_eq!(graph.path_cost(&result), Some(2.0));
+
+        let detour = graph.path_cost(&[p0, p1, p4, p3]).unwrap();
+        assert!(detour > 2.0, "The detour should be more expensive.");
+
+        assert_eq!(graph.path_cost(&[p0, p3]), None);
+        assert_eq!(graph.path_cost(&[p3]), Some(0.0));
+    }
+
+    #[test]
+    fn position_along_path_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([2.0, 0.0]);
+        let p2 = graph.add_node([2.0, 1.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        let path = [p0, p1, p2];
+
+        let position = graph.position_along_path(&path, 2.25).unwrap();
+        assert!(Vec2::from(position).approx_eq(&Vec2::new(2.0, 0.25), 0.00001));
+        assert_eq!(graph.position_along_path(&path, -1.0), Some([0.0, 0.0]));
+        assert_eq!(graph.position_along_path(&path, 10.0), Some([2.0, 1.0]));
+        assert_eq!(graph.position_along_path(&[p1], 1.0), Some([2.0, 0.0]));
+        assert_eq!(graph.position_along_path(&[], 1.0), None);
+        assert_eq!(graph.position_along_path(&[p0, 7], 1.0), None);
+    }
+
+    #[test]
+    fn smooth_path_on_graph_test() {
+        let mut graph = grid_graph(4, 1, &[false; 4]);
+        let p4 = graph.add_node([1.5, 1.0]);
+        graph.connect_nodes(1, p4).unwrap();
+        graph.connect_nodes(p4, 3).unwrap();
+
+        // Without a shortcut edge, all waypoints are kept.
+        let path = [0, 1, p4, 3];
+        assert_eq!(graph.smooth_path_on_graph(&path), path);
+        assert_eq!(graph.smooth_path_on_graph(&[0, 1, 2, 3]), [0, 1, 2, 3]);
+
+        // With a shortcut edge, the waypoints in between are removed.
+        graph.connect_nodes(0, 3).unwrap();
+        assert_eq!(graph.smooth_path_on_graph(&path), [0, 3]);
+        graph.connect_nodes(1, 3).unwrap();
+        assert_eq!(graph.smooth_path_on_graph(&[p4, 1, 2, 3]), [p4, 3]);
+        graph.disconnect_nodes(0, 3).unwrap();
+        let smoothed = graph.smooth_path_on_graph(&path);
+        assert_eq!(smoothed, [0, 1, 3]);
+        assert!(graph.is_valid_path(&smoothed));
+
+        assert!(graph.smooth_path_on_graph(&[]).is_empty());
+        assert_eq!(graph.smooth_path_on_graph(&[2]), [2]);
+    }
+
+    #[test]
+    fn funnel_path_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([2.0, 0.0]);
+        let p2 = graph.add_node([2.0, 2.0]);
+        let p3 = graph.add_node([4.0, 2.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p2, p3).unwrap();
+
+        // The funnel cuts the corner of the L shaped corridor at the inner end of the portal.
+        let refined = graph.funnel_path(&[p0, p1, p2], 0.2);
+        let inner_corner = Vec2::new(2.0 - 0.2 * 0.5_f32.sqrt(), 0.2 * 0.5_f32.sqrt());
+        assert_eq!(refined.len(), 3);
+        assert_eq!(refined[0], [0.0, 0.0]);
+        assert!(Vec2::from(refined[1]).approx_eq(&inner_corner, 0.0001));
+        assert_eq!(refined[2], [2.0, 2.0]);
+        assert!(path_length(&refined) < 4.0);
+
+        // With a wide corridor the path becomes straight, in a narrow one it stays close to the nodes.
+        assert_eq!(
+            graph.funnel_path(&[p0, p1, p2, p3], 2.0),
+            [[0.0, 0.0], [4.0, 2.0]]
+        );
+        assert_eq!(graph.funnel_path(&[p0, p1, p2, p3], 0.01).len(), 4);
+        assert_eq!(graph.funnel_path(&[p0, p1], 0.2), [[0.0, 0.0], [2.0, 0.0]]);
+        assert!(graph.funnel_path(&[p0, 9], 0.2).is_empty());
+    }
+
+    #[test]
+    fn path_length_test() {
+        assert_eq!(path_length(&[]), 0.0);
+        assert_eq!(path_length(&[[1.0, 1.0]]), 0.0);
+        let polyline = [[0.0, 0.0], [3.0, 4.0], [3.0, 0.0], [0.0, 0.0]];
+        assert!((path_length(&polyline) - 12.0).abs() < 0.00001);
+    }
+
+    #[test]
+    fn path_area_test() {
+        let square = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]];
+        assert_eq!(path_area(&square), 1.0);
+        let mut clockwise = square;
+        clockwise.reverse();
+        assert_eq!(path_area(&clockwise), -1.0);
+        let closed = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0], [0.0, 0.0]];
+        assert_eq!(path_area(&closed), 1.0);
+
+        let far_away: Vec<[f32; 2]> = square
+            .iter()
+            .map(|[x, y]| [x * 2.0 + 10000.0, y * 3.0 - 5000.0])
+            .collect();
+        assert_eq!(path_area(&far_away), 6.0);
+        let figure_eight = [[0.0, 0.0], [1.0, 1.0], [1.0, 0.0], [0.0, 1.0]];
+        assert_eq!(path_area(&figure_eight), 0.0);
+        assert_eq!(path_area(&square[..2]), 0.0);
+        assert_eq!(path_area(&[]), 0.0);
+    }
+
+    #[test]
+    fn path_eta_test() {
+        let polyline = [[0.0, 0.0], [3.0, 4.0], [3.0, 0.0], [0.0, 0.0]];
+        assert!((path_eta(&polyline, 4.0) - 3.0).abs() < 0.00001);
+        assert!((path_eta(&polyline, 0.5) - 24.0).abs() < 0.00001);
+        assert_eq!(path_eta(&polyline, 0.0), f32::INFINITY);
+        assert_eq!(path_eta(&polyline, -1.0), f32::INFINITY);
+        assert_eq!(path_eta(&polyline, f32::NAN), f32::INFINITY);
+        assert_eq!(path_eta(&[[1.0, 1.0]], 0.0), 0.0);
+    }
+
+    #[test]
+    fn spline_path_test() {
+        let path = [[0.0, 0.0], [2.0, 0.0], [2.0, 2.0], [4.0, 3.0], [5.0, 0.0]];
+        let curve = spline_path(&path, 5);
+        assert_eq!(curve.len(), 4 * 5 + 1);
+        for (index, waypoint) in path.iter().enumerate() {
+            assert_eq!(curve[index * 5], *waypoint);
+        }
+
+        // Consecutive samples are much closer to each other than the waypoints.
+        for pair in curve.windows(2) {
+            assert!(Vec2::from(pair[0]).dist_to(&Vec2::from(pair[1])) < 1.0);
+        }
+        // A straight path gives a straight curve.
+        let straight = spline_path(&[[0.0, 0.0], [1.0, 0.0], [2.0, 0.0]], 3);
+        assert!(straight.iter().all(|point| point[1] == 0.0));
+        assert!(straight.windows(2).all(|pair| pair[0][0] < pair[1][0]));
+
+        assert_eq!(spline_path(&path, 0), path);
+        assert_eq!(spline_path(&[[1.0, 2.0]], 4), [[1.0, 2.0]]);
+        assert!(spline_path(&[], 4).is_empty());
+    }
+
+    #[test]
+    fn turning_points_test() {
+        let l_shape = [
+            [0.0, 0.0],
+            [1.0, 0.0],
+            [2.0, 0.0],
+            [2.0, 0.0],
+            [3.0, 0.0],
+            [3.0, 1.0],
+            [3.0, 2.0],
+            [3.0, 3.0],
+        ];
+        assert_eq!(
+            turning_points(&l_shape, 0.1),
+            [[0.0, 0.0], [3.0, 0.0], [3.0, 3.0]]
+        );
+
+        // The slight bend is below the threshold.
+        let bent = [[0.0, 0.0], [1.0, 0.05], [2.0, 0.0], [2.0, 1.0]];
+        assert_eq!(
+            turning_points(&bent, 0.2),
+            [[0.0, 0.0], [2.0, 0.0], [2.0, 1.0]]
+        );
+        assert_eq!(turning_points(&bent, 0.01), bent);
+        assert_eq!(turning_points(&[[1.0, 1.0], [1.0, 1.0]], 0.1), [[1.0, 1.0]]);
+        assert!(turning_points(&[], 0.1).is_empty());
+    }
+
+    #[test]
+    fn simplify_path_test() {
+        let collinear = [[0.0, 0.0], [1.0, 1.0], [2.0, 2.0], [2.5, 2.5], [4.0, 4.0]];
+        assert_eq!(
+            simplify_path_rdp(&collinear, 0.001),
+            [[0.0, 0.0], [4.0, 4.0]]
+        );
+
+        let corner = [
+            [0.0, 0.0],
+            [1.0, 0.05],
+            [2.0, 0.0],
+            [2.0, 1.0],
+            [2.01, 2.0],
+            [2.0, 3.0],
+        ];
+        assert_eq!(
+            simplify_path_rdp(&corner, 0.1),
+            [[0.0, 0.0], [2.0, 0.0], [2.0, 3.0]]
+        );
+        assert_eq!(simplify_path_rdp(&corner, 0.001).len(), corner.len());
+        assert_eq!(simplify_path_rdp(&corner[..2], 10.0), corner[..2]);
+        assert!(simplify_path_rdp(&[], 1.0).is_empty());
+    }
+
+    #[test]
+    fn recompute_edge_costs_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([0.0, 1.0]);
+        let p3 = graph.add_node([2.0, 0.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p0, p2).unwrap();
+        graph.connect_nodes(p1, p3).unwrap();
+
+        graph.set_node_position(p0, [0.0, -1.0]).unwrap();
+        assert_eq!(graph.path_cost(&[p0, p2]), Some(1.0));
+        graph.recompute_edge_costs();
+        assert_eq!(graph.path_cost(&[p0, p2]), Some(2.0));
+        assert_eq!(graph.path_cost(&[p2, p0]), Some(2.0));
+        assert!((graph.path_cost(&[p1, p0]).unwrap() - 2.0_f32.sqrt()).abs() < 0.00001);
+        assert_eq!(graph.path_cost(&[p1, p3]), Some(1.0));
+        assert_eq!(
+            graph.set_node_position(9, [0.0, 0.0]),
+            Err(ConnectionError::NodeDoesntExist(9))
+        );
+    }
+
+    #[test]
+    fn node_cost_multiplier_test() {
+        let mut graph = NavGraph::new();
+
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let p3 = graph.add_node([1.0, 1.0]);
+
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p3).unwrap();
+        graph.connect_nodes(p3, p2).unwrap();
+
+        assert_eq!(graph.search_graph(p0, p2).unwrap(), [p0, p1, p2]);
+
+        graph.set_node_cost_multiplier(p1, 2.0).unwrap();
+        assert_eq!(graph.search_graph(p0, p2).unwrap(), [p0, p3, p2]);
+
+        graph.set_node_cost_multiplier(p1, 1.0).unwrap();
+        assert_eq!(graph.search_graph(p0, p2).unwrap(), [p0, p1, p2]);
+
+        assert_eq!(
+            graph.set_node_cost_multiplier(17, 2.0),
+            Err(ConnectionError::NodeDoesntExist(17))
+        );
+        for multiplier in [-0.5, f32::NAN] {
+            assert_eq!(
+                graph.set_node_cost_multiplier(p1, multiplier),
+                Err(ConnectionError::InvalidMultiplier)
+            );
+        }
+        assert_eq!(graph.search_graph(p0, p2).unwrap(), [p0, p1, p2]);
+    }
+
+    #[test]
+    fn clear_search_state_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([0.5, 0.5]);
+        let p2 = graph.add_node([1.0, 0.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+
+        graph.search_graph(p0, p2).unwrap();
+        assert!(
+            graph
+                .get_all_nodes_with_state()
+                .any(|(_, state)| *state == NodeState::Solution)
+        );
+        graph.clear_search_state();
+        assert!(
+            graph
+                .get_all_nodes_with_state()
+                .all(|(_, state)| *state == NodeState::Clear)
+        );
+        assert_eq!(graph.closed_nodes().count(), 0);
+    }
+
+    #[test]
+    fn closed_nodes_test() {
+        let mut graph = NavGraph::new();
+
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let p3 = graph.add_node([5.0, 5.0]);
+
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+
+        assert_eq!(graph.closed_nodes().count(), 0);
+
+        assert!(graph.search_graph(p0, p3).is_none());
+        let closed: Vec<usize> = graph.closed_nodes().collect();
+        assert_eq!(closed, [p0, p1, p2]);
+        assert!(!closed.contains(&p3), "The node was never reached.");
+
+        graph.search_graph(p0, p1).unwrap();
+        let closed: Vec<usize> = graph.closed_nodes().collect();
+        assert!(closed.contains(&p0), "The start is always closed.");
+        assert!(!closed.contains(&p3));
+    }
+
+    #[test]
+    fn freeze_test() {
+        let mut blocked = [false; 36];
+        for index in [7, 8, 14, 20, 21, 27] {
+            blocked[index] = true;
+        }
+        let build = || {
+            let mut graph = grid_graph(6, 6, &blocked);
+            graph.set_node_cost_multiplier(9, 4.0).unwrap();
+            graph.set_node_cost_multiplier(28, 2.0).unwrap();
+            graph.add_node([9.0, 9.0]);
+            graph
+        };
+        let mut graph = build();
+        let frozen = build().freeze();
+        assert_eq!(frozen.node_count(), 37);
+        assert_eq!(frozen.node_position(36), Some([9.0, 9.0]));
+        assert_eq!(frozen.node_position(37), None);
+        assert_eq!(frozen.neighbors(0).count(), 2);
+        assert_eq!(frozen.neighbors(36).count(), 0);
+        assert_eq!(frozen.neighbors(40).count(), 0);
+
+        let mut scratch = SearchScratch::new();
+        for start in 0..37 {
+            for destination in 0..37 {
+                let expected = graph.search_graph(start, destination);
+                assert_eq!(
+                    frozen.search_graph_with_scratch(start, destination, &mut scratch),
+                    expected
+                );
+            }
+        }
+        assert_eq!(frozen.find_path(0, 35), graph.search_graph(0, 35));
+
+        for position in [
+            [0.4, 0.4],
+            [2.6, 1.2],
+            [-3.0, 2.0],
+            [5.5, 5.5],
+            [8.0, 8.0],
+            [20.0, 20.0],
+        ] {
+            for radius in [0.1, 0.6, 2.0, 100.0] {
+                assert_eq!(
+                    frozen.find_nearest_node_with_radius(position, radius),
+                    graph.find_nearest_node_with_radius(position, radius)
+                );
+            }
+        }
+        assert_eq!(
+            NavGraph::new()
+                .freeze()
+                .find_nearest_node_with_radius([0.0, 0.0], 1.0),
+            None
+        );
+    }
+
+    #[test]
+    fn csr_test() {
+        let (graph, _) = readme_graph();
+
+        let (offsets, columns, costs) = graph.to_csr();
+        assert_eq!(offsets.len(), 6);
+        assert_eq!(columns.len(), 12);
+        assert_eq!(costs.len(), 12);
+        for node in 0..5 {
+            let row = offsets[node]..offsets[node + 1];
+            let mut neighbors: Vec<usize> = columns[row.clone()].to_vec();
+            neighbors.sort();
+            let mut expected: Vec<usize> = graph
+                .links_in_order()
+                .filter_map(|(node1, node2)| match node {
+                    _ if node == node1 => Some(node2),
+                    _ if node == node2 => Some(node1),
+                    _ => None,
+                })
+                .collect();
+            expected.sort();
+            assert_eq!(neighbors, expected);
+            for (partner, cost) in columns[row.clone()].iter().zip(costs[row].iter()) {
+                assert_eq!(graph.get_edge(node, *partner), Some(*cost));
+            }
+        }
+
+        assert_eq!(NavGraph::new().to_csr(), (vec![0], vec![], vec![]));
+    }
+
+    #[cfg(feature = "petgraph")]
+    #[test]
+    fn petgraph_round_trip_test() {
+        let mut graph = NavGraph::new();
+
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([0.5, 0.5]);
+        let p2 = graph.add_node([1.0, 0.0]);
+        let p3 = graph.add_node([1.0, 1.0]);
+
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p2, p3).unwrap();
+
+        let converted = graph.to_petgraph();
+        assert_eq!(converted.node_count(), 4);
+        assert_eq!(converted.edge_count(), 3);
+
+        let mut round_trip = NavGraph::from_petgraph(&converted).unwrap();
+        assert_eq!(round_trip.nodes.len(), graph.nodes.len());
+        assert!(round_trip.links_in_order().eq(graph.links_in_order()));
+        for (node1, node2) in graph.links_in_order() {
+            assert_eq!(
+                round_trip.get_edge(node1, node2),
+                graph.get_edge(node1, node2)
+            );
+        }
+        assert_eq!(round_trip.search_graph(p0, p3), graph.search_graph(p0, p3));
+
+        // Parallel edges collapse into one link with the minimum cost.
+        let mut converted = converted;
+        converted.add_edge(
+            petgraph::graph::NodeIndex::new(p1),
+            petgraph::graph::NodeIndex::new(p0),
+            0.25,
+        );
+        converted.add_edge(
+            petgraph::graph::NodeIndex::new(p2),
+            petgraph::graph::NodeIndex::new(p3),
+            5.0,
+        );
+        let imported = NavGraph::from_petgraph(&converted).unwrap();
+        assert!(imported.find_duplicate_edges().is_empty());
+        assert!(imported.links_in_order().eq(graph.links_in_order()));
+        assert_eq!(imported.get_edge(p0, p1), Some(0.25));
+        assert_eq!(imported.get_edge(p2, p3), graph.get_edge(p2, p3));
+
+        converted.add_edge(
+            petgraph::graph::NodeIndex::new(p3),
+            petgraph::graph::NodeIndex::new(p3),
+            1.0,
+        );
+        assert_eq!(
+            NavGraph::from_petgraph(&converted).err(),
+            Some(ConnectionError::NodeDoubled)
+        );
+    }
+
+    #[test]
+    fn ordered_f32_test() {
+        let mut costs: Vec<OrderedF32> = [2.0, f32::NAN, f32::INFINITY, -1.0, f32::NAN, 0.5]
+            .into_iter()
+            .map(OrderedF32)
+            .collect();
+        costs.sort();
+        let sorted: Vec<f32> = costs.iter().map(|cost| cost.0).collect();
+        assert_eq!(sorted[..4], [-1.0, 0.5, 2.0, f32::INFINITY]);
+        assert!(sorted[4..].iter().all(|cost| cost.is_nan()));
+        assert_eq!(OrderedF32(f32::NAN), OrderedF32(f32::NAN));
+        assert!(OrderedF32(f32::NAN) > OrderedF32(f32::INFINITY));
+        assert_eq!(
+            HeapEntry {
+                cost: f32::NAN,
+                node: 0
+            }
+            .cmp(&HeapEntry { cost: 1.0, node: 1 }),
+            Ordering::Less,
+            "The heap pops the NaN costs last."
+        );
+
+        // A node with a NaN position makes its costs NaN, which must not disturb the search.
+        let mut graph = grid_graph(3, 3, &[false; 9]);
+        let broken = graph.add_node([f32::NAN, 0.0]);
+        graph.connect_nodes(0, broken).unwrap();
+        graph.connect_nodes(broken, 8).unwrap();
+        assert_eq!(graph.search_graph(0, 8).unwrap(), [0, 4, 8]);
+        assert!(graph.search_graph(0, broken).is_some());
+        assert!(graph.dijkstra_from(0)[8].is_some());
+    }
+
+    #[test]
+    fn descend_field_test() {
+        let mut graph = grid_graph(4, 4, &[false; 16]);
+        graph.set_node_cost_multiplier(5, 4.0).unwrap();
+        let isolated = graph.add_node([9.0, 9.0]);
+        let goal = 10;
+
+        let field = graph.dijkstra_from(goal);
+        for start in 0..16 {
+            let path = graph.descend_field(&field, start);
+            assert_eq!(path[0], start);
+            assert_eq!(path[path.len() - 1], goal);
+            assert!(graph.is_valid_path(&path));
+            assert!(path.windows(2).all(|pair| field[pair[1]] < field[pair[0]]));
+        }
+        assert!(graph.descend_field(&field, isolated).is_empty());
+        assert!(graph.descend_field(&field, 20).is_empty());
+        assert_eq!(graph.descend_field(&field, goal), [goal]);
+    }
+
+    #[test]
+    fn betweenness_centrality_test() {
+        let mut graph = NavGraph::new();
+        let chain: Vec<usize> = (0..5)
+            .map(|index| graph.add_node([index as f32, 0.0]))
+            .collect();
+        for pair in chain.windows(2) {
+            graph.connect_nodes(pair[0], pair[1]).unwrap();
+        }
+        let stray = graph.add_node([9.0, 9.0]);
+
+        // The middle node lies between both nodes on its left and both on its right.
+        let scores = graph.betweenness_centrality();
+        assert_eq!(scores, [0.0, 3.0, 4.0, 3.0, 0.0, 0.0]);
+        assert_eq!(scores[stray], 0.0);
+
+        // Opposite corners of a square have two equally short paths, each corner gets half.
+        let mut square = NavGraph::new();
+        let corners = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]
+            .map(|position| square.add_node(position));
+        for (index, corner) in corners.iter().enumerate() {
+            square
+                .connect_nodes(*corner, corners[(index + 1) % 4])
+                .unwrap();
+        }
+        assert_eq!(square.betweenness_centrality(), [0.5; 4]);
+    }
+
+    #[test]
+    fn edge_betweenness_test() {
+        // Two square clusters with diagonals, joined by a single trunk road.
+        let mut graph = NavGraph::new();
+        for offset in [0.0, 5.0] {
+            let corners = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]
+                .map(|[x, y]| graph.add_node([x + offset, y]));
+            for (index, corner) in corners.iter().enumerate() {
+                graph
+                    .connect_nodes(*corner, corners[(index + 1) % 4])
+                    .unwrap();
+            }
+            graph.connect_nodes(corners[0], corners[2]).unwrap();
+        }
+        graph.connect_nodes(1, 4).unwrap();
+        graph.add_node([9.0, 9.0]);
+
+        let scores = graph.edge_betweenness();
+        assert_eq!(scores.len(), graph.stats().edges);
+        let trunk = graph
+            .links_in_order()
+            .position(|link| link == (1, 4))
+            .unwrap();
+        assert_eq!(
+            scores[trunk], 16.0,
+            "All pairs between the clusters use the trunk."
+        );
+        assert!(
+            scores
+                .iter()
+                .enumerate()
+                .all(|(index, score)| index == trunk || *score < scores[trunk])
+        );
+
+        // Opposite corners of a square without diagonal have two equally short paths.
+        let mut square = NavGraph::new();
+        let corners = [[0.0, 0.0], [1.0, 0.0], [1.0, 1.0], [0.0, 1.0]]
+            .map(|position| square.add_node(position));
+        for (index, corner) in corners.iter().enumerate() {
+            square
+                .connect_nodes(*corner, corners[(index + 1) % 4])
+                .unwrap();
+        }
+        assert_eq!(square.edge_betweenness(), [2.0; 4]);
+        assert!(NavGraph::new().edge_betweenness().is_empty());
+    }
+
+    #[test]
+    fn bounding_circle_test() {
+        let mut graph = NavGraph::new();
+        assert_eq!(graph.bounding_circle(), None);
+        graph.add_node([3.0, -2.0]);
+        assert_eq!(graph.bounding_circle(), Some(([3.0, -2.0], 0.0)));
+
+        let mut graph = NavGraph::new();
+        for index in 0..40 {
+            let angle = index as f32 * 0.37;
+            graph.add_node([angle.cos(), angle.sin()]);
+            // Points inside of the circle do not matter.
+            graph.add_node([0.5 * angle.sin(), 0.3 * angle.cos()]);
+        }
+        let (center, radius) = graph.bounding_circle().unwrap();
+        assert!(Vec2::from(center).approx_eq(&Vec2::new(0.0, 0.0), 0.0001));
+        assert!((radius - 1.0).abs() < 0.0001);
+        for (position, _) in graph.get_all_nodes_with_state() {
+            assert!(Vec2::from(position).dist_to(&Vec2::from(center)) <= radius + 0.0001);
+        }
+
+        // Collinear points and duplicates need no third point on the boundary.
+        let mut line = NavGraph::new();
+        for x in [2.0, -1.0, 0.5, 2.0, 3.0, -1.0] {
+            line.add_node([x, 1.0]);
+        }
+        line.add_node([f32::NAN, 0.0]);
+        assert_eq!(line.bounding_circle(), Some(([1.0, 1.0], 2.0)));
+    }
+
+    #[test]
+    fn two_edge_connected_test() {
+        // A dumbbell of two grids connected by a bridge of two links.
+        let mut graph = grid_graph(3, 3, &[false; 9]);
+        let handle = graph.add_node([5.0, 1.0]);
+        for y in 0..3 {
+            for x in 0..3 {
+                graph.add_node([x as f32 + 10.0, y as f32]);
+            }
+        }
+        for y in 0..3 {
+            for x in 0..3 {
+                let index = handle + 1 + y * 3 + x;
+                if x < 2 {
+                    graph.connect_nodes(index, index + 1).unwrap();
+                }
+                if y < 2 {
+                    graph.connect_nodes(index, index + 3).unwrap();
+                }
+            }
+        }
+        graph.connect_nodes(5, handle).unwrap();
+        graph.connect_nodes(handle, handle + 4).unwrap();
+        graph.add_node([20.0, 20.0]);
+
+        assert!(graph.two_edge_connected(0, 8));
+        assert!(graph.two_edge_connected(handle + 1, handle + 5));
+        assert!(graph.two_edge_connected(handle, handle));
+        assert!(
+            !graph.two_edge_connected(0, handle + 9),
+            "The bridge is a single route."
+        );
+        assert!(!graph.two_edge_connected(handle + 5, 5));
+        assert!(!graph.two_edge_connected(5, handle));
+        assert!(
+            !graph.two_edge_connected(0, 19),
+            "Node 19 is not connected at all."
+        );
+        assert!(!graph.two_edge_connected(0, 20));
+
+        // A second bridge turns the dumbbell into a cycle.
+        graph.connect_nodes(0, handle + 7).unwrap();
+        assert!(graph.two_edge_connected(0, handle + 9));
+        assert!(graph.two_edge_connected(5, handle));
+    }
+
+    #[test]
+    fn distance_matrix_test() {
+        let mut blocked = vec![false; 100];
+        for y in 0..8 {
+            blocked[y * 10 + 5] = true;
+        }
+        let mut graph = grid_graph(10, 10, &blocked);
+        graph.set_node_cost_multiplier(22, 3.0).unwrap();
+        let chosen = [0, 9, 47];
+        let matrix = graph.distance_matrix(&chosen);
+        assert_eq!(matrix.len(), 3);
+        for (row, start) in chosen.iter().enumerate() {
+            assert_eq!(matrix[row][row], 0.0);
+            for (column, destination) in chosen.iter().enumerate() {
+                assert!((matrix[row][column] - matrix[column][row]).abs() < 0.0001);
+                if row != column {
+                    graph.search_graph(*start, *destination).unwrap();
+                    let cost = graph.cost_to(*destination).unwrap();
+                    assert!((matrix[row][column] - cost).abs() < 0.0001);
+                }
+            }
+        }
+
+        let matrix = graph.distance_matrix(&[0, 5, 200]);
+        assert_eq!(matrix[0], [0.0, f32::INFINITY, f32::INFINITY]);
+        assert!(matrix[2].iter().all(|cost| cost.is_infinite()));
+        assert!(graph.distance_matrix(&[]).is_empty());
+    }
+
+    #[test]
+    fn heuristic_admissible_test() {
+        let mut graph = grid_graph(5, 5, &[false; 25]);
+        assert_eq!(graph.check_heuristic_admissible(), Ok(()));
+
+        // Moving a node away without repairing the costs under-prices its edges.
+        graph.set_node_position(24, [4.0, 5.0]).unwrap();
+        assert_eq!(
+            graph.check_heuristic_admissible(),
+            Err(vec![(18, 24), (19, 24), (23, 24)])
+        );
+        graph.recompute_edge_costs();
+        assert_eq!(graph.check_heuristic_admissible(), Ok(()));
+
+        graph.set_node_cost_multiplier(12, 0.8).unwrap();
+        let violations = graph.check_heuristic_admissible().unwrap_err();
+        assert_eq!(
+            violations.len(),
+            8,
+            "All links of the cheap node are affected."
+        );
+        assert!(
+            violations
+                .iter()
+                .all(|(first, second)| *first == 12 || *second == 12)
+        );
+        graph.set_node_cost_multiplier(12, 1.5).unwrap();
+        assert_eq!(graph.check_heuristic_admissible(), Ok(()));
+    }
+
+    #[test]
+    fn heap_threshold_test() {
+        // The grids straddle the default threshold and contain many paths of equal cost.
+        for cols in 3..9 {
+            let mut blocked = vec![false; cols * cols];
+            blocked[cols + 1] = true;
+            let mut graph = grid_graph(cols, cols, &blocked);
+            graph
+                .set_node_cost_multiplier(cols * cols / 2, 0.6)
+                .unwrap();
+            let count = cols * cols;
+            for query in 0..count * count {
+                let (start, destination) = (query / count, query % count);
+                graph.set_heap_threshold(usize::MAX);
+                let linear = graph.search_graph(start, destination);
+                let linear_states: Vec<NodeState> = graph
+                    .get_all_nodes_with_state()
+                    .map(|(_, state)| state.clone())
+                    .collect();
+                graph.set_heap_threshold(0);
+                assert_eq!(graph.search_graph(start, destination), linear);
+                assert!(
+                    graph
+                        .get_all_nodes_with_state()
+                        .map(|(_, state)| state)
+                        .eq(linear_states.iter())
+                );
+                graph.set_heap_threshold(HEAP_THRESHOLD);
+                assert_eq!(graph.search_graph(start, destination), linear);
+            }
+        }
+    }
+
+    #[test]
+    fn fewest_hops_test() {
+        // A straight corridor of many short links and a detour of three long links.
+        let mut graph = NavGraph::new();
+        for x in 0..11 {
+            graph.add_node([x as f32, 0.0]);
+        }
+        for x in 0..10 {
+            graph.connect_nodes(x, x + 1).unwrap();
+        }
+        let p1 = graph.add_node([2.0, 5.0]);
+        let p2 = graph.add_node([8.0, 5.0]);
+        graph.connect_nodes(0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p2, 10).unwrap();
+        let isolated = graph.add_node([5.0, 9.0]);
+
+        let shortest = graph.search_graph(0, 10).unwrap();
+        assert_eq!(shortest.len(), 11);
+        let fewest = graph.search_graph_fewest_hops(0, 10).unwrap();
+        assert_eq!(fewest, [0, p1, p2, 10]);
+        assert!(graph.path_cost(&fewest).unwrap() > graph.path_cost(&shortest).unwrap());
+        assert_eq!(graph.cost_to(10), Some(3.0));
+        assert_eq!(graph.cost_to(p2), Some(2.0));
+
+        assert_eq!(graph.search_graph_fewest_hops(4, 4), Some(vec![4]));
+        assert_eq!(graph.search_graph_fewest_hops(4, 7), Some(vec![4, 5, 6, 7]));
+        assert_eq!(graph.search_graph_fewest_hops(0, isolated), None);
+        assert_eq!(graph.search_graph_fewest_hops(0, 20), None);
+    }
+
+    #[test]
+    fn edges_crossing_test() {
+        let graph = grid_graph(4, 4, &[false; 16]);
+
+        // A vertical wall between the first two columns cuts the horizontal links of every row.
+        let mut crossed = graph.edges_crossing([0.5, -0.5], [0.5, 3.5]);
+        crossed.sort_unstable();
+        assert_eq!(crossed.len(), 10);
+        for row in 0..4 {
+            assert!(crossed.contains(&(row * 4, row * 4 + 1)));
+        }
+        assert!(crossed.contains(&(0, 5)) && crossed.contains(&(1, 4)));
+        assert!(!crossed.contains(&(1, 2)));
+
+        // A short wall within one cell only cuts the diagonals of that cell.
+        let mut crossed = graph.edges_crossing([1.4, 1.5], [1.6, 1.5]);
+        crossed.sort_unstable();
+        assert_eq!(crossed, [(5, 10), (6, 9)]);
+        assert!(graph.edges_crossing([-1.0, -1.0], [-1.0, 5.0]).is_empty());
+        assert!(graph.edges_crossing([0.0, 0.0], [0.0, -1.0]).is_empty());
+    }
+
+    #[test]
+    fn apply_wall_test() {
+        let mut graph = grid_graph(5, 5, &[false; 25]);
+        let direct = graph.search_graph(10, 14).unwrap();
+        assert_eq!(direct, [10, 11, 12, 13, 14]);
+
+        // A vertical wall through the middle column leaves a gap at the top row.
+        assert_eq!(graph.apply_wall([2.5, -0.5], [2.5, 3.25]), 10);
+        assert_eq!(graph.edge_enabled(12, 13), Some(false));
+        assert_eq!(graph.edge_enabled(13, 17), Some(false));
+        assert_eq!(graph.edge_enabled(22, 23), Some(true));
+        assert_eq!(graph.edge_enabled(12, 14), None);
+        assert_eq!(
+            graph.apply_wall([2.5, 0.0], [2.5, 1.0]),
+            0,
+            "Disabled links are not cut twice."
+        );
+
+        let detour = graph.search_graph(10, 14).unwrap();
+        assert!(detour.contains(&22) || detour.contains(&23));
+        assert!(graph.is_valid_path(&detour));
+        assert!(graph.path_cost(&detour).unwrap() > graph.path_cost(&direct).unwrap());
+        assert!(graph.dijkstra_from(10)[14].unwrap() > 4.0 + 0.0001);
+        let mut walled = grid_graph(5, 5, &[false; 25]);
+        walled.apply_wall([2.5, -0.5], [2.5, 3.25]);
+        assert_eq!(walled.freeze().find_path(10, 14), Some(detour));
+
+        // Closing the gap separates the halves, tearing the wall down restores the direct route.
+        assert_eq!(graph.apply_wall([2.5, 3.25], [2.5, 4.5]), 3);
+        assert_eq!(graph.search_graph(10, 14), None);
+        for (node1, node2) in graph.edges_crossing([2.5, -0.5], [2.5, 4.5]) {
+            graph.set_edge_enabled(node1, node2, true).unwrap();
+        }
+        assert_eq!(graph.search_graph(10, 14), Some(direct));
+        assert_eq!(
+            graph.set_edge_enabled(0, 24, false),
+            Err(ConnectionError::LinkDoesntExist)
+        );
+    }
+
+    #[test]
+    fn disabled_edge_walks_test() {
+        // A wall through the whole grid separates the first three columns from the last two.
+        let mut graph = grid_graph(5, 5, &[false; 25]);
+        graph.apply_wall([2.5, -0.5], [2.5, 4.5]);
+        assert_eq!(graph.stats().components, 2);
+        assert_eq!(graph.reachable_count(10), 15);
+        assert!(!graph.two_edge_connected(10, 14));
+        assert!(graph.two_edge_connected(13, 14));
+        assert_eq!(graph.search_graph_fewest_hops(10, 14), None);
+        assert_eq!(graph.search_graph_cooperative(10, 14, 0), None);
+        assert!(graph.all_paths(10, 14, f32::INFINITY, 1).is_empty());
+        assert_eq!(graph.begin_dstar(10, 14).replan(), None);
+        let cut = graph
+            .links
+            .iter()
+            .position(|link| edge_key(link.0, link.1) == (12, 13))
+            .unwrap();
+        assert_eq!(graph.edge_betweenness()[cut], 0.0);
+
+        // The shortcut of a triangle is not taken while it is disabled.
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 1.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p2).unwrap();
+        let field = graph.dijkstra_from(p2);
+        graph.set_edge_enabled(p0, p2, false).unwrap();
+        assert_eq!(graph.smooth_path_on_graph(&[p0, p1, p2]), [p0, p1, p2]);
+        assert_eq!(graph.descend_field(&field, p0), [p0, p1, p2]);
+        assert_eq!(
+            graph.search_graph_fewest_hops(p0, p2).unwrap(),
+            [p0, p1, p2]
+        );
+        assert_eq!(graph.betweenness_centrality(), [0.0, 1.0, 0.0]);
+        let mut search = graph.begin_dstar(p0, p2);
+        assert_eq!(search.replan().unwrap(), [p0, p1, p2]);
+        search.update_edge(p0, p2, 1.0).unwrap();
+        assert_eq!(search.replan().unwrap(), [p0, p2]);
+    }
+
+    #[test]
+    fn edge_sides_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([1.0, -2.0]);
+        let p1 = graph.add_node([4.0, 2.0]);
+        let p2 = graph.add_node([4.0, 2.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+
+        // The sign of the cross product tells the side of a point relative to the directed edge.
+        let start = Vec2::new(1.0, -2.0);
+        let delta = Vec2::new(3.0, 4.0);
+        let side = |point: [f32; 2]| {
+            let relative = Vec2::from(point) - start;
+            delta.x * relative.y - delta.y * relative.x
+        };
+        let (left, right) = graph.edge_sides(p0, p1).unwrap();
+        assert!(side(left) > 0.0 && side(right) < 0.0);
+        let midpoint = Vec2::new(2.5, 0.0);
+        assert!((Vec2::from(left).dist_to(&midpoint) - 0.5).abs() < 0.00001);
+        assert!((Vec2::from(right).dist_to(&midpoint) - 0.5).abs() < 0.00001);
+        assert_eq!(graph.edge_sides(p1, p0), Some((right, left)));
+
+        assert_eq!(graph.edge_sides(p1, p2), Some(([4.0, 2.0], [4.0, 2.0])));
+        assert_eq!(graph.edge_sides(p0, p2), None);
+        assert_eq!(graph.edge_sides(p0, 7), None);
+    }
+
+    #[test]
+    fn search_graph_cached_test() {
+        let mut graph = grid_graph(5, 5, &[false; 25]);
+        let path = graph.search_graph_cached(0, 24).unwrap();
+        assert!(graph.closed_nodes().count() > 0);
+
+        // The second identical query is served from the cache without expansions.
+        assert_eq!(graph.search_graph_cached(0, 24), Some(path.clone()));
+        assert_eq!(graph.closed_nodes().count(), 0);
+
+        // A mutation invalidates the cached result.
+        graph.disconnect_nodes(path[0], path[1]).unwrap();
+        let rerouted = graph.search_graph_cached(0, 24).unwrap();
+        assert!(graph.closed_nodes().count() > 0);
+        assert!(graph.is_valid_path(&rerouted));
+        assert_ne!(rerouted, path);
+
+        // The least recently used query is dropped when the cache is full.
+        for destination in 1..=PATH_CACHE_SIZE {
+            graph.search_graph_cached(0, destination);
+        }
+        graph.search_graph_cached(0, 24);
+        assert!(graph.closed_nodes().count() > 0);
+        graph.search_graph_cached(0, PATH_CACHE_SIZE);
+        assert_eq!(graph.closed_nodes().count(), 0);
+    }
+
+    #[test]
+    fn search_graph_with_options_test() {
+        let mut graph = grid_graph(5, 5, &[false; 25]);
+        let default = graph.search_graph_with_options(0, 24, &SearchOptions::default());
+        assert_eq!(default.into_path(), graph.search_graph(0, 24));
+
+        // The danger and the mask combine with the avoided nodes.
+        let mut danger = vec![0.0; 25];
+        danger[12] = 10.0;
+        graph.set_edge_tags(0, 6, 0b10).unwrap();
+        let options = SearchOptions {
+            danger: &danger,
+            allowed_mask: Some(0b01),
+            avoid: &[1],
+            ..SearchOptions::default()
+        };
+        let SearchResult::Found(path) = graph.search_graph_with_options(0, 24, &options) else {
+            panic!("The destination should be reachable.");
+        };
+        assert!(graph.is_valid_path(&path));
+        assert_eq!(path[1], 5);
+        assert!(!path.contains(&12));
+
+        // A weighted search with a cap that is too small stops early, a larger cap finds the path.
+        let capped = SearchOptions {
+            heuristic_weight: Some(2.0),
+            max_expansions: Some(2),
+            ..SearchOptions::default()
+        };
+        assert_eq!(
+            graph.search_graph_with_options(0, 24, &capped),
+            SearchResult::Capped
+        );
+        assert_eq!(graph.closed_nodes().count(), 2);
+        let options = SearchOptions {
+            max_expansions: Some(25),
+            ..capped
+        };
+        assert!(matches!(
+            graph.search_graph_with_options(0, 24, &options),
+            SearchResult::Found(_)
+        ));
+
+        graph.disconnect_nodes(0, 1).unwrap();
+        graph.disconnect_nodes(0, 5).unwrap();
+        graph.disconnect_nodes(0, 6).unwrap();
+        assert_eq!(
+            graph.search_graph_with_options(0, 24, &SearchOptions::default()),
+            SearchResult::NoPath
+        );
+    }
+
+    #[test]
+    fn improvement_epsilon_test() {
+        // The first expanded neighbor reaches the destination on a route that is longer by the indicated fraction.
+        let near_equal_graph = |excess: f32| {
+            let mut graph = NavGraph::new();
+            let start = graph.add_node([0.0, 0.0]);
+            let first = graph.add_node([1.0, 0.0]);
+            let second = graph.add_node([1.0, 1.0]);
+            let destination = graph.add_node([2.0, 0.0]);
+            for (node1, node2) in [(start, first), (start, second), (second, destination)] {
+                graph.connect_nodes(node1, node2).unwrap();
+            }
+            graph.connect_nodes(first, destination).unwrap();
+            let optimal = 2.0 * 2.0_f32.sqrt();
+            graph.set_stored_cost(first, destination, optimal * (1.0 + excess) - 1.0);
+            graph
+        };
+
+        // Noise below the epsilon keeps the ancestor found first, every time.
+        let mut graph = near_equal_graph(1e-7);
+        for _ in 0..3 {
+            assert_eq!(graph.search_graph(0, 3).unwrap(), [0, 1, 3]);
+        }
+        graph.set_improvement_epsilon(0.0);
+        assert_eq!(graph.search_graph(0, 3).unwrap(), [0, 2, 3]);
+
+        // Meaningful improvements are still accepted.
+        let mut graph = near_equal_graph(1e-3);
+        assert_eq!(graph.search_graph(0, 3).unwrap(), [0, 2, 3]);
+    }
+
+    #[test]
+    #[should_panic(expected = "improvement epsilon has to be")]
+    fn negative_improvement_epsilon_test() {
+        NavGraph::new().set_improvement_epsilon(-0.1);
+    }
+
+    #[test]
+    #[should_panic(expected = "improvement epsilon has to be")]
+    fn nan_improvement_epsilon_test() {
+        NavGraph::new().set_improvement_epsilon(f32::NAN);
+    }
+
+    #[test]
+    #[should_panic(expected = "improvement epsilon has to be")]
+    fn large_improvement_epsilon_test() {
+        NavGraph::new().set_improvement_epsilon(1.0);
+    }
+
+    #[test]
+    fn heuristic_weight_test() {
+        // The wall makes the straight-line heuristic misleading, so the weight matters.
+        let mut blocked = vec![false; 400];
+        for y in 2..18 {
+            blocked[y * 20 + 10] = true;
+        }
+        let mut graph = grid_graph(20, 20, &blocked);
+        let baseline = graph.search_graph(200, 219).unwrap();
+        let baseline_cost = graph.path_cost(&baseline).unwrap();
+        let baseline_expansions = graph.closed_nodes().count();
+
+        graph.set_heuristic_weight(1.0);
+        assert_eq!(graph.search_graph(200, 219), Some(baseline.clone()));
+        assert_eq!(graph.closed_nodes().count(), baseline_expansions);
+
+        graph.set_heuristic_weight(3.0);
+        let greedy = graph.search_graph(200, 219).unwrap();
+        assert!(graph.closed_nodes().count() < baseline_expansions);
+        let greedy_cost = graph.path_cost(&greedy).unwrap();
+        assert!(greedy_cost >= baseline_cost - 0.0001);
+        assert!(
+            greedy_cost <= 3.0 * baseline_cost,
+            "The suboptimality is bounded by the weight."
+        );
+
+        let mut weighted = grid_graph(20, 20, &blocked);
+        weighted.set_heuristic_weight(3.0);
+        assert_eq!(weighted.freeze().find_path(200, 219), Some(greedy));
+    }
+
+    #[test]
+    #[should_panic(expected = "heuristic weight has to be")]
+    fn negative_heuristic_weight_test() {
+        NavGraph::new().set_heuristic_weight(-1.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "heuristic weight has to be")]
+    fn infinite_heuristic_weight_test() {
+        NavGraph::new().set_heuristic_weight(f32::INFINITY);
+    }
+
+    #[test]
+    #[should_panic(expected = "heuristic weight has to be")]
+    fn nan_option_heuristic_weight_test() {
+        let (mut graph, [p0, _, _, _, p4]) = readme_graph();
+        let options = SearchOptions {
+            heuristic_weight: Some(f32::NAN),
+            ..SearchOptions::default()
+        };
+        graph.search_graph_with_options(p0, p4, &options);
+    }
+
+    #[test]
+    fn diameter_test() {
+        let mut graph = NavGraph::new();
+        assert_eq!(graph.diameter(), None);
+
+        let chain: Vec<usize> = (0..5)
+            .map(|index| graph.add_node([index as f32, 0.0]))
+            .collect();
+        let stray = graph.add_node([10.0, 10.0]);
+        assert_eq!(graph.diameter(), None);
+
+        for pair in chain.windows(2) {
+            graph.connect_nodes(pair[0], pair[1]).unwrap();
+        }
+        assert_eq!(graph.diameter(), Some((chain[0], chain[4], 4.0)));
+
+        let distances = graph.dijkstra_from(chain[2]);
+        assert_eq!(distances[chain[0]], Some(2.0));
+        assert_eq!(distances[stray], None);
+    }
+
+    #[test]
+    fn farthest_node_test() {
+        let mut graph = NavGraph::new();
+        let chain: Vec<usize> = (0..5)
+            .map(|index| graph.add_node([index as f32, 0.0]))
+            .collect();
+        let stray = graph.add_node([10.0, 0.0]);
+        for pair in chain.windows(2) {
+            graph.connect_nodes(pair[0], pair[1]).unwrap();
+        }
+
+        assert_eq!(graph.farthest_node(chain[0]), Some((chain[4], 4.0)));
+        assert_eq!(graph.farthest_node(chain[4]), Some((chain[0], 4.0)));
+        assert_eq!(graph.farthest_node(chain[2]), Some((chain[0], 2.0)));
+        assert_eq!(graph.farthest_node(stray), Some((stray, 0.0)));
+        assert_eq!(graph.farthest_node(9), None);
+    }
+
+    #[test]
+    fn graph_center_test() {
+        let mut graph = NavGraph::new();
+        assert_eq!(graph.graph_center(), None);
+
+        let spokes: Vec<usize> = (0..6)
+            .map(|index| {
+                let angle = index as f32 * std::f32::consts::PI / 3.0;
+                graph.add_node([2.0 * angle.cos(), 2.0 * angle.sin()])
+            })
+            .collect();
+        let hub = graph.add_node([0.0, 0.0]);
+        for spoke in spokes.iter() {
+            graph.connect_nodes(hub, *spoke).unwrap();
+        }
+        assert_eq!(graph.graph_center(), Some(hub));
+
+        graph.add_node([10.0, 10.0]);
+        assert_eq!(graph.graph_center(), None);
+    }
+
+    #[test]
+    fn smooth_edge_costs_test() {
+        let mut graph = grid_graph(6, 6, &[false; 36]);
+        let links: Vec<(usize, usize)> = graph.links_in_order().collect();
+        let untouched: Vec<(usize, usize, f32)> = graph.edges().collect();
+        graph.smooth_edge_costs(4, 0.5);
+        assert_eq!(graph.edges().collect::<Vec<_>>(), untouched);
+
+        // Noisy terrain costs as cost factors between 1 and 4.
+        for (index, (node1, node2)) in links.iter().enumerate() {
+            let noise = 1.0 + ((index * 7919) % 13) as f32 / 4.0;
+            let length = graph.get_edge(*node1, *node2).unwrap();
+            graph.set_stored_cost(*node1, *node2, noise * length);
+        }
+        let factors = |graph: &NavGraph| -> Vec<f32> {
+            links
+                .iter()
+                .map(|(node1, node2)| {
+                    let length = graph.nodes[*node1]
+                        .position
+                        .dist_to(&graph.nodes[*node2].position);
+                    graph.get_edge(*node1, *node2).unwrap() / length
+                })
+                .collect()
+        };
+        let variance = |values: &[f32]| {
+            let mean = values.iter().sum::<f32>() / values.len() as f32;
+            values
+                .iter()
+                .map(|value| (value - mean).powi(2))
+                .sum::<f32>()
+                / values.len() as f32
+        };
+        let before = factors(&graph);
+        graph.smooth_edge_costs(3, 0.5);
+        let after = factors(&graph);
+        assert!(variance(&after) < 0.5 * variance(&before));
+        assert!(after.iter().all(|factor| *factor >= 1.0 && *factor <= 4.0));
+        assert_eq!(graph.get_edge(1, 0), graph.get_edge(0, 1));
+
+        // A factor of 0 keeps the costs.
+        graph.smooth_edge_costs(3, 0.0);
+        assert_eq!(factors(&graph), after);
+    }
+
+    #[test]
+    fn all_shortest_paths_test() {
+        // Two symmetric routes around a blocked center cell.
+        let mut blocked = [false; 9];
+        blocked[4] = true;
+        let graph = grid_graph(3, 3, &blocked);
+        let paths = graph.all_shortest_paths(3, 5, 10);
+        assert_eq!(paths, [vec![3, 0, 1, 2, 5], vec![3, 6, 7, 8, 5]]);
+        for path in paths.iter() {
+            assert_eq!(graph.path_cost(path), Some(4.0));
+        }
+
+        // Diagonal and orthogonal steps sum up in different orders, but the costs still count as equal.
+        let open = grid_graph(4, 4, &[false; 16]);
+        let paths = open.all_shortest_paths(0, 14, 100);
+        assert_eq!(paths.len(), 3);
+        let cost = open.path_cost(&paths[0]).unwrap();
+        assert!((cost - (1.0 + 2.0 * 2.0_f32.sqrt())).abs() < 0.0001);
+        assert!(paths.iter().all(|path| open.is_valid_path(path)));
+
+        assert!(paths.is_sorted());
+        assert_eq!(open.all_shortest_paths(0, 14, 2), paths[..2]);
+        assert_eq!(open.all_shortest_paths(5, 5, 10), [vec![5]]);
+        assert!(graph.all_shortest_paths(3, 4, 10).is_empty());
+        assert!(graph.all_shortest_paths(3, 17, 10).is_empty());
+    }
+
+    #[test]
+    fn snapshot_test() {
+        let mut blocked = [false; 16];
+        blocked[5] = true;
+        let mut graph = grid_graph(4, 4, &blocked);
+        graph.search_graph(0, 15).unwrap();
+        graph.add_node([9.0, 9.0]);
+
+        let snapshot = graph.snapshot();
+        let (positions, states): (Vec<[f32; 2]>, Vec<NodeState>) = graph
+            .get_all_nodes_with_state()
+            .map(|(position, state)| (position, state.clone()))
+            .unzip();
+        assert_eq!(snapshot.positions, positions);
+        assert_eq!(snapshot.states, states);
+        assert_eq!(snapshot.states[16], NodeState::Clear);
+
+        let links: Vec<([f32; 2], [f32; 2], bool)> =
+            graph.get_all_links_with_solution_hint().collect();
+        assert_eq!(snapshot.edges.len(), links.len());
+        for ((node1, node2, solution), (start, end, hint)) in snapshot.edges.iter().zip(links) {
+            assert_eq!(snapshot.positions[*node1], start);
+            assert_eq!(snapshot.positions[*node2], end);
+            assert_eq!(*solution, hint);
+        }
+        assert!(snapshot.edges.iter().any(|(_, _, solution)| *solution));
+    }
+
+    #[test]
+    fn reroute_avoiding_edge_test() {
+        let mut graph = grid_graph(6, 3, &[false; 18]);
+        let path = graph.search_graph(6, 11).unwrap();
+        assert_eq!(path, [6, 7, 8, 9, 10, 11]);
+
+        let rerouted = graph.reroute_avoiding_edge(&path, (9, 8)).unwrap();
+        assert_eq!(rerouted[..3], path[..3]);
+        assert_eq!(rerouted.last(), Some(&11));
+        assert!(graph.is_valid_path(&rerouted));
+        assert!(
+            !rerouted
+                .windows(2)
+                .any(|pair| edge_key(pair[0], pair[1]) == (8, 9))
+        );
+        assert_eq!(graph.get_edge(8, 9), Some(1.0));
+        let cost = graph.path_cost(&rerouted).unwrap();
+        assert!((cost - (3.0 + 2.0 * 2.0_f32.sqrt())).abs() < 0.0001);
+
+        // An edge off the path keeps the path, a bridge leaves no detour.
+        assert_eq!(
+            graph.reroute_avoiding_edge(&path, (0, 1)),
+            Some(path.clone())
+        );
+        assert_eq!(graph.reroute_avoiding_edge(&[], (0, 1)), None);
+        let mut chain = grid_graph(3, 1, &[false; 3]);
+        assert_eq!(chain.reroute_avoiding_edge(&[0, 1, 2], (1, 2)), None);
+    }
+
+    #[test]
+    fn path_corridor_test() {
+        let mut graph = NavGraph::new();
+        let chain: Vec<usize> = (0..4)
+            .map(|index| graph.add_node([index as f32, 0.0]))
+            .collect();
+        for pair in chain.windows(2) {
+            graph.connect_nodes(pair[0], pair[1]).unwrap();
+        }
+        let near_above = graph.add_node([1.5, 0.4]);
+        let near_below = graph.add_node([2.5, -0.5]);
+        let near_end = graph.add_node([3.3, 0.3]);
+        let far_above = graph.add_node([1.5, 2.0]);
+        let far_beyond = graph.add_node([4.0, 0.0]);
+
+        let path = graph.search_graph(chain[0], chain[3]).unwrap();
+        let corridor = graph.path_corridor(&path, 0.5);
+        assert_eq!(
+            corridor,
+            [
+                chain[0], chain[1], chain[2], chain[3], near_above, near_below, near_end
+            ]
+        );
+        assert!(!corridor.contains(&far_above));
+        assert!(!corridor.contains(&far_beyond));
+
+        assert_eq!(
+            graph.path_corridor(&[chain[3]], 1.0),
+            [chain[2], chain[3], near_below, near_end, far_beyond]
+        );
+        assert!(graph.path_corridor(&[], 1.0).is_empty());
+        assert!(graph.path_corridor(&[chain[0], 17], 1.0).is_empty());
+    }
+
+    #[test]
+    fn last_query_test() {
+        let mut graph = grid_graph(3, 3, &[false; 9]);
+        assert_eq!(graph.last_query(), None);
+
+        graph.search_graph(0, 8).unwrap();
+        assert_eq!(graph.last_query(), Some((0, 8)));
+        graph.search_graph_avoiding(2, 6, &[4]).unwrap();
+        assert_eq!(graph.last_query(), Some((2, 6)));
+        graph.search_graph_fewest_hops(1, 7).unwrap();
+        assert_eq!(graph.last_query(), Some((1, 7)));
+        graph.search_graph_cached(3, 5).unwrap();
+        graph.search_graph_cached(0, 8).unwrap();
+        graph.search_graph_cached(3, 5).unwrap();
+        assert_eq!(graph.last_query(), Some((3, 5)));
+
+        // Searches that leave the node states untouched keep the last query.
+        graph.find_path(6, 2).unwrap();
+        assert_eq!(graph.last_query(), Some((3, 5)));
+
+        graph.search_graph_multi_start(&[0, 2], 7).unwrap();
+        assert_eq!(graph.last_query(), None);
+        graph.search_graph(8, 0).unwrap();
+        graph.clear_search_state();
+        assert_eq!(graph.last_query(), None);
+    }
+
+    #[test]
+    fn search_avoiding_test() {
+        let mut graph = NavGraph::new();
+
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let p3 = graph.add_node([1.0, 1.0]);
+
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p3).unwrap();
+        graph.connect_nodes(p3, p2).unwrap();
+
+        assert_eq!(graph.search_graph(p0, p2).unwrap(), [p0, p1, p2]);
+        assert_eq!(
+            graph.search_graph_avoiding(p0, p2, &[p1]).unwrap(),
+            [p0, p3, p2]
+        );
+        assert!(graph.search_graph_avoiding(p0, p2, &[p1, p3]).is_none());
+        assert!(graph.search_graph_avoiding(p0, p2, &[p2]).is_none());
+
+        assert_eq!(
+            graph.search_graph(p0, p2).unwrap(),
+            [p0, p1, p2],
+            "Avoidance is not persistent."
+        );
+    }
+
+    #[test]
+    fn sorted_connections_test() {
+        let mut late = grid_graph(4, 4, &[false; 16]);
+        let mut links: Vec<(usize, usize)> = late.links_in_order().collect();
+        // A fully blocked grid has the nodes but no links.
+        let build = |links: &[(usize, usize)]| {
+            let mut graph = grid_graph(4, 4, &[true; 16]);
+            graph.set_sorted_connections(true);
+            for (node1, node2) in links.iter() {
+                graph.connect_nodes(*node1, *node2).unwrap();
+            }
+            graph
+        };
+        let mut forward = build(&links);
+        links.reverse();
+        for link in links.iter_mut() {
+            *link = (link.1, link.0);
+        }
+        let mut backward = build(&links);
+
+        for start in 0..16 {
+            for destination in 0..16 {
+                assert_eq!(
+                    forward.search_graph(start, destination),
+                    backward.search_graph(start, destination)
+                );
+            }
+        }
+
+        // Removing and re-adding links keeps the order.
+        forward.disconnect_nodes(5, 6).unwrap();
+        forward.disconnect_nodes(0, 1).unwrap();
+        forward.connect_nodes(1, 0).unwrap();
+        forward.connect_nodes(6, 5).unwrap();
+        for node in forward.nodes.iter() {
+            assert!(node.connections.is_sorted_by_key(|(partner, _)| *partner));
+        }
+        assert_eq!(forward.search_graph(0, 15), backward.search_graph(0, 15));
+
+        late.set_sorted_connections(true);
+        assert_eq!(late.search_graph(15, 0), backward.search_graph(15, 0));
+    }
+
+    #[test]
+    fn best_effort_test() {
+        let mut graph = grid_graph(3, 3, &[false; 9]);
+        let island = graph.add_node([6.0, 1.0]);
+        let p9 = graph.add_node([7.0, 1.0]);
+        graph.connect_nodes(island, p9).unwrap();
+
+        assert!(graph.search_graph(0, island).is_none());
+        let path = graph.search_graph_best_effort(0, island);
+        assert_eq!(path.first(), Some(&0));
+        assert_eq!(
+            path.last(),
+            Some(&5),
+            "Node 5 is the reachable node closest to the island."
+        );
+        assert!(graph.is_valid_path(&path));
+        assert_eq!(graph.solution_nodes(), path);
+
+        assert_eq!(graph.search_graph_best_effort(0, 8), [0, 4, 8]);
+        assert_eq!(graph.search_graph_best_effort(island, 0), [island]);
+    }
+
+    #[test]
+    fn multi_start_test() {
+        let mut graph = NavGraph::new();
+        let guard_far = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let target = graph.add_node([3.0, 0.0]);
+        let p4 = graph.add_node([3.0, 1.0]);
+        let guard_near = graph.add_node([3.0, 2.0]);
+        let unreachable = graph.add_node([5.0, 5.0]);
+        graph.connect_nodes(guard_far, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p2, target).unwrap();
+        graph.connect_nodes(target, p4).unwrap();
+        graph.connect_nodes(p4, guard_near).unwrap();
+
+        let (start, path) = graph
+            .search_graph_multi_start(&[guard_far, guard_near], target)
+            .unwrap();
+        assert_eq!(start, guard_near);
+        assert_eq!(path, [guard_near, p4, target]);
+        assert_eq!(
+            graph.get_all_nodes_with_state().nth(guard_far).unwrap().1,
+            &NodeState::Visited
+        );
+
+        assert_eq!(graph.search_graph_multi_start(&[unreachable], target), None);
+        assert_eq!(graph.search_graph_multi_start(&[], target), None);
+    }
+
+    /// Builds the graph of the sample code in the readme and returns it with the handles of its nodes.
+    fn readme_graph() -> (NavGraph, [usize; 5]) {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([0.5, 0.5]);
+        let p2 = graph.add_node([1.0, 0.0]);
+        let p3 = graph.add_node([1.0, 1.0]);
+        let p4 = graph.add_node([0.1, 0.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p2).unwrap();
+        graph.connect_nodes(p1, p4).unwrap();
+        graph.connect_nodes(p4, p3).unwrap();
+        graph.connect_nodes(p2, p3).unwrap();
+        (graph, [p0, p1, p2, p3, p4])
+    }
+
+    /// Builds a graph for a grid with unit spacing, with straight and diagonal links between free cells
+    /// that do not cut corners of blocked cells.
+    fn grid_graph(cols: usize, rows: usize, blocked: &[bool]) -> NavGraph {
+        let mut graph = NavGraph::new();
+        for y in 0..rows {
+            for x in 0..cols {
+                graph.add_node([x as f32, y as f32]);
+            }
+        }
+        let free = |x: usize, y: usize| x < cols && y < rows && !blocked[y * cols + x];
+        for y in 0..rows {
+            for x in 0..cols {
+                if !free(x, y) {
+                    continue;
+                }
+                let index = y * cols + x;
+                if free(x + 1, y) {
+                    graph.connect_nodes(index, index + 1).unwrap();
+                }
+                if free(x, y + 1) {
+                    graph.connect_nodes(index, index + cols).unwrap();
+                }
+                if free(x + 1, y + 1) && free(x + 1, y) && free(x, y + 1) {
+                    graph.connect_nodes(index, index + cols + 1).unwrap();
+                }
+                if x > 0 && free(x - 1, y + 1) && free(x - 1, y) && free(x, y + 1) {
+                    graph.connect_nodes(index, index + cols - 1).unwrap();
+                }
+            }
+        }
+        graph
+    }
+
+    #[test]
+    fn jump_point_search_test() {
+        let (cols, rows) = (20, 20);
+        let mut blocked = vec![false; cols * rows];
+
+        // On the open grid both searches find equally short paths, but jump point search expands far less.
+        let mut graph = grid_graph(cols, rows, &blocked);
+        let (start, destination) = (2 * cols + 1, 15 * cols + 18);
+        let a_star_path = graph.search_graph(start, destination).unwrap();
+        let a_star_expansions = graph.closed_nodes().count();
+        let (jps_path, jps_expansions) =
+            graph.jump_point_search(cols, rows, &blocked, start, destination);
+        let jps_path = jps_path.unwrap();
+        assert!(graph.is_valid_path(&jps_path));
+        assert_eq!(jps_path[0], start);
+        let a_star_cost = graph.path_cost(&a_star_path).unwrap();
+        assert!((graph.path_cost(&jps_path).unwrap() - a_star_cost).abs() < 0.001);
+        assert!(jps_expansions * 4 < a_star_expansions);
+
+        // With a wall in between, the path has to go around it.
+        for y in 0..15 {
+            blocked[y * cols + 10] = true;
+        }
+        let mut graph = grid_graph(cols, rows, &blocked);
+        let a_star_path = graph.search_graph(start, destination).unwrap();
+        let jps_path = graph
+            .search_grid_jps(cols, rows, &blocked, start, destination)
+            .unwrap();
+        assert!(graph.is_valid_path(&jps_path));
+        let a_star_cost = graph.path_cost(&a_star_path).unwrap();
+        assert!((graph.path_cost(&jps_path).unwrap() - a_star_cost).abs() < 0.001);
+
+        // Closing the wall makes the destination unreachable.
+        for y in 15..rows {
+            blocked[y * cols + 10] = true;
+        }
+        assert_eq!(
+            graph.search_grid_jps(cols, rows, &blocked, start, destination),
+            None
+        );
+        assert_eq!(
+            graph.search_grid_jps(cols, rows + 1, &blocked, start, destination),
+            None
+        );
+    }
+
+    #[test]
+    fn tag_mask_test() {
+        const LAND: u32 = 1;
+        const WATER: u32 = 2;
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let p3 = graph.add_node([1.0, 1.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p3).unwrap();
+        graph.connect_nodes(p3, p2).unwrap();
+        graph.set_edge_tags(p0, p1, WATER).unwrap();
+        graph.set_edge_tags(p1, p2, WATER).unwrap();
+        graph.set_edge_tags(p0, p3, LAND).unwrap();
+        graph.set_edge_tags(p2, p3, LAND).unwrap();
+        graph.set_node_tags(p1, WATER).unwrap();
+
+        assert_eq!(
+            graph.search_graph_with_mask(p0, p2, LAND | WATER).unwrap(),
+            [p0, p1, p2]
+        );
+        assert_eq!(
+            graph.search_graph_with_mask(p0, p2, LAND).unwrap(),
+            [p0, p3, p2]
+        );
+        assert_eq!(graph.search_graph_with_mask(p0, p1, LAND), None);
+        assert_eq!(graph.node_tags(p1), Some(WATER));
+        assert_eq!(graph.node_tags(p0), Some(ALL_TAGS));
+        assert_eq!(
+            graph.set_edge_tags(p1, p3, LAND),
+            Err(ConnectionError::LinkDoesntExist)
+        );
+
+        // Split edges inherit the tags, removed edges lose them.
+        let p4 = graph.split_edge(p1, p2, [1.5, 0.0]).unwrap();
+        assert_eq!(graph.edge_tags(p1, p4), Some(WATER));
+        assert_eq!(graph.edge_tags(p4, p2), Some(WATER));
+        graph.disconnect_nodes(p0, p3).unwrap();
+        graph.connect_nodes(p0, p3).unwrap();
+        assert_eq!(graph.edge_tags(p0, p3), Some(ALL_TAGS));
+    }
+
+    /// Builds a graph with a detour, where a heuristic that overestimates leads the search to a suboptimal path.
+    fn detour_graph() -> NavGraph {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let p3 = graph.add_node([1.0, 2.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p3).unwrap();
+        graph.connect_nodes(p3, p2).unwrap();
+        graph
+    }
+
+    #[test]
+    fn custom_heuristic_test() {
+        let mut graph = detour_graph();
+        graph.set_heuristic_check(true);
+        let euclidean = |a: [f32; 2], b: [f32; 2]| Vec2::from(a).dist_to(&Vec2::from(b));
+        assert_eq!(
+            graph.search_graph_with_heuristic(0, 2, euclidean).unwrap(),
+            [0, 1, 2]
+        );
+        assert_eq!(
+            graph.search_graph_with_heuristic(0, 2, |_, _| 0.0).unwrap(),
+            [0, 1, 2]
+        );
+    }
+
+    #[test]
+    #[cfg(debug_assertions)]
+    #[should_panic(expected = "not admissible")]
+    fn inadmissible_heuristic_test() {
+        let mut graph = detour_graph();
+        graph.set_heuristic_check(true);
+        let inflated = |a: [f32; 2], b: [f32; 2]| 10.0 * Vec2::from(a).dist_to(&Vec2::from(b));
+        graph.search_graph_with_heuristic(0, 2, inflated);
+    }
+
+    #[test]
+    fn path_clearance_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let p3 = graph.add_node([3.0, 0.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p2, p3).unwrap();
+        graph.set_edge_clearance(p0, p1, 2.0).unwrap();
+        graph.set_edge_clearance(p2, p1, 0.3).unwrap();
+        graph.set_edge_tags(p2, p1, 4).unwrap();
+
+        assert_eq!(graph.path_min_clearance(&[p0, p1, p2, p3]), Some(0.3));
+        assert_eq!(graph.path_min_clearance(&[p0, p1]), Some(2.0));
+        assert_eq!(graph.path_min_clearance(&[p2, p3]), Some(f32::INFINITY));
+        assert_eq!(graph.path_min_clearance(&[p3]), Some(f32::INFINITY));
+        assert_eq!(graph.path_min_clearance(&[p0, p2]), None);
+        assert_eq!(graph.path_min_clearance(&[p0, 8]), None);
+        assert_eq!(
+            graph.set_edge_clearance(p0, p3, 1.0),
+            Err(ConnectionError::LinkDoesntExist)
+        );
+
+        // The clearance and the tags are independent attributes, and both survive splitting an edge.
+        assert_eq!(graph.edge_tags(p1, p2), Some(4));
+        let p4 = graph.split_edge(p1, p2, [1.5, 0.0]).unwrap();
+        assert_eq!(graph.edge_clearance(p4, p2), Some(0.3));
+        assert_eq!(graph.edge_tags(p1, p4), Some(4));
+    }
+
+    #[test]
+    fn elevation_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let p3 = graph.add_node([1.0, 1.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p3).unwrap();
+        graph.connect_nodes(p3, p2).unwrap();
+
+        // Without heights, the elevation search is the plain search.
+        assert_eq!(
+            graph.search_graph_with_elevation(p0, p2).unwrap(),
+            [p0, p1, p2]
+        );
+
+        // A gentle hill is still cheaper than the detour of about 2.83.
+        graph.set_node_height(p1, 0.5).unwrap();
+        assert_eq!(
+            graph.search_graph_with_elevation(p0, p2).unwrap(),
+            [p0, p1, p2]
+        );
+
+        // The steep climb costs about 10.2, so the flat detour wins.
+        graph.set_node_height(p1, 5.0).unwrap();
+        assert_eq!(
+            graph.search_graph_with_elevation(p0, p2).unwrap(),
+            [p0, p3, p2]
+        );
+        assert_eq!(graph.search_graph(p0, p2).unwrap(), [p0, p1, p2]);
+
+        // Raising the whole detour by the same amount does not matter, only differences count.
+        graph.set_node_height(p0, 5.0).unwrap();
+        graph.set_node_height(p2, 5.0).unwrap();
+        graph.set_node_height(p3, 5.0).unwrap();
+        assert_eq!(
+            graph.search_graph_with_elevation(p0, p2).unwrap(),
+            [p0, p1, p2]
+        );
+
+        assert_eq!(graph.node_height(p3), Some(5.0));
+        assert_eq!(graph.node_height(7), None);
+        assert_eq!(
+            graph.set_node_height(7, 1.0),
+            Err(ConnectionError::NodeDoesntExist(7))
+        );
+    }
+
+    #[test]
+    fn search_with_danger_test() {
+        let build_graph = |detour_height: f32| {
+            let mut graph = NavGraph::new();
+            let p0 = graph.add_node([0.0, 0.0]);
+            let p1 = graph.add_node([1.0, 0.0]);
+            let p2 = graph.add_node([2.0, 0.0]);
+            let p3 = graph.add_node([1.0, detour_height]);
+            graph.connect_nodes(p0, p1).unwrap();
+            graph.connect_nodes(p1, p2).unwrap();
+            graph.connect_nodes(p0, p3).unwrap();
+            graph.connect_nodes(p3, p2).unwrap();
+            graph
+        };
+        let danger = [0.0, 1.5, 0.0, 0.0];
+
+        let mut small_detour = build_graph(0.5);
+        assert_eq!(small_detour.search_graph(0, 2).unwrap(), [0, 1, 2]);
+        assert_eq!(
+            small_detour
+                .search_graph_with_danger(0, 2, &danger)
+                .unwrap(),
+            [0, 3, 2]
+        );
+
+        let mut huge_detour = build_graph(5.0);
+        assert_eq!(
+            huge_detour.search_graph_with_danger(0, 2, &danger).unwrap(),
+            [0, 1, 2]
+        );
+        assert_eq!(
+            huge_detour.search_graph_with_danger(0, 2, &[]).unwrap(),
+            [0, 1, 2]
+        );
+    }
+
+    #[test]
+    fn d_star_lite_test() {
+        let (mut graph, [p0, p1, p2, p3, p4]) = readme_graph();
+
+        let mut search = graph.begin_dstar(p0, p3);
+        assert_eq!(search.replan(), graph.search_graph(p0, p3));
+
+        search.update_edge(p2, p3, f32::INFINITY).unwrap();
+        graph.disconnect_nodes(p2, p3).unwrap();
+        assert_eq!(search.replan(), graph.search_graph(p0, p3));
+
+        search.move_start(p1);
+        assert_eq!(search.replan(), graph.search_graph(p1, p3));
+
+        search.update_edge(p4, p3, f32::INFINITY).unwrap();
+        assert!(search.replan().is_none());
+
+        search.update_edge(p4, p3, 0.1).unwrap();
+        assert_eq!(search.replan().unwrap(), [p1, p4, p3]);
+
+        assert_eq!(
+            search.update_edge(p0, p3, 1.0),
+            Err(ConnectionError::LinkDoesntExist)
+        );
+    }
+
+    #[test]
+    fn generation_test() {
+        let mut graph = NavGraph::new();
+        let mut last = graph.generation();
+        let mut assert_bumped = |graph: &NavGraph, operation: &str| {
+            assert!(
+                graph.generation() > last,
+                "{operation} should bump the generation."
+            );
+            last = graph.generation();
+        };
+
+        let p0 = graph.add_node([0.0, 0.0]);
+        assert_bumped(&graph, "add_node");
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([1.0, 1.0]);
+        assert_bumped(&graph, "add_node");
+        graph.connect_nodes(p0, p1).unwrap();
+        assert_bumped(&graph, "connect_nodes");
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p2).unwrap();
+        assert_bumped(&graph, "connect_nodes");
+        graph.disconnect_nodes(p0, p2).unwrap();
+        assert_bumped(&graph, "disconnect_nodes");
+        graph.set_node_position(p2, [1.0, 2.0]).unwrap();
+        assert_bumped(&graph, "set_node_position");
+        graph.recompute_edge_costs();
+        assert_bumped(&graph, "recompute_edge_costs");
+        graph.set_node_cost_multiplier(p1, 2.0).unwrap();
+        assert_bumped(&graph, "set_node_cost_multiplier");
+        graph.set_node_tags(p1, 4).unwrap();
+        assert_bumped(&graph, "set_node_tags");
+        graph.set_node_height(p1, 4.0).unwrap();
+        assert_bumped(&graph, "set_node_height");
+        graph.set_edge_tags(p0, p1, 4).unwrap();
+        assert_bumped(&graph, "set_edge_tags");
+        graph.set_edge_clearance(p0, p1, 0.5).unwrap();
+        assert_bumped(&graph, "set_edge_clearance");
+        graph.set_links(&[]).unwrap();
+        assert_bumped(&graph, "set_links");
+        graph.set_links(&[(p0, p1), (p1, p2)]).unwrap();
+        assert_bumped(&graph, "set_links");
+        graph.set_sorted_connections(true);
+        assert_bumped(&graph, "set_sorted_connections");
+        graph.reorder_spatially();
+        assert_bumped(&graph, "reorder_spatially");
+
+        // Queries, searches and failing modifications keep the generation.
+        graph.search_graph(p0, p2).unwrap();
+        graph.find_path(p0, p2).unwrap();
+        graph.stats();
+        graph.reserve_node(p0, 1).unwrap();
+        assert!(graph.connect_nodes(p0, p0).is_err());
+        assert!(graph.set_node_tags(17, 0).is_err());
+        assert_eq!(graph.generation(), last);
+
+        // A search created from the graph detects later changes.
+        let search = graph.begin_dstar(p0, p2);
+        assert!(!search.is_stale(&graph));
+        graph.connect_nodes(p0, p2).unwrap();
+        assert!(search.is_stale(&graph));
+        let search = graph.begin_dstar(p0, p2);
+        assert!(!search.is_stale(&graph));
+        let (subgraph, _) = graph.subgraph_within_radius([0.0, 0.0], 10.0);
+        assert!(search.is_stale(&subgraph));
+        assert!(search.is_stale(&NavGraph::new()));
+    }
+
+    #[test]
+    fn all_paths_test() {
+        let mut graph = NavGraph::new();
+
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let p3 = graph.add_node([1.0, 1.0]);
+        let p4 = graph.add_node([5.0, 5.0]);
+
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p3).unwrap();
+        graph.connect_nodes(p3, p2).unwrap();
+
+        let paths = graph.all_paths(p0, p2, 10.0, 10);
+        assert_eq!(paths.len(), 2);
+        assert_eq!(paths[0], (vec![p0, p1, p2], 2.0));
+        assert_eq!(paths[1].0, [p0, p3, p2]);
+        assert!((paths[1].1 - 2.0 * 2.0_f32.sqrt()).abs() < 0.0001);
+
+        assert_eq!(graph.all_paths(p0, p2, 2.5, 10).len(), 1, "Cost budget.");
+        assert_eq!(graph.all_paths(p0, p2, 10.0, 1).len(), 1, "Path cap.");
+        assert!(graph.all_paths(p0, p4, 100.0, 10).is_empty());
+        assert!(
+            graph.all_paths(17, p2, 100.0, 10).is_empty(),
+            "Invalid start."
+        );
+        assert!(
+            graph.all_paths(p0, 17, 100.0, 10).is_empty(),
+            "Invalid end."
+        );
+        assert!(
+            graph.all_paths(p0, p2, 10.0, 0).is_empty(),
+            "No path requested."
+        );
+        assert!(graph.all_paths(p0, p0, 10.0, 0).is_empty());
+        assert_eq!(graph.all_paths(p0, p0, 10.0, 1), [(vec![p0], 0.0)]);
+    }
+
+    #[test]
+    fn cooperative_search_test() {
+        // A crossing, where the first agent moves from west to east and the second one from south to north.
+        let mut graph = NavGraph::new();
+        let center = graph.add_node([0.0, 0.0]);
+        let west = graph.add_node([-1.0, 0.0]);
+        let east = graph.add_node([1.0, 0.0]);
+        let south = graph.add_node([0.0, -1.0]);
+        let north = graph.add_node([0.0, 1.0]);
+        for node in [west, east, south, north] {
+            graph.connect_nodes(center, node).unwrap();
+        }
+
+        let first = graph.search_graph_cooperative(west, east, 0).unwrap();
+        assert_eq!(first, [west, center, east]);
+        assert_eq!(
+            graph.search_graph_cooperative(south, north, 0).unwrap(),
+            [south, center, north],
+            "Without reservations, both agents are in the center at time 1."
+        );
+        graph.reserve_path(&first, 0).unwrap();
+        let second = graph.search_graph_cooperative(south, north, 0).unwrap();
+        assert_eq!(
+            second,
+            [south, south, center, north],
+            "The second agent waits."
+        );
+        for (time, node) in second.iter().enumerate() {
+            assert_ne!(first.get(time), Some(node));
+        }
+
+        // With a bypass, the second agent reroutes instead of waiting.
+        let bypass = graph.add_node([0.5, 0.0]);
+        graph.connect_nodes(south, bypass).unwrap();
+        graph.connect_nodes(bypass, north).unwrap();
+        assert_eq!(
+            graph.search_graph_cooperative(south, north, 0).unwrap(),
+            [south, bypass, north]
+        );
+
+        // The destination has to stay free after the arrival, and the start time shifts everything.
+        graph.reserve_node(north, 5).unwrap();
+        assert_eq!(
+            graph
+                .search_graph_cooperative(center, north, 0)
+                .unwrap()
+                .len(),
+            7
+        );
+        assert_eq!(
+            graph.search_graph_cooperative(center, north, 6).unwrap(),
+            [center, north]
+        );
+
+        let island = graph.add_node([5.0, 5.0]);
+        assert!(graph.search_graph_cooperative(center, island, 0).is_none());
+        assert_eq!(
+            graph.reserve_node(island + 1, 0),
+            Err(ConnectionError::NodeDoesntExist(island + 1))
+        );
+        assert_eq!(
+            graph.reserve_path(&[center, island + 1], 0),
+            Err(ConnectionError::NodeDoesntExist(island + 1))
+        );
+        graph.clear_reservations();
+        assert!(!graph.is_reserved(center, 1));
+    }
+
+    #[test]
+    fn k_shortest_paths_test() {
+        let mut graph = NavGraph::new();
+
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let p3 = graph.add_node([1.0, 0.5]);
+        let p4 = graph.add_node([1.0, 2.0]);
+
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p3).unwrap();
+        graph.connect_nodes(p3, p2).unwrap();
+        graph.connect_nodes(p0, p4).unwrap();
+        graph.connect_nodes(p4, p2).unwrap();
+        graph.connect_nodes(p1, p3).unwrap();
+
+        let paths = graph.k_shortest_paths(p0, p2, 3);
+        assert_eq!(paths.len(), 3);
+        assert_eq!(paths[0], (vec![p0, p1, p2], 2.0));
+        assert_eq!(paths[1].0, [p0, p3, p2]);
+        assert!(paths[0].1 <= paths[1].1 && paths[1].1 <= paths[2].1);
+
+        let all_paths = graph.all_paths(p0, p2, 100.0, 100);
+        let paths = graph.k_shortest_paths(p0, p2, 100);
+        assert_eq!(paths.len(), all_paths.len(), "All loopless paths found.");
+        for ((path, cost), (expected_path, expected_cost)) in paths.iter().zip(all_paths.iter()) {
+            assert!(graph.is_valid_path(path));
+            assert!(
+                (cost - expected_cost).abs() < 0.0001,
+                "{path:?} {expected_path:?}"
+            );
+        }
+
+        assert!(graph.k_shortest_paths(p0, p2, 0).is_empty());
+    }
+
+    #[test]
+    fn search_with_scratch_test() {
+        let (mut graph, [p0, _, p2, p3, p4]) = readme_graph();
+
+        let expected_first = graph.search_graph(p0, p3);
+        let expected_second = graph.search_graph(p4, p2);
+        graph.clear_search_state();
+
+        let mut first_scratch = SearchScratch::new();
+        let mut second_scratch = SearchScratch::new();
+        let shared = &graph;
+        let (first, second) = std::thread::scope(|scope| {
+            let first =
+                scope.spawn(|| shared.search_graph_with_scratch(p0, p3, &mut first_scratch));
+            let second =
+                scope.spawn(|| shared.search_graph_with_scratch(p4, p2, &mut second_scratch));
+            (first.join().unwrap(), second.join().unwrap())
+        });
+        assert_eq!(first, expected_first);
+        assert_eq!(second, expected_second);
+
+        assert_eq!(first_scratch.node_state(p3), Some(&NodeState::Solution));
+        assert_ne!(second_scratch.node_state(p3), Some(&NodeState::Solution));
+        assert_eq!(second_scratch.node_state(p2), Some(&NodeState::Solution));
+        assert!(
+            graph
+                .get_all_nodes_with_state()
+                .all(|(_, state)| *state == NodeState::Clear),
+            "The graph itself stays untouched."
+        );
+    }
+
+    #[test]
+    fn find_path_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([0.5, 0.5]);
+        let p2 = graph.add_node([1.0, 0.0]);
+        let p3 = graph.add_node([2.0, 0.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p2, p3).unwrap();
+
+        assert_eq!(graph.find_path(p0, p3).unwrap(), [p0, p1, p2, p3]);
+        assert!(
+            graph
+                .get_all_nodes_with_state()
+                .all(|(_, state)| *state == NodeState::Clear)
+        );
+
+        // The markings of a previous search stay as they are.
+        graph.search_graph(p2, p3).unwrap();
+        let before: Vec<NodeState> = graph
+            .get_all_nodes_with_state()
+            .map(|(_, state)| state.clone())
+            .collect();
+        assert_eq!(graph.find_path(p3, p0).unwrap(), [p3, p2, p1, p0]);
+        let after: Vec<NodeState> = graph
+            .get_all_nodes_with_state()
+            .map(|(_, state)| state.clone())
+            .collect();
+        assert_eq!(before, after);
+    }
+
+    #[test]
+    fn search_session_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([0.0, 1.0]);
+        let p3 = graph.add_node([2.0, 0.0]);
+        let p4 = graph.add_node([5.0, 5.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p0, p2).unwrap();
+        graph.connect_nodes(p1, p3).unwrap();
+
+        let mut session = graph.begin_search(p0, p3);
+        assert_eq!(session.frontier(), [p0]);
+        assert_eq!(session.step(), SearchStatus::Running);
+        let mut frontier = session.frontier().to_vec();
+        frontier.sort();
+        assert_eq!(frontier, [p1, p2]);
+        assert_eq!(session.node_state(p0), Some(&NodeState::Closed));
+        let mut status = session.step();
+        while status == SearchStatus::Running {
+            status = session.step();
         }
-        None
-    }
+        assert_eq!(status, SearchStatus::Found(vec![p0, p1, p3]));
+        assert_eq!(session.step(), status);
+        assert_eq!(Some(vec![p0, p1, p3]), graph.find_path(p0, p3));
 
-    /// Connects two graph nodes with indicated indices.
-    ///
-    /// # Error
-    /// For the case that nodes do not exist, that indices handed over are the same, or that such a link has already been
-    /// established that an error is returned.
-    ///
-    /// # Example
-    /// ```
-    /// use astar_lib::a_star::NavGraph;
-    /// let mut graph = NavGraph::new();
-    /// let p0 = graph.add_node([0.0, 0.0]);
-    /// let p1 = graph.add_node([1.0, 1.0]);
-    /// graph.connect_nodes(p0, p1).unwrap();
-    /// ```
-    pub fn connect_nodes(&mut self, node1: usize, node2: usize) -> Result<(), ConnectionError> {
-        if node1 == node2 {
-            return Err(ConnectionError::NodeDoubled);
+        let mut session = graph.begin_search(p0, p4);
+        let mut steps = 0;
+        while session.step() == SearchStatus::Running {
+            steps += 1;
         }
+        assert_eq!(steps, 4);
+        assert!(session.frontier().is_empty());
+        assert_eq!(session.step(), SearchStatus::NoPath);
+    }
+
+    #[test]
+    fn iteration_cap_test() {
+        let graph = grid_graph(4, 4, &[false; 16]);
+        let mut progress = graph.search_graph_with_iteration_cap(0, 15, 1);
+        let mut frames = 1;
+        let path = loop {
+            match progress {
+                SearchProgress::Found(path) => break path,
+                SearchProgress::Exhausted => panic!("The destination is reachable."),
+                SearchProgress::Incomplete(session) => {
+                    assert_ne!(session.node_state(15), Some(&NodeState::Closed));
+                    frames += 1;
+                    progress = session.resume(1);
+                }
+            }
+        };
+        assert_eq!(path, [0, 5, 10, 15]);
+        assert_eq!(frames, 4, "Every frame closes one node of the diagonal.");
+        assert!(matches!(
+            graph.search_graph_with_iteration_cap(0, 15, 100),
+            SearchProgress::Found(_)
+        ));
+        assert!(matches!(
+            graph.search_graph_with_iteration_cap(0, 15, 0),
+            SearchProgress::Incomplete(_)
+        ));
+
+        let mut graph = grid_graph(2, 1, &[false; 2]);
+        let island = graph.add_node([5.0, 5.0]);
+        let SearchProgress::Incomplete(session) =
+            graph.search_graph_with_iteration_cap(0, island, 1)
+        else {
+            panic!("The cap should be hit first.");
+        };
+        assert!(matches!(session.resume(5), SearchProgress::Exhausted));
+    }
+
+    #[test]
+    fn heuristic_cache_test() {
+        let mut graph = grid_graph(4, 4, &[false; 16]);
+        let goal = 15;
 
-        if node1 > self.nodes.len() {
-            return Err(ConnectionError::NodeDoesntExist(node1));
+        let mut scratch = SearchScratch::new();
+        for start in [0, 4, 5, 9, 10, 14, 15] {
+            assert_eq!(
+                graph.search_graph_with_scratch(start, goal, &mut scratch),
+                graph.find_path(start, goal)
+            );
+            assert_eq!(scratch.heuristic_key, Some((goal, graph.layout_id)));
         }
 
-        if node2 > self.nodes.len() {
-            return Err(ConnectionError::NodeDoesntExist(node2));
+        // Moving a node invalidates the cache.
+        graph.set_node_position(10, [9.0, 9.0]).unwrap();
+        graph.recompute_edge_costs();
+        assert_ne!(scratch.heuristic_key, Some((goal, graph.layout_id)));
+        for start in [0, 5, 10] {
+            assert_eq!(
+                graph.search_graph_with_scratch(start, goal, &mut scratch),
+                graph.find_path(start, goal)
+            );
         }
-        if self.get_link_index(node1, node2).is_some() {
-            return Err(ConnectionError::LinkAlreadyExists);
+        assert_eq!(
+            scratch.heuristic_values[10],
+            Vec2::new(9.0, 9.0).dist_to(&Vec2::new(3.0, 3.0))
+        );
+    }
+
+    #[test]
+    fn anytime_search_test() {
+        let mut graph = NavGraph::new();
+        let start = graph.add_node([0.0, 0.0]);
+        let goal = graph.add_node([10.0, 0.0]);
+        let detour = graph.add_node([9.0, 3.0]);
+        let shortcut = graph.add_node([3.0, -1.0]);
+        let far = graph.add_node([20.0, 20.0]);
+        graph.connect_nodes(start, detour).unwrap();
+        graph.connect_nodes(detour, goal).unwrap();
+        graph.connect_nodes(start, shortcut).unwrap();
+        graph.connect_nodes(shortcut, goal).unwrap();
+
+        // The greedy first search takes the detour close to the goal.
+        let mut search = graph.begin_anytime(start, goal, 5.0);
+        assert_eq!(search.best_path().unwrap(), [start, detour, goal]);
+        let mut costs = vec![search.best_cost().unwrap()];
+        let mut weights = vec![search.weight()];
+        while !search.is_optimal() {
+            search.improve();
+            costs.push(search.best_cost().unwrap());
+            weights.push(search.weight());
         }
+        assert!(costs.windows(2).all(|pair| pair[1] <= pair[0]));
+        assert!(weights.windows(2).all(|pair| pair[1] < pair[0]));
+        assert!(costs[0] > costs[costs.len() - 1]);
+        assert_eq!(
+            search.best_path().unwrap(),
+            graph.find_path(start, goal).unwrap()
+        );
+        assert_eq!(search.improve().unwrap(), [start, shortcut, goal]);
+        assert_eq!(search.weight(), 1.0);
 
-        let dist = self.nodes[node1]
-            .position
-            .dist_to(&self.nodes[node2].position);
-        self.nodes[node1].connections.push((node2, dist));
-        self.nodes[node2].connections.push((node1, dist));
-        self.links.push((node1, node2));
+        let mut unreachable = graph.begin_anytime(start, far, 2.0);
+        assert!(unreachable.best_path().is_none());
+        assert!(unreachable.improve().is_none());
+    }
 
-        Ok(())
+    #[test]
+    fn search_context_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([2.0, 0.0]);
+        let p3 = graph.add_node([0.0, 1.0]);
+        let p4 = graph.add_node([5.0, 5.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.connect_nodes(p0, p3).unwrap();
+        let graph = &graph;
+
+        let first = graph.search(p0, p2);
+        let second = graph.search(p3, p1);
+        let third = graph.search(p0, p4);
+        assert_eq!(first.path().unwrap(), [p0, p1, p2]);
+        assert_eq!(second.path().unwrap(), [p3, p0, p1]);
+        assert!(third.path().is_none());
+        assert_eq!(
+            first.path_positions().unwrap(),
+            [[0.0, 0.0], [1.0, 0.0], [2.0, 0.0]]
+        );
+
+        assert_eq!(first.node_state(p2), Some(&NodeState::Solution));
+        assert_eq!(second.node_state(p2), Some(&NodeState::Clear));
+        assert_eq!(first.node_state(p3), Some(&NodeState::Visited));
+        assert_eq!(second.node_state(p3), Some(&NodeState::Solution));
+        assert_eq!(first.expanded(), 3);
+        assert_eq!(third.expanded(), 4);
+        assert_eq!(first.node_state(9), None);
     }
 
-    /// Removes an already existing connection between two nodes.
-    /// In the case of a game, this would be a closing door.
-    ///
-    /// # Error
-    /// Returns an error if the link does not exist.
-    ///
-    /// # Example
-    /// ```
-    /// use astar_lib::a_star::NavGraph;
-    /// let mut graph = NavGraph::new();
-    /// let p0 = graph.add_node([0.0, 0.0]);
-    /// let p1 = graph.add_node([1.0, 1.0]);
-    /// graph.connect_nodes(p0, p1).unwrap();
-    /// graph.disconnect_nodes(p1, p0).unwrap();
-    pub fn disconnect_nodes(&mut self, node1: usize, node2: usize) -> Result<(), ConnectionError> {
-        if let Some(link) = self.get_link_index(node1, node2) {
-            self.links.remove(link);
+    #[test]
+    fn memory_bounded_search_test() {
+        let (cols, rows) = (20, 20);
+        let mut blocked = vec![false; cols * rows];
+        for y in 0..(rows - 1) {
+            blocked[y * cols + 10] = true;
+        }
+        let mut graph = grid_graph(cols, rows, &blocked);
+        let start = 0;
+        let goal = 19;
+        let max_open = 8;
 
-            let first_ind = self.nodes[node1]
-                .connections
-                .iter()
-                .position(|(element, _)| *element == node2)
-                .unwrap();
-            self.nodes[node1].connections.swap_remove(first_ind);
-            let second_ind = self.nodes[node2]
-                .connections
-                .iter()
-                .position(|(element, _)| *element == node1)
-                .unwrap();
-            self.nodes[node2].connections.swap_remove(second_ind);
-            return Ok(());
+        let mut session = graph.begin_search(start, goal);
+        let mut largest_frontier = 0;
+        while session.step() == SearchStatus::Running {
+            largest_frontier = largest_frontier.max(session.frontier().len());
         }
-        Err(ConnectionError::LinkDoesntExist)
+        assert!(largest_frontier > 2 * max_open);
+        let optimal = graph.find_path(start, goal).unwrap();
+
+        let path = graph
+            .search_graph_memory_bounded(start, goal, max_open)
+            .unwrap();
+        assert!(graph.is_valid_path(&path));
+        assert_eq!((path[0], path[path.len() - 1]), (start, goal));
+        assert!(graph.last_search.open_list.len() <= max_open);
+        assert!(graph.search_cost_of_path(&path) >= graph.search_cost_of_path(&optimal) - 0.0001);
+
+        // With enough memory, it is a plain A* search.
+        let unbounded = graph
+            .search_graph_memory_bounded(start, goal, usize::MAX)
+            .unwrap();
+        assert_eq!(unbounded, optimal);
+        let isolated = graph.add_node([50.0, 50.0]);
+        assert!(
+            graph
+                .search_graph_memory_bounded(start, isolated, max_open)
+                .is_none()
+        );
     }
 
-    fn reset_graph_search(&mut self) {
-        for node in self.nodes.iter_mut() {
-            node.reset();
+    #[test]
+    fn indices_only_test() {
+        let (mut graph, [p0, _, p2, p3, p4]) = readme_graph();
+
+        let mut scratch = SearchScratch::new();
+        for (start, destination) in [(p0, p3), (p3, p4), (p2, p2)] {
+            let path = graph.find_path_indices_only(start, destination, &mut scratch);
+            assert_eq!(path, graph.search_graph(start, destination));
+            assert!(
+                scratch
+                    .states
+                    .iter()
+                    .all(|state| *state != NodeState::Solution)
+            );
+            assert_eq!(scratch.node_state(destination), Some(&NodeState::Closed));
         }
+
+        // The scratch marks the solution again in normal searches.
+        graph.search_graph_with_scratch(p0, p3, &mut scratch);
+        assert_eq!(scratch.node_state(p3), Some(&NodeState::Solution));
     }
 
-    fn get_path(&mut self, start_index: usize, destination_index: usize) -> Vec<usize> {
-        let mut path: Vec<usize> = Vec::new();
-        let mut scan = destination_index;
+    #[test]
+    fn search_batch_test() {
+        let (mut graph, [p0, p1, p2, p3, p4]) = readme_graph();
+        let p5 = graph.add_node([3.0, 3.0]);
 
-        while scan != start_index {
-            path.push(scan);
-            self.nodes[scan].state = NodeState::Solution;
-            scan = self.nodes[scan].ancestor_node;
+        let queries = [(p0, p3), (p4, p2), (p3, p3), (p0, p5), (p3, p0), (p1, p4)];
+        let batch = graph.search_batch(&queries);
+        let individual: Vec<Option<Vec<usize>>> = queries
+            .iter()
+            .map(|(start, destination)| graph.search_graph(*start, *destination))
+            .collect();
+        assert_eq!(batch, individual);
+        assert!(graph.search_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn edge_usage_test() {
+        // Two grids connected by a single bridge.
+        let mut graph = grid_graph(2, 2, &[false; 4]);
+        for position in [[5.0, 0.0], [6.0, 0.0], [5.0, 1.0], [6.0, 1.0]] {
+            graph.add_node(position);
         }
-        self.nodes[scan].state = NodeState::Solution;
-        path.push(scan);
-        path.reverse();
-        path
+        graph.connect_nodes(4, 5).unwrap();
+        graph.connect_nodes(4, 6).unwrap();
+        graph.connect_nodes(5, 7).unwrap();
+        graph.connect_nodes(6, 7).unwrap();
+        graph.connect_nodes(1, 4).unwrap();
+
+        let queries = [(0, 7), (2, 5), (3, 6), (0, 4), (5, 2), (0, 3)];
+        let usage = graph.edge_usage(&queries);
+        assert_eq!(usage.len(), graph.links_in_order().count());
+        let bridge = graph
+            .links_in_order()
+            .position(|link| link == (1, 4))
+            .unwrap();
+        assert_eq!(usage[bridge], 5);
+        assert_eq!(usage.iter().max(), Some(&5));
+        assert_eq!(graph.edge_usage(&[(0, 0)]), vec![0; usage.len()]);
     }
 
-    /// Does the real search from the start point to the end point of the graph. This method is the real search operation.
-    /// # Parameters:
-    /// * start: The start point to start searching for,
-    /// * end: The end point of the search.
-    /// # Returns
-    /// If the algorithm could find a path, it returns the positions of the path; otherwise, it returns None.
-    /// The positions are the node indicators that have been generated by *add_node*.
-    ///
-    /// # Example:
-    ///
-    ///  ```
-    ///  use astar_lib::a_star::NavGraph;
-    ///  let mut graph = NavGraph::new();
-    ///  let p0 = graph.add_node([0.0, 0.0]);
-    ///  let p1 = graph.add_node([0.5, 0.5]);
-    ///  let p2 = graph.add_node([1.0, 0.0]);
-    ///  let p3 = graph.add_node([1.0, 1.0]);
-    ///  let p4 = graph.add_node([0.1, 0.0]);
-    ///  graph.connect_nodes(p0, p1).unwrap();
-    ///  graph.connect_nodes(p1, p2).unwrap();
-    ///  graph.connect_nodes(p0, p2).unwrap();
-    ///  graph.connect_nodes(p1, p4).unwrap();
-    ///  graph.connect_nodes(p4, p3).unwrap();
-    ///  graph.connect_nodes(p2, p3).unwrap();
-    ///
-    ///  let result = graph.search_graph(p0, p3);
-    ///
-    ///  if let Some(result) = result {
-    ///      for pos in result.iter() {
-    ///          println!("{:?}", pos);
-    ///       } }
-    ///  ```
-    pub fn search_graph(
-        &mut self,
-        start_index: usize,
-        destination_index: usize,
-    ) -> Option<Vec<usize>> {
-        self.reset_graph_search();
-        let dest_point = self.nodes[destination_index].position;
-        let mut todo_list: Vec<usize> = Vec::new();
+    #[test]
+    fn immutable_search_test() {
+        fn assert_sync<T: Sync>(_: &T) {}
 
-        self.nodes[start_index].state = NodeState::Visited;
-        todo_list.push(start_index);
+        let mut graph = NavGraph::new();
+        let chain: Vec<usize> = (0..6)
+            .map(|index| graph.add_node([index as f32, (index % 2) as f32]))
+            .collect();
+        for pair in chain.windows(2) {
+            graph.connect_nodes(pair[0], pair[1]).unwrap();
+        }
+        assert_sync(&graph);
 
-        loop {
-            // In this case, there is no path, so we return none.
-            let (best_index, best_candidate) = todo_list.iter().enumerate().min_by(|a, b| {
-                self.nodes[*a.1]
-                    .f_value
-                    .total_cmp(&self.nodes[*b.1].f_value)
-            })?;
-            let best_candidate = *best_candidate;
-            todo_list.swap_remove(best_index);
+        let shared = &graph;
+        let mut scratches = vec![SearchScratch::new(); chain.len()];
+        let results: Vec<Option<Vec<usize>>> = scratches
+            .iter_mut()
+            .enumerate()
+            .map(|(start, scratch)| shared.search_graph_with_scratch(start, 5, scratch))
+            .collect();
+        for (start, result) in results.iter().enumerate() {
+            assert_eq!(*result.as_ref().unwrap(), chain[start..]);
+            assert_eq!(scratches[start].node_state(5), Some(&NodeState::Solution));
+        }
 
-            self.nodes[best_candidate].state = NodeState::Closed;
+        let last_result = graph.search_graph(0, 5).unwrap();
+        graph.add_node([10.0, 10.0]);
+        let states: Vec<NodeState> = graph
+            .get_all_nodes_with_state()
+            .map(|(_, state)| state.clone())
+            .collect();
+        assert_eq!(last_result.len(), 6);
+        assert_eq!(states[6], NodeState::Clear, "Added after the search.");
+        assert!(
+            states[..6]
+                .iter()
+                .all(|state| *state == NodeState::Solution)
+        );
+    }
 
-            if best_candidate == destination_index {
-                return Some(self.get_path(start_index, destination_index));
-            }
+    #[test]
+    fn degree_test() {
+        let mut graph = NavGraph::new();
+        assert!(graph.degree_histogram().is_empty());
 
-            let connection_count = self.nodes[best_candidate].connections.len();
-            let root_g_value = self.nodes[best_candidate].g_value;
+        let hub = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([0.0, 1.0]);
+        let p3 = graph.add_node([-1.0, 0.0]);
+        let p4 = graph.add_node([5.0, 5.0]);
 
-            for partner in 0..connection_count {
-                let (global_index, distance) = self.nodes[best_candidate].connections[partner];
-                let partner_node = &mut self.nodes[global_index];
+        graph.connect_nodes(hub, p1).unwrap();
+        graph.connect_nodes(hub, p2).unwrap();
+        graph.connect_nodes(hub, p3).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
 
-                match partner_node.state {
-                    NodeState::Clear => {
-                        partner_node.state = NodeState::Visited;
-                        partner_node.ancestor_node = best_candidate;
-                        partner_node.g_value = root_g_value + distance;
-                        partner_node.f_value =
-                            partner_node.g_value + partner_node.position.dist_to(&dest_point);
-                        todo_list.push(global_index);
-                    }
-                    NodeState::Visited => {
-                        let new_g_value = root_g_value + distance;
-                        if new_g_value < partner_node.g_value {
-                            partner_node.g_value = new_g_value;
-                            partner_node.f_value =
-                                new_g_value + partner_node.position.dist_to(&dest_point);
-                            partner_node.ancestor_node = best_candidate;
-                        }
-                    }
-                    NodeState::Closed => {}
-                    NodeState::Solution => {
-                        panic!("Case should not happen")
-                    }
-                }
-            }
+        assert_eq!(graph.node_degree(hub), Some(3));
+        assert_eq!(graph.node_degree(p1), Some(2));
+        assert_eq!(graph.node_degree(p3), Some(1));
+        assert_eq!(graph.node_degree(p4), Some(0));
+        assert_eq!(graph.node_degree(17), None);
+        assert_eq!(graph.degree_histogram(), [1, 1, 2, 1]);
+        for node in [hub, p1, p3, p4, 17] {
+            assert_eq!(graph.node_in_degree(node), graph.node_degree(node));
+            assert_eq!(graph.node_out_degree(node), graph.node_degree(node));
         }
     }
-}
-
-#[cfg(test)]
-mod tests {
-    use super::*;
 
     #[test]
-    fn base_test() {
+    fn in_out_degree_test() {
         let mut graph = NavGraph::new();
+        let chain: Vec<usize> = (0..4)
+            .map(|index| graph.add_node([index as f32, 0.0]))
+            .collect();
+        for pair in chain.windows(2) {
+            graph.connect_nodes(pair[0], pair[1]).unwrap();
+        }
+        let degrees = |graph: &NavGraph| -> Vec<(usize, usize)> {
+            (0..4)
+                .map(|node| {
+                    (
+                        graph.node_in_degree(node).unwrap(),
+                        graph.node_out_degree(node).unwrap(),
+                    )
+                })
+                .collect()
+        };
+        assert_eq!(degrees(&graph), [(1, 1), (2, 2), (2, 2), (1, 1)]);
+        assert_eq!(graph.node_in_degree(4), None);
+        assert_eq!(graph.node_out_degree(4), None);
 
-        let p0 = graph.add_node([0.0, 0.0]);
-        let p1 = graph.add_node([0.5, 0.5]);
-        let p2 = graph.add_node([1.0, 0.0]);
-        let p3 = graph.add_node([1.0, 1.0]);
-        let p4 = graph.add_node([0.1, 0.0]);
-        let p5 = graph.add_node([2.0, 2.0]);
+        // A one way connection from the end back to the start only counts once on each side.
+        let distance = graph.nodes[chain[3]]
+            .position
+            .dist_to(&graph.nodes[chain[0]].position);
+        graph.nodes[chain[3]].connections.push((chain[0], distance));
+        assert_eq!(degrees(&graph), [(2, 1), (2, 2), (2, 2), (1, 2)]);
+    }
 
+    #[test]
+    fn reachable_count_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([1.0, 1.0]);
+        let p3 = graph.add_node([5.0, 0.0]);
+        let p4 = graph.add_node([6.0, 0.0]);
         graph.connect_nodes(p0, p1).unwrap();
         graph.connect_nodes(p1, p2).unwrap();
-        graph.connect_nodes(p0, p2).unwrap();
-        graph.connect_nodes(p1, p4).unwrap();
-        graph.connect_nodes(p4, p3).unwrap();
-        graph.connect_nodes(p2, p3).unwrap();
+        graph.connect_nodes(p2, p0).unwrap();
+        graph.connect_nodes(p3, p4).unwrap();
 
-        let double_con_test = graph.connect_nodes(p1, p0);
-        assert_eq!(double_con_test, Err(ConnectionError::LinkAlreadyExists));
+        assert_eq!(graph.reachable_count(p0), 3);
+        assert_eq!(graph.reachable_count(p2), 3);
+        assert_eq!(graph.reachable_count(p4), 2);
+        assert_eq!(graph.reachable_count(17), 0);
+    }
 
-        let result = graph.search_graph(p0, p3);
-        assert!(result.is_some());
+    #[test]
+    fn k_hop_neighborhood_test() {
+        let mut graph = grid_graph(6, 1, &[false; 6]);
+        let (nodes, links) = graph.k_hop_neighborhood(0, 2);
+        assert_eq!(nodes, [0, 1, 2]);
+        assert_eq!(links, [(0, 1), (1, 2)]);
 
-        let result = result.unwrap();
-        assert_eq!(result, [0, 2, 3]);
+        let (mut nodes, links) = graph.k_hop_neighborhood(3, 2);
+        nodes.sort();
+        assert_eq!(nodes, [1, 2, 3, 4, 5]);
+        assert_eq!(links, [(1, 2), (2, 3), (3, 4), (4, 5)]);
 
-        for (source, destination, solution) in graph.get_all_links_with_solution_hint() {
-            println!("{:?} -> {:?} : {}", source, destination, solution);
+        // A long link still counts as a single hop.
+        let far = graph.add_node([100.0, 0.0]);
+        graph.connect_nodes(0, far).unwrap();
+        assert_eq!(graph.k_hop_neighborhood(far, 1).0, [far, 0]);
+        assert_eq!(graph.k_hop_neighborhood(far, 0), (vec![far], vec![]));
+        assert_eq!(graph.k_hop_neighborhood(far + 1, 3), (vec![], vec![]));
+    }
+
+    #[cfg(feature = "rand")]
+    #[test]
+    fn random_reachable_test() {
+        let mut graph = grid_graph(3, 3, &[false; 9]);
+        let p9 = graph.add_node([10.0, 0.0]);
+        let p10 = graph.add_node([11.0, 0.0]);
+        let p11 = graph.add_node([12.0, 0.0]);
+        graph.connect_nodes(p9, p10).unwrap();
+        graph.connect_nodes(p10, p11).unwrap();
+
+        let mut rng = rand::rng();
+        let mut seen = [false; 9];
+        for _ in 0..500 {
+            let node = graph.random_reachable(4, &mut rng).unwrap();
+            assert!(node < 9 && node != 4, "The node has to be in the grid.");
+            seen[node] = true;
+            let node = graph.random_reachable(p10, &mut rng).unwrap();
+            assert!(node == p9 || node == p11);
         }
+        assert_eq!(seen.iter().filter(|flag| **flag).count(), 8);
 
-        for (position, state) in graph.get_all_nodes_with_state() {
-            println!("{:?} : {:?}", position, state);
+        let lonely = graph.add_node([20.0, 20.0]);
+        assert_eq!(graph.random_reachable(lonely, &mut rng), None);
+        assert_eq!(graph.random_reachable(lonely + 1, &mut rng), None);
+    }
+
+    #[test]
+    fn reorder_spatially_test() {
+        assert_eq!(morton_code(0b11, 0b01), 0b0111);
+        assert_eq!(morton_code(0, u32::MAX), 0xAAAA_AAAA_AAAA_AAAA);
+
+        // The nodes of a grid get added in a scattered order.
+        let cols = 7;
+        let order: Vec<usize> = (0..cols * cols)
+            .map(|index| (index * 17) % (cols * cols))
+            .collect();
+        let mut graph = NavGraph::new();
+        for cell in order.iter() {
+            graph.add_node([(cell % cols) as f32, (cell / cols) as f32]);
         }
+        let handle =
+            |x: usize, y: usize| order.iter().position(|cell| *cell == y * cols + x).unwrap();
+        for y in 0..cols {
+            for x in 0..cols {
+                if x + 1 < cols && (x, y) != (3, 2) {
+                    graph.connect_nodes(handle(x, y), handle(x + 1, y)).unwrap();
+                }
+                if y + 1 < cols {
+                    graph.connect_nodes(handle(x, y), handle(x, y + 1)).unwrap();
+                }
+            }
+        }
+        graph.set_node_cost_multiplier(handle(2, 2), 3.0).unwrap();
+        graph.set_edge_tags(handle(4, 4), handle(4, 5), 2).unwrap();
+        let count = cols * cols;
+        let paths: Vec<Option<Vec<usize>>> = (0..count * count)
+            .map(|query| graph.search_graph(query / count, query % count))
+            .collect();
+        let positions: Vec<[f32; 2]> = graph
+            .get_all_nodes_with_state()
+            .map(|(pos, _)| pos)
+            .collect();
 
+        let mapping = graph.reorder_spatially();
+        let mut sorted = mapping.clone();
+        sorted.sort();
+        assert_eq!(sorted, (0..count).collect::<Vec<usize>>());
         assert_eq!(
-            result.len(),
-            graph
-                .get_all_nodes_with_state()
-                .filter(|(_, state)| **state == NodeState::Solution)
-                .count(),
-            "They should be the same."
+            mapping[handle(0, 0)],
+            0,
+            "The origin comes first in Morton order."
+        );
+        assert_eq!(mapping[handle(1, 0)], 1);
+        assert_eq!(mapping[handle(0, 1)], 2);
+        assert_eq!(mapping[handle(1, 1)], 3);
+        for (old, position) in positions.iter().enumerate() {
+            assert_eq!(graph.nodes[mapping[old]].position, Vec2::from(*position));
+        }
+        assert_eq!(
+            graph.edge_tags(mapping[handle(4, 5)], mapping[handle(4, 4)]),
+            Some(2)
         );
+        assert_eq!(graph.node_tags(mapping[handle(2, 2)]), Some(ALL_TAGS));
 
-        let result = graph.search_graph(p0, p5);
-        assert!(result.is_none(), "There should not be a solution!");
+        for (query, path) in paths.iter().enumerate() {
+            let (start, destination) = (query / count, query % count);
+            let expected = path.as_ref().map(|path| {
+                path.iter()
+                    .map(|node| mapping[*node])
+                    .collect::<Vec<usize>>()
+            });
+            assert_eq!(
+                graph.search_graph(mapping[start], mapping[destination]),
+                expected
+            );
+        }
+        assert!(NavGraph::new().reorder_spatially().is_empty());
+    }
 
-        graph.disconnect_nodes(p3, p2).unwrap();
-        let result = graph.search_graph(p0, p3).unwrap();
-        assert_eq!(result, [0, 1, 4, 3]);
+    #[test]
+    fn prune_to_landmarks_test() {
+        let mut blocked = [false; 49];
+        for index in [16, 23, 30, 17, 31] {
+            blocked[index] = true;
+        }
+        let keep = [0, 6, 42, 48, 24, 3, 45, 10];
+        let mut sorted_keep = keep.to_vec();
+        sorted_keep.sort();
 
-        let test = graph.disconnect_nodes(p3, p2);
-        assert_eq!(
-            test,
-            Err(ConnectionError::LinkDoesntExist),
-            "Should not work"
-        );
+        for tolerance in [0.0, 0.5, 2.0, 100.0] {
+            let mut graph = grid_graph(7, 7, &blocked);
+            graph.set_node_cost_multiplier(24, 2.0).unwrap();
+            let mut reference = grid_graph(7, 7, &blocked);
+            reference.set_node_cost_multiplier(24, 2.0).unwrap();
+            let mapping = graph.prune_to_landmarks(&keep, tolerance);
+            assert_eq!(graph.nodes.len(), keep.len());
+            for (new_index, old_index) in sorted_keep.iter().enumerate() {
+                assert_eq!(mapping[*old_index], Some(new_index));
+            }
+
+            for (first, old_first) in sorted_keep.iter().enumerate() {
+                let expected = reference.dijkstra_from(*old_first);
+                let after = graph.dijkstra_from(first);
+                for (second, old_second) in sorted_keep.iter().enumerate() {
+                    let before = expected[*old_second].unwrap();
+                    let after = after[second].unwrap();
+                    assert!(
+                        after >= before - 0.0001,
+                        "Shortcuts never undercut the costs."
+                    );
+                    assert!(after - before <= tolerance + 0.0001);
+                }
+            }
+            if tolerance == 0.0 {
+                assert!(graph.links.len() >= keep.len() - 1);
+            }
+        }
+
+        // Pairs that were not connected before stay unconnected.
+        let mut graph = grid_graph(3, 1, &[false, false, true]);
+        let mapping = graph.prune_to_landmarks(&[0, 2, 7], 0.1);
+        assert_eq!(mapping, [Some(0), None, Some(1)]);
+        assert_eq!(graph.links.len(), 0);
+    }
+
+    #[test]
+    fn isolated_nodes_test() {
+        let mut graph = NavGraph::new();
+
+        let p0 = graph.add_node([0.0, 0.0]);
+        let stray = graph.add_node([5.0, 5.0]);
+        let p2 = graph.add_node([1.0, 0.0]);
+        let p3 = graph.add_node([1.0, 1.0]);
+
+        graph.connect_nodes(p0, p2).unwrap();
+        graph.connect_nodes(p2, p3).unwrap();
+
+        assert_eq!(graph.isolated_nodes(), [stray]);
+        assert!(graph.search_graph(p0, stray).is_none());
+
+        let mapping = graph.prune_isolated();
+        assert_eq!(mapping, [Some(0), None, Some(1), Some(2)]);
+        assert!(graph.isolated_nodes().is_empty());
+        assert!(graph.links_in_order().eq([(0, 1), (1, 2)]));
+        assert_eq!(graph.search_graph(0, 2).unwrap(), [0, 1, 2]);
     }
 }
@@ -0,0 +1,373 @@
+//! Provides an edit history on top of the nav graph, so that edits may be undone and redone.
+
+use super::a_star::{ConnectionError, NavGraph};
+
+/// A single recorded edit of the graph. Every edit contains enough information to be inverted.
+#[derive(Debug, Clone, PartialEq)]
+pub enum GraphEdit {
+    /// A node got added at the indicated position.
+    AddNode([f32; 2]),
+    /// The two nodes got connected.
+    Connect(usize, usize),
+    /// The link between the two nodes got removed, with the state it had at that point.
+    Disconnect(usize, usize, LinkState),
+    /// The node got removed. The links it had at that point are kept for restoring them.
+    RemoveNode {
+        node: usize,
+        links: Vec<(usize, usize, LinkState)>,
+    },
+}
+
+/// The stored cost and the attributes of a removed link, so that undoing the removal restores it exactly.
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkState {
+    cost: f32,
+    tags: u32,
+    clearance: f32,
+    enabled: bool,
+}
+
+/// Wraps a nav graph and records all edits performed through it, so that they may be undone and redone.
+/// Performing a new edit clears the redo history.
+///
+/// Removing a node leaves a tombstone: the node loses all its links, but keeps its handle,
+/// so that all other handles stay valid and the removal can be inverted.
+/// Compacting the graph with [`NavGraph::prune_isolated`] has to be done on the final graph obtained with
+/// [`GraphEditor::into_graph`].
+#[derive(Default)]
+pub struct GraphEditor {
+    graph: NavGraph,
+    undo_stack: Vec<GraphEdit>,
+    redo_stack: Vec<GraphEdit>,
+}
+
+impl From<NavGraph> for GraphEditor {
+    fn from(graph: NavGraph) -> Self {
+        GraphEditor {
+            graph,
+            undo_stack: Vec::new(),
+            redo_stack: Vec::new(),
+        }
+    }
+}
+
+impl GraphEditor {
+    /// Generates a new editor with an empty graph.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::edit_history::GraphEditor;
+    /// let editor = GraphEditor::new();
+    /// ```
+    pub fn new() -> GraphEditor {
+        Self::from(NavGraph::new())
+    }
+
+    /// Gets the edited graph.
+    pub fn graph(&self) -> &NavGraph {
+        &self.graph
+    }
+
+    /// Consumes the editor and returns the edited graph.
+    pub fn into_graph(self) -> NavGraph {
+        self.graph
+    }
+
+    /// Adds a node to the graph, see [`NavGraph::add_node`].
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::edit_history::GraphEditor;
+    /// let mut editor = GraphEditor::new();
+    /// let p0 = editor.add_node([0.0, 0.0]);
+    /// assert_eq!(p0, 0);
+    /// ```
+    pub fn add_node(&mut self, position: [f32; 2]) -> usize {
+        self.record(GraphEdit::AddNode(position));
+        self.graph.add_node(position)
+    }
+
+    /// Connects two nodes, see [`NavGraph::connect_nodes`].
+    ///
+    /// # Error
+    /// Returns the error of the graph, in which case nothing gets recorded.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::edit_history::GraphEditor;
+    /// let mut editor = GraphEditor::new();
+    /// let p0 = editor.add_node([0.0, 0.0]);
+    /// let p1 = editor.add_node([1.0, 0.0]);
+    /// editor.connect_nodes(p0, p1).unwrap();
+    /// ```
+    pub fn connect_nodes(&mut self, node1: usize, node2: usize) -> Result<(), ConnectionError> {
+        self.graph.connect_nodes(node1, node2)?;
+        self.record(GraphEdit::Connect(node1, node2));
+        Ok(())
+    }
+
+    /// Disconnects two nodes, see [`NavGraph::disconnect_nodes`].
+    ///
+    /// # Error
+    /// Returns the error of the graph, in which case nothing gets recorded.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::edit_history::GraphEditor;
+    /// let mut editor = GraphEditor::new();
+    /// let p0 = editor.add_node([0.0, 0.0]);
+    /// let p1 = editor.add_node([1.0, 0.0]);
+    /// editor.connect_nodes(p0, p1).unwrap();
+    /// editor.disconnect_nodes(p0, p1).unwrap();
+    /// ```
+    pub fn disconnect_nodes(&mut self, node1: usize, node2: usize) -> Result<(), ConnectionError> {
+        let state = self
+            .link_state(node1, node2)
+            .ok_or(ConnectionError::LinkDoesntExist)?;
+        self.graph.disconnect_nodes(node1, node2)?;
+        self.record(GraphEdit::Disconnect(node1, node2, state));
+        Ok(())
+    }
+
+    /// Removes a node by removing all its links, leaving the node as an isolated tombstone.
+    ///
+    /// # Error
+    /// Returns an error if the node does not exist.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::edit_history::GraphEditor;
+    /// let mut editor = GraphEditor::new();
+    /// let p0 = editor.add_node([0.0, 0.0]);
+    /// let p1 = editor.add_node([1.0, 0.0]);
+    /// editor.connect_nodes(p0, p1).unwrap();
+    /// editor.remove_node(p1).unwrap();
+    /// assert_eq!(editor.graph().node_degree(p0), Some(0));
+    /// ```
+    pub fn remove_node(&mut self, node: usize) -> Result<(), ConnectionError> {
+        let links = self.detach_node(node)?;
+        self.record(GraphEdit::RemoveNode { node, links });
+        Ok(())
+    }
+
+    /// Undoes the most recent edit. Returns false if there is nothing to undo.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::edit_history::GraphEditor;
+    /// let mut editor = GraphEditor::new();
+    /// let p0 = editor.add_node([0.0, 0.0]);
+    /// let p1 = editor.add_node([1.0, 0.0]);
+    /// editor.connect_nodes(p0, p1).unwrap();
+    /// assert!(editor.undo());
+    /// assert_eq!(editor.graph().node_degree(p0), Some(0));
+    /// ```
+    pub fn undo(&mut self) -> bool {
+        let Some(edit) = self.undo_stack.pop() else {
+            return false;
+        };
+        match &edit {
+            GraphEdit::AddNode(_) => self.graph.remove_last_node(),
+            GraphEdit::Connect(node1, node2) => {
+                self.graph.disconnect_nodes(*node1, *node2).unwrap();
+            }
+            GraphEdit::Disconnect(node1, node2, state) => {
+                self.restore_link(*node1, *node2, state);
+            }
+            GraphEdit::RemoveNode { links, .. } => {
+                for (node1, node2, state) in links.iter() {
+                    self.restore_link(*node1, *node2, state);
+                }
+            }
+        }
+        self.redo_stack.push(edit);
+        true
+    }
+
+    /// Redoes the most recently undone edit. Returns false if there is nothing to redo.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::edit_history::GraphEditor;
+    /// let mut editor = GraphEditor::new();
+    /// let p0 = editor.add_node([0.0, 0.0]);
+    /// let p1 = editor.add_node([1.0, 0.0]);
+    /// editor.connect_nodes(p0, p1).unwrap();
+    /// editor.undo();
+    /// assert!(editor.redo());
+    /// assert_eq!(editor.graph().node_degree(p0), Some(1));
+    /// ```
+    pub fn redo(&mut self) -> bool {
+        let Some(edit) = self.redo_stack.pop() else {
+            return false;
+        };
+        match &edit {
+            GraphEdit::AddNode(position) => {
+                self.graph.add_node(*position);
+            }
+            GraphEdit::Connect(node1, node2) => {
+                self.graph.connect_nodes(*node1, *node2).unwrap();
+            }
+            GraphEdit::Disconnect(node1, node2, _) => {
+                self.graph.disconnect_nodes(*node1, *node2).unwrap();
+            }
+            GraphEdit::RemoveNode { node, .. } => {
+                self.detach_node(*node).unwrap();
+            }
+        }
+        self.undo_stack.push(edit);
+        true
+    }
+
+    /// Records a new edit, which invalidates the redo history.
+    fn record(&mut self, edit: GraphEdit) {
+        self.undo_stack.push(edit);
+        self.redo_stack.clear();
+    }
+
+    /// Removes all links of the node and returns them with their states.
+    fn detach_node(
+        &mut self,
+        node: usize,
+    ) -> Result<Vec<(usize, usize, LinkState)>, ConnectionError> {
+        if self.graph.node_degree(node).is_none() {
+            return Err(ConnectionError::NodeDoesntExist(node));
+        }
+        let links: Vec<(usize, usize, LinkState)> = self
+            .graph
+            .links_in_order()
+            .filter(|(node1, node2)| (*node1 == node) || (*node2 == node))
+            .map(|(node1, node2)| (node1, node2, self.link_state(node1, node2).unwrap()))
+            .collect();
+        for (node1, node2, _) in links.iter() {
+            self.graph.disconnect_nodes(*node1, *node2).unwrap();
+        }
+        Ok(links)
+    }
+
+    /// Gets the state of the link between the two nodes, or None if the link does not exist.
+    fn link_state(&self, node1: usize, node2: usize) -> Option<LinkState> {
+        Some(LinkState {
+            cost: self.graph.get_edge(node1, node2)?,
+            tags: self.graph.edge_tags(node1, node2)?,
+            clearance: self.graph.edge_clearance(node1, node2)?,
+            enabled: self.graph.edge_enabled(node1, node2)?,
+        })
+    }
+
+    /// Connects the two nodes again and restores the state the link had.
+    fn restore_link(&mut self, node1: usize, node2: usize, state: &LinkState) {
+        self.graph.connect_nodes(node1, node2).unwrap();
+        // Only attributes differing from the defaults get stored, like with a fresh link.
+        if self.graph.get_edge(node1, node2) != Some(state.cost) {
+            self.graph.set_stored_cost(node1, node2, state.cost);
+        }
+        if self.graph.edge_tags(node1, node2) != Some(state.tags) {
+            self.graph.set_edge_tags(node1, node2, state.tags).unwrap();
+        }
+        if self.graph.edge_clearance(node1, node2) != Some(state.clearance) {
+            self.graph
+                .set_edge_clearance(node1, node2, state.clearance)
+                .unwrap();
+        }
+        if !state.enabled {
+            self.graph.set_edge_enabled(node1, node2, false).unwrap();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Gets the number of nodes and the normalized, sorted links of the graph.
+    fn snapshot(graph: &NavGraph) -> (usize, Vec<(usize, usize)>) {
+        let mut links: Vec<(usize, usize)> = graph
+            .links_in_order()
+            .map(|(node1, node2)| (node1.min(node2), node1.max(node2)))
+            .collect();
+        links.sort();
+        (graph.get_all_nodes_with_state().count(), links)
+    }
+
+    #[test]
+    fn undo_redo_test() {
+        let mut editor = GraphEditor::new();
+        let p0 = editor.add_node([0.0, 0.0]);
+        let p1 = editor.add_node([1.0, 0.0]);
+        let p2 = editor.add_node([1.0, 1.0]);
+        editor.connect_nodes(p0, p1).unwrap();
+        editor.connect_nodes(p1, p2).unwrap();
+        let prior = snapshot(editor.graph());
+
+        // Failing edits do not get recorded.
+        assert!(editor.connect_nodes(p0, p1).is_err());
+
+        let p3 = editor.add_node([0.0, 1.0]);
+        editor.connect_nodes(p2, p3).unwrap();
+        editor.connect_nodes(p3, p0).unwrap();
+        editor.disconnect_nodes(p0, p1).unwrap();
+        editor.remove_node(p2).unwrap();
+        let after = snapshot(editor.graph());
+        assert_eq!(after, (4, vec![(0, 3)]));
+
+        for _ in 0..5 {
+            assert!(editor.undo());
+        }
+        assert_eq!(snapshot(editor.graph()), prior);
+
+        for _ in 0..5 {
+            assert!(editor.redo());
+        }
+        assert!(!editor.redo());
+        assert_eq!(snapshot(editor.graph()), after);
+
+        // A new edit clears the redo history.
+        editor.undo();
+        editor.connect_nodes(p0, p2).unwrap();
+        assert!(!editor.redo());
+
+        while editor.undo() {}
+        assert_eq!(snapshot(editor.graph()), (0, Vec::new()));
+    }
+
+    #[test]
+    fn undo_restores_link_state_test() {
+        let mut graph = NavGraph::new();
+        let p0 = graph.add_node([0.0, 0.0]);
+        let p1 = graph.add_node([1.0, 0.0]);
+        let p2 = graph.add_node([1.0, 1.0]);
+        graph.connect_nodes(p0, p1).unwrap();
+        graph.connect_nodes(p1, p2).unwrap();
+        graph.set_node_position(p1, [2.0, 0.0]).unwrap();
+        graph.set_edge_tags(p0, p1, 0b10).unwrap();
+        graph.set_edge_clearance(p0, p1, 0.25).unwrap();
+        graph.set_edge_enabled(p1, p2, false).unwrap();
+        let state = |graph: &NavGraph, node1: usize, node2: usize| {
+            (
+                graph.get_edge(node1, node2),
+                graph.edge_tags(node1, node2),
+                graph.edge_clearance(node1, node2),
+                graph.edge_enabled(node1, node2),
+            )
+        };
+        let first = state(&graph, p0, p1);
+        let second = state(&graph, p1, p2);
+        assert_eq!(first.0, Some(1.0), "The cost is not recomputed.");
+
+        let mut editor = GraphEditor::from(graph);
+        editor.disconnect_nodes(p1, p0).unwrap();
+        assert_eq!(state(editor.graph(), p0, p1), (None, None, None, None));
+        editor.undo();
+        assert_eq!(state(editor.graph(), p0, p1), first);
+
+        editor.remove_node(p1).unwrap();
+        editor.undo();
+        assert_eq!(state(editor.graph(), p0, p1), first);
+        assert_eq!(state(editor.graph(), p1, p2), second);
+
+        editor.redo();
+        editor.undo();
+        assert_eq!(state(editor.graph(), p1, p2), second);
+    }
+}
@@ -0,0 +1,96 @@
+//! Provides a compact byte format for paths of node handles, for example to send a path computed
+//! on a server to the clients. Every handle is stored as a variable length integer, where each byte holds seven bits
+//! of the value and the highest bit indicates that more bytes follow.
+
+/// Contains the errors that may happen when decoding a path with [`path_from_bytes`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DecodeError {
+    /// The data ended in the middle of a handle.
+    Truncated,
+    /// A handle does not fit into a usize.
+    Overflow,
+}
+
+/// Encodes a path of node handles into bytes.
+///
+/// # Example
+/// ```
+/// use astar_lib::encoding::path_to_bytes;
+/// assert_eq!(path_to_bytes(&[1, 300]), [1, 0xAC, 0x02]);
+/// ```
+pub fn path_to_bytes(path: &[usize]) -> Vec<u8> {
+    let mut bytes = Vec::with_capacity(path.len());
+    for node in path.iter() {
+        let mut value = *node;
+        while value >= 0x80 {
+            bytes.push((value & 0x7F) as u8 | 0x80);
+            value >>= 7;
+        }
+        bytes.push(value as u8);
+    }
+    bytes
+}
+
+/// Decodes a path of node handles from bytes generated with [`path_to_bytes`].
+///
+/// # Error
+/// Returns an error if the data ends in the middle of a handle, or if a handle is too large for a usize.
+///
+/// # Example
+/// ```
+/// use astar_lib::encoding::{DecodeError, path_from_bytes, path_to_bytes};
+/// let bytes = path_to_bytes(&[4, 1000, 2]);
+/// assert_eq!(path_from_bytes(&bytes).unwrap(), [4, 1000, 2]);
+/// assert_eq!(path_from_bytes(&[0x80]), Err(DecodeError::Truncated));
+/// ```
+pub fn path_from_bytes(bytes: &[u8]) -> Result<Vec<usize>, DecodeError> {
+    let mut path = Vec::new();
+    let mut value: usize = 0;
+    let mut shift: u32 = 0;
+    for byte in bytes.iter() {
+        let bits = (*byte & 0x7F) as usize;
+        if shift >= usize::BITS || (bits << shift) >> shift != bits {
+            return Err(DecodeError::Overflow);
+        }
+        value |= bits << shift;
+        if *byte & 0x80 == 0 {
+            path.push(value);
+            value = 0;
+            shift = 0;
+        } else {
+            shift += 7;
+        }
+    }
+    if shift != 0 {
+        return Err(DecodeError::Truncated);
+    }
+    Ok(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_test() {
+        let path = vec![0, 1, 127, 128, 16383, 16384, 123456789, usize::MAX];
+        let bytes = path_to_bytes(&path);
+        assert_eq!(path_from_bytes(&bytes).unwrap(), path);
+        assert_eq!(path_to_bytes(&[0, 127]).len(), 2);
+        assert!(path_from_bytes(&[]).unwrap().is_empty());
+    }
+
+    #[test]
+    fn malformed_test() {
+        let mut bytes = path_to_bytes(&[5, 70000]);
+        bytes.pop();
+        assert_eq!(path_from_bytes(&bytes), Err(DecodeError::Truncated));
+
+        let mut too_large = vec![0xFF; 10];
+        too_large.push(0x01);
+        assert_eq!(path_from_bytes(&too_large), Err(DecodeError::Overflow));
+        let mut max_bytes = path_to_bytes(&[usize::MAX]);
+        *max_bytes.last_mut().unwrap() = 0x7F;
+        assert_eq!(path_from_bytes(&max_bytes), Err(DecodeError::Overflow));
+    }
+}
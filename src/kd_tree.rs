@@ -0,0 +1,197 @@
+//! Provides a static k-d tree over two-dimensional points, which accelerates the nearest neighbor
+//! and radius queries of large point sets with a non-uniform distribution.
+
+use super::vector::Vec2;
+
+/// The k-d tree, which is stored implicitly: the points are arranged so that the middle element of every range
+/// splits the remaining points of the range, alternately along the x and the y axis.
+/// Every point keeps the index it had in the list the tree was built from.
+pub(crate) struct KdTree {
+    points: Vec<(Vec2, usize)>,
+}
+
+/// Collects the points found by a query and tells the radius that is still of interest.
+trait Collector {
+    /// Gets the maximum distance a point may have to be of interest.
+    fn radius(&self) -> f32;
+    /// Offers a point with its distance to the probing position.
+    fn offer(&mut self, dist: f32, index: usize);
+}
+
+/// Collects the nearest point, preferring the smallest index among equally distant ones.
+struct Nearest {
+    radius: f32,
+    best: Option<(f32, usize)>,
+}
+
+impl Collector for Nearest {
+    fn radius(&self) -> f32 {
+        self.best.map_or(self.radius, |(dist, _)| dist)
+    }
+
+    fn offer(&mut self, dist: f32, index: usize) {
+        if dist <= self.radius && self.best.is_none_or(|best| (dist, index) < best) {
+            self.best = Some((dist, index));
+        }
+    }
+}
+
+/// Collects all points within the radius.
+struct Within {
+    radius: f32,
+    found: Vec<usize>,
+}
+
+impl Collector for Within {
+    fn radius(&self) -> f32 {
+        self.radius
+    }
+
+    fn offer(&mut self, dist: f32, index: usize) {
+        if dist <= self.radius {
+            self.found.push(index);
+        }
+    }
+}
+
+/// Collects the indicated number of nearest points, sorted by distance and index.
+struct KNearest {
+    count: usize,
+    best: Vec<(f32, usize)>,
+}
+
+impl Collector for KNearest {
+    fn radius(&self) -> f32 {
+        if self.best.len() < self.count {
+            f32::INFINITY
+        } else {
+            self.best.last().map_or(f32::INFINITY, |(dist, _)| *dist)
+        }
+    }
+
+    fn offer(&mut self, dist: f32, index: usize) {
+        let position = self.best.partition_point(|entry| *entry < (dist, index));
+        if position < self.count {
+            self.best.insert(position, (dist, index));
+            self.best.truncate(self.count);
+        }
+    }
+}
+
+/// Gets the coordinate of the point along the indicated axis, 0 for x and 1 for y.
+fn coordinate(point: Vec2, axis: usize) -> f32 {
+    if axis == 0 { point.x } else { point.y }
+}
+
+impl KdTree {
+    /// Builds the tree over the points, which are identified by their index in the iteration order.
+    pub(crate) fn new(points: impl Iterator<Item = Vec2>) -> KdTree {
+        let mut points: Vec<(Vec2, usize)> = points
+            .enumerate()
+            .map(|(index, point)| (point, index))
+            .collect();
+        Self::arrange(&mut points, 0);
+        KdTree { points }
+    }
+
+    /// Arranges the range so that its middle element splits it along the indicated axis, and recurses into both halves.
+    fn arrange(points: &mut [(Vec2, usize)], axis: usize) {
+        if points.len() <= 1 {
+            return;
+        }
+        let middle = points.len() / 2;
+        points.select_nth_unstable_by(middle, |first, second| {
+            coordinate(first.0, axis).total_cmp(&coordinate(second.0, axis))
+        });
+        let (lower, upper) = points.split_at_mut(middle);
+        Self::arrange(lower, 1 - axis);
+        Self::arrange(&mut upper[1..], 1 - axis);
+    }
+
+    /// Offers the points of the range to the collector, skipping the halves that are farther away than its radius.
+    fn visit(points: &[(Vec2, usize)], axis: usize, probing: Vec2, collector: &mut impl Collector) {
+        if points.is_empty() {
+            return;
+        }
+        let middle = points.len() / 2;
+        let (split, index) = points[middle];
+        let dist = split.dist_to(&probing);
+        if !dist.is_nan() {
+            collector.offer(dist, index);
+        }
+
+        let difference = coordinate(probing, axis) - coordinate(split, axis);
+        let (near, far) = if difference < 0.0 {
+            (&points[..middle], &points[middle + 1..])
+        } else {
+            (&points[middle + 1..], &points[..middle])
+        };
+        Self::visit(near, 1 - axis, probing, collector);
+        // Both halves get visited if a coordinate is NaN, as the order of the halves is undefined then.
+        if difference.abs() <= collector.radius() || difference.is_nan() {
+            Self::visit(far, 1 - axis, probing, collector);
+        }
+    }
+
+    /// Gets the index of the nearest point within the radius, or None if there is none.
+    /// Among equally distant points, the one with the smallest index is taken.
+    pub(crate) fn nearest(&self, probing: Vec2, radius: f32) -> Option<usize> {
+        let mut collector = Nearest { radius, best: None };
+        Self::visit(&self.points, 0, probing, &mut collector);
+        collector.best.map(|(_, index)| index)
+    }
+
+    /// Gets the indices of all points within the radius in ascending order.
+    pub(crate) fn within(&self, probing: Vec2, radius: f32) -> Vec<usize> {
+        let mut collector = Within {
+            radius,
+            found: Vec::new(),
+        };
+        Self::visit(&self.points, 0, probing, &mut collector);
+        collector.found.sort_unstable();
+        collector.found
+    }
+
+    /// Gets the indices of the indicated number of nearest points, sorted by ascending distance.
+    pub(crate) fn k_nearest(&self, probing: Vec2, count: usize) -> Vec<usize> {
+        let mut collector = KNearest {
+            count,
+            best: Vec::new(),
+        };
+        if count > 0 {
+            Self::visit(&self.points, 0, probing, &mut collector);
+        }
+        collector.best.iter().map(|(_, index)| *index).collect()
+    }
+
+    /// Gets the number of bytes occupied on the heap.
+    pub(crate) fn memory_footprint(&self) -> usize {
+        self.points.capacity() * size_of::<(Vec2, usize)>()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn degenerate_points_test() {
+        let empty = KdTree::new(std::iter::empty());
+        assert_eq!(empty.nearest(Vec2::new(0.0, 0.0), f32::MAX), None);
+        assert!(empty.k_nearest(Vec2::new(0.0, 0.0), 3).is_empty());
+
+        let points = [
+            Vec2::new(f32::NAN, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, f32::NAN),
+            Vec2::new(-1.0, 2.0),
+        ];
+        let tree = KdTree::new(points.iter().copied());
+        assert_eq!(tree.nearest(Vec2::new(1.0, 1.2), 1.0), Some(1));
+        assert_eq!(tree.nearest(Vec2::new(1.0, 1.2), 0.1), None);
+        assert_eq!(tree.k_nearest(Vec2::new(0.0, 2.0), 10), [4, 1, 2]);
+        assert_eq!(tree.within(Vec2::new(0.0, 1.5), 1.2), [1, 2, 4]);
+        assert_eq!(tree.k_nearest(Vec2::new(0.0, 2.0), 0), []);
+    }
+}
@@ -19,8 +19,30 @@
 //! * **NavGraph**: This is the central class that provides the complete functionality.
 //! * **ConnectionError**: This enum represents the various errors that may happen during establishing and releasing connections.
 //! * **NodeState**: You will not need this structure unless you plan to make a debug visualization of the NavGraph  
+//! * **SearchScratch**: The mutable state of a search, which allows parallel searches on a shared NavGraph.
+//! * **DStarLite**: An incremental search that repairs a path after edge cost changes, created from the NavGraph.
+//! * **FrozenNavGraph**: An immutable, query optimized form of a finished NavGraph.
+//! * **DiagonalMoves**: The options for diagonal links when building a NavGraph from a walkability grid.
+//! * **NearestQueryCache**: The result of the last nearest node query, which speeds up queries at close positions.
+//! * **SearchOptions**: The options of a search that combine into one query, which returns a **SearchResult**.
+//!
+//! The *edit_history* module contains the **GraphEditor**, which wraps a NavGraph and records all edits,
+//! so that they may be undone and redone.
+//!
+//! The *encoding* module converts paths of node handles into a compact byte format and back,
+//! for example to send them over the network.
+//!
+//! The *line* module contains the **Line** structure, a line segment for intersection and distance checks.
+//!
+//! The *transform* module contains the **Transform2D** structure, which maps world coordinates into the
+//! coordinate space of the nav graph and back.
 //!   
 
 pub mod a_star;
+pub mod edit_history;
+pub mod encoding;
+mod kd_tree;
+pub mod line;
+pub mod transform;
 #[doc(hidden)]
 pub mod vector;
@@ -0,0 +1,286 @@
+//! Provides line segments for geometric checks, like intersections in graph construction or path simplification.
+
+use super::vector::Vec2;
+
+/// The default tolerance of a line relative to its length.
+pub const DEFAULT_EPSILON: f32 = 0.00001;
+
+/// Contains a line segment. can be used for intersection calculation.
+///
+/// The tolerance of the geometric checks is relative to the length of the segment, so that
+/// the checks behave the same for graphs in large and in tiny coordinate spaces.
+/// The tolerance keeps the end points out of the checks, which means lines sharing an end point do not intersect.
+///
+/// Two lines intersect if they cross at a point that is inside of both of them by more than their tolerances,
+/// or if they are collinear and overlap by more than the larger tolerance. Touching an end point,
+/// like in a T-junction, is no intersection, and lines of length zero never intersect.
+#[derive(Debug, Clone)]
+pub struct Line {
+    start: Vec2,
+    delta: Vec2,
+    magnitude: f32,
+    unit_delta: Vec2,
+    orthogonal: Vec2,
+    epsilon: f32,
+}
+
+impl Line {
+    /// Creates a new line with the default tolerance.
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// use astar_lib::line::Line;
+    /// let line_a = Line::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+    /// ```
+    pub fn new(start: Vec2, end: Vec2) -> Line {
+        Self::with_epsilon(start, end, DEFAULT_EPSILON)
+    }
+
+    /// Creates a new line with an explicit tolerance, given as a fraction of the length of the line.
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// use astar_lib::line::Line;
+    /// let line_a = Line::with_epsilon(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0), 0.001);
+    /// ```
+    pub fn with_epsilon(start: Vec2, end: Vec2, epsilon: f32) -> Line {
+        let delta = end - start;
+        let (magnitude, unit_delta) = delta.get_mag_normalized();
+        let orthogonal = unit_delta.get_orthogonal();
+        Line {
+            start,
+            delta,
+            magnitude,
+            unit_delta,
+            orthogonal,
+            epsilon,
+        }
+    }
+
+    /// Checks if an indicated point is in the voronoi region of the edge and if its distance
+    /// is lower than the indicated range.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// use astar_lib::line::Line;
+    /// let line_a = Line::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+    /// let critical = line_a.is_in_critical_range(Vec2::new(0.5, 0.5), 0.001);
+    /// assert!(critical, "We should be right on the line.");
+    /// ```
+    pub fn is_in_critical_range(&self, test_point: Vec2, range: f32) -> bool {
+        let rel_to_start = test_point - self.start;
+
+        // First we check if we are in the voronoi region of the edge.
+        let rel_dist = rel_to_start.dot(self.unit_delta);
+        let tolerance = self.epsilon * self.magnitude;
+        if !(tolerance..self.magnitude - tolerance).contains(&rel_dist) {
+            return false;
+        }
+
+        let orthogonal_dist = self.orthogonal.dot(rel_to_start).abs();
+        orthogonal_dist <= range
+    }
+
+    /// Computes the distance of a point to the line segment, which is the distance to the nearest end point
+    /// if the point is not in the voronoi region of the edge.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// use astar_lib::line::Line;
+    /// let line_a = Line::new(Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0));
+    /// assert_eq!(line_a.distance_to_point(Vec2::new(1.0, 0.5)), 0.5);
+    /// assert_eq!(line_a.distance_to_point(Vec2::new(3.0, 0.0)), 1.0);
+    /// ```
+    pub fn distance_to_point(&self, test_point: Vec2) -> f32 {
+        if self.magnitude == 0.0 {
+            return self.start.dist_to(&test_point);
+        }
+        let rel_dist = (test_point - self.start)
+            .dot(self.unit_delta)
+            .clamp(0.0, self.magnitude);
+        let closest = self
+            .start
+            .lerp(&(self.start + self.delta), rel_dist / self.magnitude);
+        closest.dist_to(&test_point)
+    }
+
+    /// Checks if this line intersects with another line. The tolerance of each line applies to its own segment.
+    /// Lines whose directions differ by less than the float precision are treated as parallel, and they only
+    /// intersect if they overlap.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// use astar_lib::line::Line;
+    /// let line_a = Line::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+    /// let line_b = Line::new(Vec2::new(0.0, 1.0), Vec2::new(1.0, 0.0));
+    /// let intersect = line_a.intersects_with(&line_b);
+    /// assert!(intersect);
+    /// ```
+    pub fn intersects_with(&self, other: &Line) -> bool {
+        if self.magnitude == 0.0 || other.magnitude == 0.0 {
+            return false;
+        }
+        let start_delta = other.start - self.start;
+
+        let base_det = -self.delta.x * other.delta.y + self.delta.y * other.delta.x;
+        if base_det.abs() <= f32::EPSILON * self.magnitude * other.magnitude {
+            return self.overlaps_collinear(other, start_delta);
+        }
+        let own_det = -start_delta.x * other.delta.y + start_delta.y * other.delta.x;
+        let other_det = self.delta.x * start_delta.y - self.delta.y * start_delta.x;
+
+        let my = own_det / base_det;
+        let lambda = other_det / base_det;
+
+        let inside =
+            |parameter: f32, epsilon: f32| parameter > epsilon && parameter < 1.0 - epsilon;
+        inside(my, self.epsilon) && inside(lambda, other.epsilon)
+    }
+
+    /// Checks if a parallel line lies on this line and overlaps with it by more than the larger tolerance.
+    fn overlaps_collinear(&self, other: &Line, start_delta: Vec2) -> bool {
+        if self.orthogonal.dot(start_delta).abs() > self.epsilon * self.magnitude {
+            return false;
+        }
+        let first = self.unit_delta.dot(start_delta);
+        let second = first + self.unit_delta.dot(other.delta);
+        let overlap = first.max(second).min(self.magnitude) - first.min(second).max(0.0);
+        overlap > (self.epsilon * self.magnitude).max(other.epsilon * other.magnitude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn line_test() {
+        let line_a = Line::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+        let line_b = Line::new(Vec2::new(0.0, 1.0), Vec2::new(1.0, 0.0));
+        let line_c = Line::new(Vec2::new(1.0, 0.0), Vec2::new(1.0, 1.0));
+        let intersect = line_a.intersects_with(&line_b);
+        assert!(intersect);
+        let intersect = line_c.intersects_with(&line_b);
+        assert!(!intersect);
+    }
+
+    #[test]
+    fn scale_test() {
+        for scale in [0.0001_f32, 10000.0] {
+            let point = |x: f32, y: f32| Vec2::new(x * scale, y * scale);
+            let line_a = Line::new(point(0.0, 0.0), point(1.0, 1.0));
+            let line_b = Line::new(point(0.0, 1.0), point(1.0, 0.0));
+            let line_c = Line::new(point(1.0, 1.0), point(2.0, 0.0));
+            let line_d = Line::new(point(0.0, 1.0), point(1.0, 2.0));
+            assert!(line_a.intersects_with(&line_b), "Crossing at scale {scale}");
+            assert!(
+                !line_a.intersects_with(&line_c),
+                "Shared end point at scale {scale}"
+            );
+            assert!(
+                !line_a.intersects_with(&line_d),
+                "Parallel at scale {scale}"
+            );
+
+            assert!(line_a.is_in_critical_range(point(0.5, 0.51), 0.02 * scale));
+            assert!(!line_a.is_in_critical_range(point(0.5, 0.6), 0.02 * scale));
+            assert!(!line_a.is_in_critical_range(point(1.0, 1.0), 0.02 * scale));
+        }
+    }
+
+    #[test]
+    fn explicit_epsilon_test() {
+        let line_a = Line::with_epsilon(Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0), 0.1);
+        let line_b = Line::with_epsilon(Vec2::new(0.95, -1.0), Vec2::new(0.95, 1.0), 0.1);
+        let line_c = Line::new(Vec2::new(0.95, -1.0), Vec2::new(0.95, 1.0));
+        assert!(!line_a.intersects_with(&line_b));
+        assert!(!line_a.intersects_with(&line_c));
+        assert!(Line::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 0.0)).intersects_with(&line_c));
+    }
+
+    #[test]
+    fn collinear_test() {
+        let line_a = Line::new(Vec2::new(0.0, 0.0), Vec2::new(2.0, 0.0));
+        let overlapping = Line::new(Vec2::new(3.0, 0.0), Vec2::new(1.0, 0.0));
+        let touching = Line::new(Vec2::new(2.0, 0.0), Vec2::new(3.0, 0.0));
+        let shifted = Line::new(Vec2::new(1.0, 0.001), Vec2::new(3.0, 0.001));
+        let point = Line::new(Vec2::new(1.0, 0.0), Vec2::new(1.0, 0.0));
+        assert!(line_a.intersects_with(&overlapping));
+        assert!(overlapping.intersects_with(&line_a));
+        assert!(!line_a.intersects_with(&touching));
+        assert!(!line_a.intersects_with(&shifted));
+        assert!(!line_a.intersects_with(&point));
+        assert!(!point.intersects_with(&line_a));
+    }
+
+    /// The reference predicate on integer coordinates, which decides with the exact signs of the orientations.
+    fn reference_intersection(
+        p1: (i64, i64),
+        p2: (i64, i64),
+        q1: (i64, i64),
+        q2: (i64, i64),
+    ) -> bool {
+        if p1 == p2 || q1 == q2 {
+            return false;
+        }
+        let orientation = |a: (i64, i64), b: (i64, i64), c: (i64, i64)| {
+            ((b.0 - a.0) * (c.1 - a.1) - (b.1 - a.1) * (c.0 - a.0)).signum()
+        };
+        let (o1, o2) = (orientation(p1, p2, q1), orientation(p1, p2, q2));
+        let (o3, o4) = (orientation(q1, q2, p1), orientation(q1, q2, p2));
+        if o1 == 0 && o2 == 0 {
+            let project =
+                |q: (i64, i64)| (q.0 - p1.0) * (p2.0 - p1.0) + (q.1 - p1.1) * (p2.1 - p1.1);
+            let length = project(p2);
+            let (first, second) = (project(q1), project(q2));
+            return first.max(second).min(length) > first.min(second).max(0);
+        }
+        o1 * o2 < 0 && o3 * o4 < 0
+    }
+
+    #[test]
+    fn random_segments_test() {
+        // The small grid produces many shared end points, T-junctions and collinear or parallel segments.
+        let mut state: u64 = 0x2545_F491_4F6C_DD1D;
+        let mut coordinate = || {
+            state ^= state << 13;
+            state ^= state >> 7;
+            state ^= state << 17;
+            (state % 9) as i64
+        };
+        for _ in 0..20000 {
+            let points: Vec<(i64, i64)> = (0..4).map(|_| (coordinate(), coordinate())).collect();
+            let expected = reference_intersection(points[0], points[1], points[2], points[3]);
+            // Scaling with powers of two keeps the coordinates exact.
+            for scale in [1.0_f32, 0.00390625, 4096.0] {
+                let vector =
+                    |point: (i64, i64)| Vec2::new(point.0 as f32 * scale, point.1 as f32 * scale);
+                let line_a = Line::new(vector(points[0]), vector(points[1]));
+                let line_b = Line::new(vector(points[2]), vector(points[3]));
+                assert_eq!(
+                    line_a.intersects_with(&line_b),
+                    expected,
+                    "{points:?} at scale {scale}"
+                );
+                assert_eq!(
+                    line_b.intersects_with(&line_a),
+                    expected,
+                    "{points:?} at scale {scale}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn distance_test() {
+        let line_a = Line::new(Vec2::new(0.0, 0.0), Vec2::new(1.0, 1.0));
+        assert!((line_a.distance_to_point(Vec2::new(1.0, 0.0)) - 0.5_f32.sqrt()).abs() < 0.00001);
+        assert!((line_a.distance_to_point(Vec2::new(-1.0, 0.0)) - 1.0).abs() < 0.00001);
+        assert!((line_a.distance_to_point(Vec2::new(2.0, 2.0)) - 2.0_f32.sqrt()).abs() < 0.00001);
+        let point_line = Line::new(Vec2::new(1.0, 1.0), Vec2::new(1.0, 1.0));
+        assert_eq!(point_line.distance_to_point(Vec2::new(1.0, 2.0)), 1.0);
+    }
+}
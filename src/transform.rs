@@ -0,0 +1,141 @@
+//! Provides a coordinate transform between world coordinates and graph coordinates.
+
+use super::vector::Vec2;
+
+/// A similarity transform that maps world coordinates to the coordinate space of the nav graph.
+/// A world position gets scaled, rotated around the origin, and translated, in that order.
+/// This allows picking nodes with [`crate::a_star::NavGraph::find_nearest_node_with_radius`] in world units.
+#[derive(Debug, Copy, Clone)]
+pub struct Transform2D {
+    translation: Vec2,
+    scale: f32,
+    cos_rotation: f32,
+    sin_rotation: f32,
+}
+
+impl Default for Transform2D {
+    fn default() -> Self {
+        Self::identity()
+    }
+}
+
+impl Transform2D {
+    /// Generates a new transform.
+    ///
+    /// # Parameters
+    /// * **translation:** The offset added in graph space after scaling and rotating.
+    /// * **scale:** The uniform scale factor from world units to graph units. It must be finite and not zero.
+    /// * **rotation:** The counterclockwise rotation angle in radians.
+    ///
+    /// # Panics
+    /// Panics if the scale is zero, infinite or NaN, as the transform could not be inverted.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::transform::Transform2D;
+    /// let transform = Transform2D::new([1.0, 0.0], 0.5, 0.0);
+    /// assert_eq!(transform.world_to_graph([2.0, 2.0]), [2.0, 1.0]);
+    /// ```
+    pub fn new(translation: [f32; 2], scale: f32, rotation: f32) -> Transform2D {
+        assert!(
+            scale.is_finite() && scale != 0.0,
+            "The scale has to be finite and may not be zero."
+        );
+        Transform2D {
+            translation: Vec2::from(translation),
+            scale,
+            cos_rotation: rotation.cos(),
+            sin_rotation: rotation.sin(),
+        }
+    }
+
+    /// Generates the transform that leaves all positions unchanged.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::transform::Transform2D;
+    /// let transform = Transform2D::identity();
+    /// assert_eq!(transform.world_to_graph([2.0, 3.0]), [2.0, 3.0]);
+    /// ```
+    pub fn identity() -> Transform2D {
+        Self::new([0.0, 0.0], 1.0, 0.0)
+    }
+
+    /// Transforms a position from world coordinates into graph coordinates.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::transform::Transform2D;
+    /// let transform = Transform2D::new([0.0, 0.0], 2.0, std::f32::consts::FRAC_PI_2);
+    /// let graph_pos = transform.world_to_graph([1.0, 0.0]);
+    /// assert!((graph_pos[0] - 0.0).abs() < 0.0001 && (graph_pos[1] - 2.0).abs() < 0.0001);
+    /// ```
+    pub fn world_to_graph(&self, position: [f32; 2]) -> [f32; 2] {
+        let scaled = Vec2::from(position);
+        let scaled = Vec2::new(scaled.x * self.scale, scaled.y * self.scale);
+        let rotated = Vec2::new(
+            self.cos_rotation * scaled.x - self.sin_rotation * scaled.y,
+            self.sin_rotation * scaled.x + self.cos_rotation * scaled.y,
+        );
+        (rotated + self.translation).into()
+    }
+
+    /// Transforms a position from graph coordinates back into world coordinates.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::transform::Transform2D;
+    /// let transform = Transform2D::new([1.0, 0.0], 0.5, 0.0);
+    /// assert_eq!(transform.graph_to_world([2.0, 1.0]), [2.0, 2.0]);
+    /// ```
+    pub fn graph_to_world(&self, position: [f32; 2]) -> [f32; 2] {
+        let shifted = Vec2::from(position) - self.translation;
+        let rotated = Vec2::new(
+            self.cos_rotation * shifted.x + self.sin_rotation * shifted.y,
+            -self.sin_rotation * shifted.x + self.cos_rotation * shifted.y,
+        );
+        [rotated.x / self.scale, rotated.y / self.scale]
+    }
+
+    /// Transforms a distance, like a picking radius, from world units into graph units.
+    ///
+    /// # Example
+    /// ```
+    /// use astar_lib::transform::Transform2D;
+    /// let transform = Transform2D::new([1.0, 0.0], 0.5, 0.0);
+    /// assert_eq!(transform.world_to_graph_distance(4.0), 2.0);
+    /// ```
+    pub fn world_to_graph_distance(&self, distance: f32) -> f32 {
+        distance * self.scale.abs()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip_test() {
+        let transform = Transform2D::new([3.0, -2.0], 0.25, 0.7);
+        let world = [12.5, -4.0];
+        let graph = transform.world_to_graph(world);
+        let back = transform.graph_to_world(graph);
+        assert!(Vec2::from(back).approx_eq(&Vec2::from(world), 0.0001));
+
+        let distance = Vec2::from(transform.world_to_graph([0.0, 0.0]))
+            .dist_to(&Vec2::from(transform.world_to_graph([4.0, 0.0])));
+        assert!((distance - transform.world_to_graph_distance(4.0)).abs() < 0.0001);
+    }
+
+    #[test]
+    #[should_panic(expected = "has to be finite")]
+    fn nan_scale_test() {
+        Transform2D::new([0.0, 0.0], f32::NAN, 0.0);
+    }
+
+    #[test]
+    #[should_panic(expected = "has to be finite")]
+    fn infinite_scale_test() {
+        Transform2D::new([0.0, 0.0], f32::INFINITY, 0.0);
+    }
+}
@@ -3,7 +3,7 @@
 use std::ops::{Add, Sub};
 
 /// Contains a two dimensional vector.
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
 pub struct Vec2 {
     pub x: f32,
     pub y: f32,
@@ -74,6 +74,101 @@ impl Vec2 {
         (self.x * self.x + self.y * self.y).sqrt()
     }
 
+    /// Checks if two vectors are equal within a tolerance in each component.
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// let p1 = Vec2::new(1.0, 2.0);
+    /// let p2 = Vec2::new(1.0, 2.000001);
+    /// assert!(p1.approx_eq(&p2, 0.0001));
+    /// ```
+    pub fn approx_eq(&self, other: &Vec2, eps: f32) -> bool {
+        (self.x - other.x).abs() <= eps && (self.y - other.y).abs() <= eps
+    }
+
+    /// Gets a hashable key of the grid cell with the indicated size that this vector is in.
+    /// As floats do not hash reliably, this may be used for spatial deduplication. Note that
+    /// two close vectors may still end up in neighboring cells.
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// let p1 = Vec2::new(1.01, 2.01);
+    /// let p2 = Vec2::new(1.02, 2.02);
+    /// assert_eq!(p1.quantized_key(0.1), p2.quantized_key(0.1));
+    /// ```
+    pub fn quantized_key(&self, cell_size: f32) -> (i64, i64) {
+        (
+            (self.x / cell_size).floor() as i64,
+            (self.y / cell_size).floor() as i64,
+        )
+    }
+
+    /// Interpolates linearly between this vector for a parameter of 0.0 and the other vector for 1.0.
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// let p1 = Vec2::new(0.0, 2.0);
+    /// let p2 = Vec2::new(2.0, 2.0);
+    /// assert_eq!(p1.lerp(&p2, 0.25), Vec2::new(0.5, 2.0));
+    /// ```
+    pub fn lerp(&self, other: &Vec2, lambda: f32) -> Vec2 {
+        Vec2::new(
+            self.x + (other.x - self.x) * lambda,
+            self.y + (other.y - self.y) * lambda,
+        )
+    }
+
+    /// Gets the component-wise minimum of two vectors.
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// let p1 = Vec2::new(1.0, 4.0);
+    /// let p2 = Vec2::new(2.0, 3.0);
+    /// assert_eq!(p1.min(&p2), Vec2::new(1.0, 3.0));
+    /// ```
+    pub fn min(&self, other: &Vec2) -> Vec2 {
+        Vec2::new(self.x.min(other.x), self.y.min(other.y))
+    }
+
+    /// Gets the component-wise maximum of two vectors.
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// let p1 = Vec2::new(1.0, 4.0);
+    /// let p2 = Vec2::new(2.0, 3.0);
+    /// assert_eq!(p1.max(&p2), Vec2::new(2.0, 4.0));
+    /// ```
+    pub fn max(&self, other: &Vec2) -> Vec2 {
+        Vec2::new(self.x.max(other.x), self.y.max(other.y))
+    }
+
+    /// Clamps every component into the range given by the components of the lower and the upper bound,
+    /// which may be used to keep a position inside of a world boundary.
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// let p1 = Vec2::new(-1.0, 0.5);
+    /// let clamped = p1.clamp(&Vec2::new(0.0, 0.0), &Vec2::new(1.0, 1.0));
+    /// assert_eq!(clamped, Vec2::new(0.0, 0.5));
+    /// ```
+    pub fn clamp(&self, lower: &Vec2, upper: &Vec2) -> Vec2 {
+        self.max(lower).min(upper)
+    }
+
+    /// Gets the angle between the directions of this vector and another one in radians, ranging from 0.0 to π.
+    /// The angle is 0.0 if one of the vectors is zero.
+    /// # Example
+    /// ```
+    /// use astar_lib::vector::Vec2;
+    /// let p1 = Vec2::new(1.0, 0.0);
+    /// let p2 = Vec2::new(0.0, 2.0);
+    /// assert_eq!(p1.angle_to(&p2), std::f32::consts::FRAC_PI_2);
+    /// ```
+    pub fn angle_to(&self, other: &Vec2) -> f32 {
+        let cross = self.x * other.y - self.y * other.x;
+        cross.atan2(self.dot(*other)).abs()
+    }
+
     /// Computes the distance to another vector.
     /// # Example
     /// ```
@@ -119,6 +214,18 @@ impl From<Vec2> for [f32; 2] {
     }
 }
 
+impl From<(f32, f32)> for Vec2 {
+    fn from(v: (f32, f32)) -> Vec2 {
+        Vec2 { x: v.0, y: v.1 }
+    }
+}
+
+impl From<Vec2> for (f32, f32) {
+    fn from(v: Vec2) -> (f32, f32) {
+        (v.x, v.y)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -130,4 +237,56 @@ mod tests {
         let dist = vec_a.dist_to(&vec_b);
         assert!((dist - (2.0_f32).sqrt()).abs() < 0.00000000001);
     }
+
+    #[test]
+    fn tuple_test() {
+        let vec_a = Vec2::from((1.5, -2.0));
+        assert_eq!(vec_a, Vec2::new(1.5, -2.0));
+        let tuple: (f32, f32) = vec_a.into();
+        assert_eq!(tuple, (1.5, -2.0));
+        assert_eq!(<[f32; 2]>::from(Vec2::from((0.25, 4.0))), [0.25, 4.0]);
+    }
+
+    #[test]
+    fn approx_eq_test() {
+        let vec_a = Vec2::new(0.55, -0.55);
+        let vec_b = Vec2::new(0.550001, -0.550001);
+        let vec_c = Vec2::new(0.65, -0.55);
+        assert!(vec_a.approx_eq(&vec_b, 0.0001));
+        assert!(!vec_a.approx_eq(&vec_c, 0.0001));
+        assert_eq!(vec_a, Vec2::from([0.55, -0.55]));
+        assert_ne!(vec_a, vec_b);
+
+        assert_eq!(vec_a.quantized_key(0.1), vec_b.quantized_key(0.1));
+        assert_ne!(vec_a.quantized_key(0.1), vec_c.quantized_key(0.1));
+        assert_eq!(Vec2::new(-0.05, 0.05).quantized_key(0.1), (-1, 0));
+    }
+
+    #[test]
+    fn min_max_test() {
+        let vec_a = Vec2::new(-1.0, 2.0);
+        let vec_b = Vec2::new(0.5, -3.0);
+        assert_eq!(vec_a.min(&vec_b), Vec2::new(-1.0, -3.0));
+        assert_eq!(vec_a.max(&vec_b), Vec2::new(0.5, 2.0));
+
+        let lower = Vec2::new(0.0, 0.0);
+        let upper = Vec2::new(1.0, 1.0);
+        assert_eq!(vec_a.clamp(&lower, &upper), Vec2::new(0.0, 1.0));
+        assert_eq!(vec_b.clamp(&lower, &upper), Vec2::new(0.5, 0.0));
+        assert_eq!(
+            Vec2::new(0.25, 0.75).clamp(&lower, &upper),
+            Vec2::new(0.25, 0.75)
+        );
+    }
+
+    #[test]
+    fn angle_test() {
+        let right = Vec2::new(2.0, 0.0);
+        assert_eq!(right.angle_to(&Vec2::new(3.0, 0.0)), 0.0);
+        assert_eq!(right.angle_to(&Vec2::new(-1.0, 0.0)), std::f32::consts::PI);
+        let diagonal = Vec2::new(1.0, -1.0);
+        assert!((right.angle_to(&diagonal) - std::f32::consts::FRAC_PI_4).abs() < 0.00001);
+        assert_eq!(diagonal.angle_to(&right), right.angle_to(&diagonal));
+        assert_eq!(right.angle_to(&Vec2::new(0.0, 0.0)), 0.0);
+    }
 }